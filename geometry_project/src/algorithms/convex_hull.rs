@@ -1,30 +1,33 @@
-use log::logger;
-
 use crate::{
-    display::{scene::Scene, scene_proxy::ISceneProxy},
-    entities::{point2d::Point2d, polygon2d::Polygon2D, vect2d::Vector2D},
+    algorithms::tracer::{NoTracer, Tracer},
+    display::{rgb::RGB, scene::Scene, style::PointStyle},
+    entities::{line2d::Line2D, point2d::Point2d, polygon2d::Polygon2D, vect2d::Vector2D},
     extensions::vec_extensions::{VecExtensions, VecPointExtesions},
     numerics::floating_comparisons::{approx_greater, approx_less},
-    scene_logger::scene_logger::SceneLogger,
 };
 
-use std::sync::OnceLock;
+//Input points, the chain built so far and the point the step is about
+fn hull_scene(points: &[Point2d], chain: &[Point2d], focus: Option<Point2d>) -> Scene {
+    let mut scene: Scene = Scene::new();
 
-static LOGGER: OnceLock<SceneLogger> = OnceLock::new();
+    scene
+        .layer("points")
+        .push_points_styled(points.iter().copied(), PointStyle::new(RGB::white(), 3));
 
-fn get_logger() -> &'static SceneLogger {
-    LOGGER.get_or_init(|| SceneLogger::new("Algorithms", true))
-}
+    scene
+        .layer("chain")
+        .push_lines_color(chain.windows(2).map(|w| (Line2D::new(w[0], w[1]), RGB::red())));
 
-fn log_scene<T>(proxy: &T)
-where
-    T: ISceneProxy,
-{
-    #[cfg(feature = "Algorithms")]
-    {
-        let logger = get_logger();
-        logger.log_scene_proxy(proxy);
+    if let Some(point) = focus {
+        scene.layer("focus").push_point_styled(point, PointStyle::new(RGB::green(), 5));
+        scene.push_note(format!("focus {}", point));
     }
+
+    //the chain is the stack of the monotone chain algorithm, top last
+    scene.push_note(format!("stack ({})", chain.len()));
+    scene.notes.extend(chain.iter().rev().map(|p| format!("  {}", p)));
+
+    scene
 }
 
 pub fn right_turn(a: Point2d, b: Point2d, c: Point2d) -> bool {
@@ -35,6 +38,12 @@ pub fn right_turn(a: Point2d, b: Point2d, c: Point2d) -> bool {
 }
 
 pub fn convex_hull(points: &mut Vec<Point2d>) -> Option<Polygon2D> {
+    convex_hull_traced(points, &mut NoTracer)
+}
+
+//Andrew's monotone chain, steps are "push" when a point is added to a chain,
+//"pop" when a left turn removes one and "upper"/"lower" when a chain is done
+pub fn convex_hull_traced<T: Tracer>(points: &mut Vec<Point2d>, tracer: &mut T) -> Option<Polygon2D> {
     if points.len() < 3 {
         return None;
     }
@@ -48,6 +57,8 @@ pub fn convex_hull(points: &mut Vec<Point2d>) -> Option<Polygon2D> {
     for i in 2..points.len() {
         l_upper.push(points[i]);
 
+        tracer.trace("push", || hull_scene(points, &l_upper, Some(points[i])));
+
         while l_upper.len() > 2 {
             if right_turn(
                 l_upper.from_last(2),
@@ -57,22 +68,22 @@ pub fn convex_hull(points: &mut Vec<Point2d>) -> Option<Polygon2D> {
                 break;
             }
 
-            l_upper.remove(l_upper.len() - 2);
+            let removed: Point2d = l_upper.remove(l_upper.len() - 2);
+
+            tracer.trace("pop", || hull_scene(points, &l_upper, Some(removed)));
         }
     }
 
-    log_scene(&l_upper);
+    tracer.trace("upper", || hull_scene(points, &l_upper, None));
 
     let mut l_lower: Vec<Point2d> = Vec::new();
     l_lower.push(points.from_last(0));
     l_lower.push(points.from_last(1));
 
-    log_scene(&l_lower);
-
     for i in (0..points.len() - 3).rev() {
         l_lower.push(points[i]);
 
-        log_scene(&l_lower);
+        tracer.trace("push", || hull_scene(points, &l_lower, Some(points[i])));
 
         while l_lower.len() > 2 {
             if right_turn(
@@ -83,14 +94,16 @@ pub fn convex_hull(points: &mut Vec<Point2d>) -> Option<Polygon2D> {
                 break;
             }
 
-            l_lower.remove(l_lower.len() - 2);
+            let removed: Point2d = l_lower.remove(l_lower.len() - 2);
+
+            tracer.trace("pop", || hull_scene(points, &l_lower, Some(removed)));
         }
     }
 
     l_lower.remove(l_lower.len() - 1);
     l_lower.remove(0);
 
-    log_scene(&l_lower);
+    tracer.trace("lower", || hull_scene(points, &l_lower, None));
 
     l_upper.append(&mut l_lower);
 
@@ -101,6 +114,7 @@ pub fn convex_hull(points: &mut Vec<Point2d>) -> Option<Polygon2D> {
 mod tests {
 
     use super::*;
+    use crate::algorithms::tracer::SceneRecorder;
 
     #[test]
     fn test_right_turn_1() {
@@ -164,4 +178,25 @@ mod tests {
         let hull = convex_hull(&mut points);
         assert!(matches!(hull, Some(_)));
     }
+
+    #[test]
+    fn test_traced_steps() {
+        let mut points: Vec<Point2d> = [
+            Point2d { x: 0f32, y: 0f32 },
+            Point2d { x: 0f32, y: 10f32 },
+            Point2d { x: 10f32, y: 10f32 },
+            Point2d { x: 10f32, y: 0f32 },
+            Point2d { x: 5f32, y: 5f32 },
+        ]
+        .into();
+
+        let mut recorder: SceneRecorder = SceneRecorder::new();
+        let hull = convex_hull_traced(&mut points, &mut recorder);
+        let labels: Vec<&str> = recorder.labels();
+
+        assert!(hull.is_some());
+        assert!(labels.contains(&"push"));
+        assert!(labels.contains(&"pop"));
+        assert_eq!(Some(&"lower"), labels.last());
+    }
 }
@@ -1,10 +1,11 @@
-use std::{collections::{BTreeMap, BTreeSet}, f32::consts::E};
+use std::{cell::Cell, cmp::Ordering, collections::{BTreeMap, BTreeSet}, ops::Bound, rc::Rc};
 
-use crate::entities::{lexicographic2d::{LexicographicLine2d, LexicographicPoint2d}, line2d::Line2D, point2d::Point2d};
+use crate::algorithms::tracer::{NoTracer, Tracer};
+use crate::display::{rgb::RGB, scene::Scene, style::PointStyle};
+use crate::entities::{lexicographic2d::{LexicographicLine2d, LexicographicPoint2d}, line2d::Line2D, point2d::Point2d, rectangle2d::Rectangle2D, vect2d::Vector2D};
+use crate::numerics::approx_equatable::ApproxEquals;
 
-use binary_search_tree::BinarySearchTree;
-
-pub fn naive_line_intersection(lines: &Vec<Line2D>, epsilon: f32) -> Vec<Point2d> {
+pub fn naive_line_intersection(lines: &[Line2D], epsilon: f32) -> Vec<Point2d> {
     if lines.len() <= 1 {
         return Vec::new();
     }
@@ -22,7 +23,7 @@ pub fn naive_line_intersection(lines: &Vec<Line2D>, epsilon: f32) -> Vec<Point2d
     points
 }
 
-pub fn naive_line_intersection_with_lines(lines: &Vec<Line2D>, epsilon: f32) -> Vec<(Point2d, Line2D, Line2D)>
+pub fn naive_line_intersection_with_lines(lines: &[Line2D], epsilon: f32) -> Vec<(Point2d, Line2D, Line2D)>
 {
     if lines.len() <= 1 {
         return Vec::new();
@@ -83,7 +84,7 @@ impl Eq for Event
 impl PartialOrd for Event
 {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.point.partial_cmp(&other.point)
+        Some(self.cmp(other))
     }
 }
 
@@ -95,8 +96,12 @@ impl Ord for Event
     }
 }
 
-//wrapper around Lexicographic line, assume all lines are finite here...
-//we only use the start point for ordering
+//How close, relative to a segment's length, a point has to be to count as on
+//the segment. Intersection points are computed, so they are rarely exactly on
+//either segment
+const ON_SEGMENT_TOLERANCE: f32 = 0.00001;
+
+//A segment normalized so start is the end point the sweep reaches first
 #[repr(transparent)]
 #[derive(Debug, Clone, Copy)]
 struct SweepSegment(LexicographicLine2d);
@@ -108,188 +113,455 @@ impl SweepSegment
         Self(LexicographicLine2d::new_normalized(line))
     }
 
-    pub fn event_line(event_point: LexicographicPoint2d) -> Self
+    fn is_horizontal(&self) -> bool
     {
-        Self(LexicographicLine2d::upper_lower(event_point, event_point))
+        self.0.0.start.y == self.0.0.end.y
     }
-}
 
-impl PartialEq for SweepSegment
-{
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+    //Where the segment crosses the sweep line at y. A horizontal segment lies
+    //on the sweep line, it is taken at the event point as far as it reaches
+    fn x_at(&self, y: f32, event_x: f32) -> f32
+    {
+        let line: Line2D = self.0.line();
+
+        match self.is_horizontal()
+        {
+            true => event_x.clamp(line.start.x, line.end.x),
+            false => line.start.x + (y - line.start.y) * (line.end.x - line.start.x) / (line.end.y - line.start.y),
+        }
+    }
+
+    //Change of x per unit of y, the order of segments just past a point they
+    //share. Horizontal segments come last
+    fn slope(&self) -> f32
+    {
+        let line: Line2D = self.0.line();
+
+        match self.is_horizontal()
+        {
+            true => f32::INFINITY,
+            false => (line.end.x - line.start.x) / (line.end.y - line.start.y),
+        }
+    }
+
+    fn tolerance(&self) -> f32
+    {
+        ON_SEGMENT_TOLERANCE * self.0.line().len()
+    }
+
+    //Where on the segment the point is, 0 at start and 1 at end, None when it
+    //is not on the segment
+    fn position_of(&self, point: Point2d) -> Option<f32>
+    {
+        let line: Line2D = self.0.line();
+        let direction: Vector2D = line.end - line.start;
+        let offset: Vector2D = point - line.start;
+        let len: f32 = direction.len();
+
+        //the status probe, a segment of no length at the event
+        if len == 0f32
+        {
+            return (offset.len() == 0f32).then_some(0f32);
+        }
+
+        let t: f32 = (offset.x * direction.x + offset.y * direction.y) / (len * len);
+        let distance: f32 = offset.cross(&direction).abs() / len;
+
+        match distance <= self.tolerance() && (-ON_SEGMENT_TOLERANCE..=1f32 + ON_SEGMENT_TOLERANCE).contains(&t)
+        {
+            true => Some(t),
+            false => None,
+        }
     }
 }
 
-impl PartialOrd for SweepSegment
+//Where the status is ordered, shared by all of its keys. While the segments
+//through the event point are taken out they are ordered as just before it,
+//when they are put back as just past it
+#[derive(Debug, Clone, Copy)]
+struct SweepPosition
 {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.0.start().partial_cmp(&other.0.start())
-    }
+    point: Point2d,
+    past: bool,
 }
 
-impl Eq for SweepSegment
+//A status entry, ordered by where its segment crosses the sweep line. Status
+//segments do not cross between events, so moving the sweep line keeps the set
+//ordered. Segments meeting at a point are ordered by slope
+#[derive(Debug, Clone)]
+struct StatusKey
 {
+    segment: SweepSegment,
+    //tells copies of a segment apart
+    id: usize,
+    sweep: Rc<Cell<SweepPosition>>,
 }
 
-impl Ord for SweepSegment
+impl StatusKey
 {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap_or(std::cmp::Ordering::Equal) //assume all valid numbers
+    //Sorts where a segment through the point would, after any of them when
+    //ordering as past the point
+    fn probe(point: Point2d, sweep: &Rc<Cell<SweepPosition>>) -> Self
+    {
+        StatusKey { segment: SweepSegment::new(Line2D::new(point, point)), id: usize::MAX, sweep: Rc::clone(sweep) }
     }
 }
 
-fn segments_containing_point(tree: &BinarySearchTree<SweepSegment>, point: LexicographicPoint2d, epsilon: f32) -> (Vec<SweepSegment>, Vec<SweepSegment>)
+impl StatusKey
 {
-    let mut contains: Vec<SweepSegment> = Vec::new();
-    let mut lower: Vec<SweepSegment> = Vec::new();
-    let mut start_containing: bool = false;
-    for segment in tree.inorder()
+    //Segments through the event point cross the sweep line right at it, the
+    //point is computed and rarely exactly on them
+    fn crossing(&self, sweep: SweepPosition) -> f32
     {
-        if segment.0.end().approx_equals(&point.0, epsilon)
+        match self.segment.position_of(sweep.point)
         {
-            start_containing = true;
-            lower.push(*segment);
-            continue;
+            Some(_) => sweep.point.x,
+            None => self.segment.x_at(sweep.point.y, sweep.point.x),
         }
+    }
+}
 
-        if segment.0.line().intersects_point(point.0, epsilon)
+impl Ord for StatusKey
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        let sweep: SweepPosition = self.sweep.get();
+        let (x, other_x) = (self.crossing(sweep), other.crossing(sweep));
+
+        if x != other_x
         {
-            start_containing = true;
-            contains.push(*segment);
+            return x.total_cmp(&other_x);
         }
-        else 
+
+        //meeting on the sweep line, past the meeting point a larger slope is
+        //further right and before it further left
+        let past: bool = match x.total_cmp(&sweep.point.x)
         {
-            if start_containing
-            {
-                break;
-            }
-        }
+            Ordering::Equal => sweep.past,
+            Ordering::Less => true,
+            Ordering::Greater => false,
+        };
+
+        let by_slope: Ordering = match past
+        {
+            true => self.segment.slope().total_cmp(&other.segment.slope()),
+            false => other.segment.slope().total_cmp(&self.segment.slope()),
+        };
+
+        by_slope.then(self.id.cmp(&other.id))
     }
+}
 
-    (contains, lower)
+impl PartialOrd for StatusKey
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
-fn find_next_intersection_point(left_line: &SweepSegment, right_line: &SweepSegment, point: LexicographicPoint2d, event_queue: &mut BTreeMap<Event, Vec<SweepSegment>>, epsilon: f32)
+impl PartialEq for StatusKey
 {
-    if let Some(p) = left_line.0.0.intersect(&right_line.0.0, epsilon)
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for StatusKey
+{
+}
+
+//Longer queues and status lists are cut off in the step notes
+const SWEEP_NOTE_LIMIT: usize = 12;
+
+//All segments, the sweep line through the event, the status segments and the
+//segments and point the step is about
+#[allow(clippy::mutable_key_type)] //the shared sweep position only changes between status updates
+fn sweep_scene(lines: &[Line2D], queue: &BTreeMap<Event, Vec<SweepSegment>>, status: &BTreeSet<StatusKey>, event: Point2d, focus: &[Line2D], found: Option<Point2d>) -> Scene
+{
+    let mut scene: Scene = Scene::new();
+
+    scene.layer("segments").push_lines_color(lines.iter().map(|l| (*l, RGB::new(90, 90, 90))));
+
+    if let Some(bounds) = Rectangle2D::from_points(lines.iter().flat_map(|l| [l.start, l.end]))
     {
-        if LexicographicPoint2d(p) >= point
-        {
-            return;
-        }
+        scene.layer("sweep").push_line_colored(Line2D::new_flat(bounds.min.x, event.y, bounds.max.x, event.y), RGB::blue());
+    }
 
-        let event = Event::new(LexicographicPoint2d(p), EventType::Intersection);
+    scene.layer("status").push_lines_color(status.iter().map(|k| (k.segment.0.line(), RGB::white())));
+    scene.layer("focus").push_lines_color(focus.iter().map(|l| (*l, RGB::green())));
+    scene.layer("event").push_point_styled(event, PointStyle::new(RGB::green(), 5));
 
-        if event_queue.contains_key(&event)
-        {
-            return;
-        }
+    if let Some(point) = found
+    {
+        scene.layer("intersections").push_point_styled(point, PointStyle::new(RGB::red(), 5));
+    }
+
+    scene.push_note(format!("event {}", event));
 
-        event_queue.insert(event, Vec::new());
+    scene.push_note(format!("queue ({})", queue.len()));
+    scene.notes.extend(queue.keys().take(SWEEP_NOTE_LIMIT).map(|e| format!("  {:?} {}", e.event, e.point.0)));
+
+    //left to right, the order of the status
+    scene.push_note(format!("status ({})", status.len()));
+    scene.notes.extend(status.iter().take(SWEEP_NOTE_LIMIT).map(|k| format!("  {} - {}", k.segment.0.line().start, k.segment.0.line().end)));
+
+    scene
+}
+
+//Queues where the two neighbours cross when that is past the current event.
+//A point close to an end point or to a queued event is taken to be that one,
+//so one meeting point does not turn into several events
+fn find_next_intersection_point(left_line: &SweepSegment, right_line: &SweepSegment, point: LexicographicPoint2d, event_queue: &mut BTreeMap<Event, Vec<SweepSegment>>, epsilon: f32) -> Option<Point2d>
+{
+    let mut p: Point2d = left_line.0.line().intersect(&right_line.0.line(), epsilon)?;
+    let reach: f32 = left_line.tolerance().max(right_line.tolerance());
+
+    let ends: [Point2d; 4] = [left_line.0.0.start, left_line.0.0.end, right_line.0.0.start, right_line.0.0.end];
+    if let Some(end) = ends.iter().find(|e| (**e - p).len() <= reach)
+    {
+        p = *end;
     }
+
+    if LexicographicPoint2d(p) <= point || (p - point.0).len() <= reach
+    {
+        return None;
+    }
+
+    let low: Event = Event::new(LexicographicPoint2d::new(f32::NEG_INFINITY, p.y - reach), EventType::Intersection);
+    let high: Event = Event::new(LexicographicPoint2d::new(f32::INFINITY, p.y + reach), EventType::Intersection);
+
+    if event_queue.range(low..=high).any(|(e, _)| (e.point.0 - p).len() <= reach)
+    {
+        return None;
+    }
+
+    event_queue.insert(Event::new(LexicographicPoint2d(p), EventType::Intersection), Vec::new());
+    Some(p)
 }
 
-pub fn line_sweep_intersection(lines: &Vec<Line2D>, epsilon: f32) -> Option<Vec<Point2d>>
+pub fn line_sweep_intersection(lines: &[Line2D], epsilon: f32) -> Option<Vec<Point2d>>
 {
-    //TODO: This is not exactly ideal... this requires us to store a vec for each key
-    //even if that key is a lower point
-    //todo.. may be better for the segment_status to be a ref struct wrapping the index
-    //of the list
+    line_sweep_intersection_traced(lines, epsilon, &mut NoTracer)
+}
+
+//Bentley-Ottmann, every point where segments meet is reported once, in sweep
+//order from the lowest y. None when a segment is not finite. Segments of no
+//length are skipped, the same as naive_line_intersection does.
+//Steps are "event" for every point taken off the queue, "intersection" when
+//several segments meet at it, "swap" when segments crossing at the event
+//change order, "remove"/"insert" for status updates and "queue" when a
+//neighbour check finds a new intersection event
+#[allow(clippy::mutable_key_type)] //the shared sweep position only changes between status updates
+pub fn line_sweep_intersection_traced<T: Tracer>(lines: &[Line2D], epsilon: f32, tracer: &mut T) -> Option<Vec<Point2d>>
+{
+    //Upper events keep the segments starting there, every other event an empty vec
     let mut event_queue: BTreeMap<Event, Vec<SweepSegment>> = BTreeMap::new();
 
-    for i in 0..lines.len()
+    for line in lines
     {
-        if !lines[i].is_finite()
+        if !line.is_finite()
         {
             return None; //all lines must be finite
-        }   
+        }
+
+        if line.len().approx_equals(&0f32, epsilon)
+        {
+            continue;
+        }
 
         //normalize line, guarnetee start == upper
-        let segment: SweepSegment = SweepSegment::new(lines[i]);
+        let segment: SweepSegment = SweepSegment::new(*line);
 
         let upper = Event::new(segment.0.start(), EventType::Upper);
-        let lower= Event::new(segment.0.end(), EventType::Lower);
-
-        if let Some(s) = event_queue.get_mut(&upper)
-        {
-            s.push(segment);
-        }
-        else {
-            event_queue.insert(upper, vec![segment]);
-        }
+        let lower = Event::new(segment.0.end(), EventType::Lower);
 
-        if !event_queue.contains_key(&lower)
-        {
-            event_queue.insert(lower, Vec::new());
-        }
+        event_queue.entry(upper).or_default().push(segment);
+        event_queue.entry(lower).or_default();
     }
-    
-    let mut segment_status: BinarySearchTree<SweepSegment> = BinarySearchTree::new();
+
+    //The segments the sweep line crosses, left to right
+    let sweep: Rc<Cell<SweepPosition>> = Rc::new(Cell::new(SweepPosition { point: Point2d::origin(), past: false }));
+    let mut segment_status: BTreeSet<StatusKey> = BTreeSet::new();
+    let mut next_id: usize = 0;
     let mut intersections: Vec<Point2d> = Vec::new();
 
-    while !event_queue.is_empty()
+    while let Some((next_event, upper_segments)) = event_queue.pop_first()
     {
-        let next_event = event_queue.pop_first().unwrap(); //safe checked above
-        let event_point = next_event.0.point;
-
-        let upper_segments: &Vec<SweepSegment> = &next_event.1;
-        let (containing_segments, lower) = segments_containing_point(&segment_status, event_point, epsilon);
+        let event_point: LexicographicPoint2d = next_event.point;
+        sweep.set(SweepPosition { point: event_point.0, past: false });
 
-        let lower_containing: Vec<&SweepSegment> = lower.iter()
-        .filter(|x| containing_segments.contains(x)).collect();
+        tracer.trace("event", || sweep_scene(lines, &event_queue, &segment_status, event_point.0, &[], None));
 
-        let upper_containing: Vec<SweepSegment> = upper_segments.clone().into_iter()
-        .filter(|x| containing_segments.contains(x)).collect();
+        //segments in the status that end at the event or pass through it, they
+        //are next to each other around where a segment through the event sorts
+        let probe: StatusKey = StatusKey::probe(event_point.0, &sweep);
+        let on_event = |k: &&StatusKey| k.segment.position_of(event_point.0).is_some();
 
-        let upper_containing_lower: Vec<SweepSegment> = upper_containing.clone().into_iter()
-        .filter(|x| lower.contains(x)).collect();
+        let (lower, containing): (Vec<StatusKey>, Vec<StatusKey>) = segment_status
+            .range((Bound::Unbounded, Bound::Excluded(&probe)))
+            .rev()
+            .take_while(on_event)
+            .chain(segment_status.range((Bound::Excluded(&probe), Bound::Unbounded)).take_while(on_event))
+            .cloned()
+            .partition(|k| k.segment.position_of(event_point.0).is_some_and(|t| t >= 1f32 - ON_SEGMENT_TOLERANCE));
 
-
-        if upper_containing_lower.len() > 1
+        if upper_segments.len() + lower.len() + containing.len() > 1
         {
-            intersections.push(next_event.0.point.0);
+            intersections.push(event_point.0);
+
+            tracer.trace("intersection", || {
+                let focus: Vec<Line2D> = upper_segments.iter().map(|s| s.0.line()).chain(lower.iter().chain(&containing).map(|k| k.segment.0.line())).collect();
+                sweep_scene(lines, &event_queue, &segment_status, event_point.0, &focus, Some(event_point.0))
+            });
         }
 
-        for seg in lower_containing
+        if matches!(next_event.event, EventType::Intersection) && !containing.is_empty()
         {
-            segment_status.remove(&seg);
+            tracer.trace("swap", || {
+                let focus: Vec<Line2D> = containing.iter().map(|k| k.segment.0.line()).collect();
+                sweep_scene(lines, &event_queue, &segment_status, event_point.0, &focus, Some(event_point.0))
+            });
         }
 
-        let upper_len = upper_containing.len();
-        for seg in upper_containing.iter()        
+        //segments passing through are taken out and put back in their order past the event
+        for key in &containing
         {
-            segment_status.insert(seg.clone());
+            segment_status.remove(key);
         }
 
-        if upper_len == 0
+        for key in &lower
         {
-            let event_line = SweepSegment::event_line(event_point);
+            segment_status.remove(key);
 
+            tracer.trace("remove", || sweep_scene(lines, &event_queue, &segment_status, event_point.0, &[key.segment.0.line()], None));
+        }
 
-            let left_line = match segment_status.predecessor(&event_line) {Some(v) => v, None => {continue;}};
-            let right_line = match segment_status.successor(&event_line) {Some(v) => v, None => {continue;}};
+        sweep.set(SweepPosition { point: event_point.0, past: true });
 
-            find_next_intersection_point(left_line, right_line, event_point, &mut event_queue, epsilon);
+        let mut inserted: Vec<StatusKey> = containing;
+        for segment in upper_segments
+        {
+            inserted.push(StatusKey { segment, id: next_id, sweep: Rc::clone(&sweep) });
+            next_id += 1;
         }
-        else 
+        inserted.sort();
+
+        for key in &inserted
         {
-            let left_most = upper_containing.iter().min().unwrap(); //upper_len != 0
-            let left_neighbour = match segment_status.predecessor(left_most) {Some(v) => v, None => {continue;}};
-            find_next_intersection_point(left_neighbour, left_most, event_point, &mut event_queue, epsilon);
+            segment_status.insert(key.clone());
 
-            let right_most = upper_containing.iter().max().unwrap(); //upper_len != 0
-            let right_neighbour = match segment_status.successor(right_most) {Some(v) => v, None => {continue;}};
-            find_next_intersection_point(right_most, right_neighbour, event_point, &mut event_queue, epsilon);
+            tracer.trace("insert", || sweep_scene(lines, &event_queue, &segment_status, event_point.0, &[key.segment.0.line()], None));
         }
 
+        //the neighbours around the gap left by the event, or around the inserted segments
+        let (first, last): (&StatusKey, &StatusKey) = match (inserted.first(), inserted.last())
+        {
+            (Some(first), Some(last)) => (first, last),
+            _ => (&probe, &probe),
+        };
+
+        let left: Option<&StatusKey> = segment_status.range((Bound::Unbounded, Bound::Excluded(first))).next_back();
+        let right: Option<&StatusKey> = segment_status.range((Bound::Excluded(last), Bound::Unbounded)).next();
+
+        let pairs: [(Option<&StatusKey>, Option<&StatusKey>); 2] = match inserted.is_empty()
+        {
+            true => [(left, right), (None, None)],
+            false => [(left, Some(first)), (Some(last), right)],
+        };
+
+        for (left_key, right_key) in pairs
+        {
+            let (left_line, right_line) = match (left_key, right_key)
+            {
+                (Some(l), Some(r)) => (&l.segment, &r.segment),
+                _ => continue,
+            };
+
+            if let Some(p) = find_next_intersection_point(left_line, right_line, event_point, &mut event_queue, epsilon)
+            {
+                tracer.trace("queue", || sweep_scene(lines, &event_queue, &segment_status, event_point.0, &[left_line.0.line(), right_line.0.line()], Some(p)));
+            }
+        }
     }
-    return None;
+
+    Some(intersections)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::algorithms::tracer::SceneRecorder;
+
+    //Each meeting point once, in sweep order, so it compares with the sweep
+    fn distinct(mut points: Vec<Point2d>) -> Vec<Point2d> {
+        points.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+        points.dedup_by(|a, b| a.approx_equals(b, 0.0001f32));
+        points
+    }
 
+    fn assert_matches_naive(lines: &[Line2D]) {
+        let sweep: Vec<Point2d> = line_sweep_intersection(lines, f32::EPSILON).unwrap();
+        let naive: Vec<Point2d> = distinct(naive_line_intersection(lines, f32::EPSILON));
 
-}
\ No newline at end of file
+        assert_eq!(naive.len(), sweep.len(), "sweep {:?} naive {:?}", sweep, naive);
+        assert!(naive.iter().zip(&sweep).all(|(a, b)| a.approx_equals(b, 0.0001f32)), "sweep {:?} naive {:?}", sweep, naive);
+    }
+
+    #[test]
+    fn test_cross() {
+        let lines: Vec<Line2D> = vec![Line2D::new_flat(0f32, 0f32, 4f32, 4f32), Line2D::new_flat(0f32, 4f32, 4f32, 0f32)];
+
+        assert_eq!(Some(vec![Point2d::new(2f32, 2f32)]), line_sweep_intersection(&lines, f32::EPSILON));
+        assert_matches_naive(&lines);
+    }
+
+    #[test]
+    fn test_shared_endpoints() {
+        //a V, a T and a segment starting on the V's tip
+        let lines: Vec<Line2D> = vec![
+            Line2D::new_flat(0f32, 4f32, 2f32, 0f32),
+            Line2D::new_flat(2f32, 0f32, 4f32, 4f32),
+            Line2D::new_flat(2f32, 0f32, 2f32, -3f32),
+            Line2D::new_flat(0f32, 6f32, 4f32, 6f32),
+            Line2D::new_flat(2f32, 6f32, 2f32, 8f32),
+        ];
+
+        assert_eq!(Some(vec![Point2d::new(2f32, 0f32), Point2d::new(2f32, 6f32)]), line_sweep_intersection(&lines, f32::EPSILON));
+        assert_matches_naive(&lines);
+    }
+
+    #[test]
+    fn test_vertical_and_horizontal() {
+        let lines: Vec<Line2D> = vec![
+            Line2D::new_flat(1f32, -2f32, 1f32, 5f32),
+            Line2D::new_flat(3f32, -2f32, 3f32, 5f32),
+            Line2D::new_flat(-1f32, 0f32, 5f32, 0f32),
+            Line2D::new_flat(0f32, 4f32, 4f32, 1f32),
+        ];
+
+        assert_matches_naive(&lines);
+        assert_eq!(4, line_sweep_intersection(&lines, f32::EPSILON).unwrap().len());
+    }
+
+    #[test]
+    fn test_several_crossings() {
+        //a star of long segments and a grid of short ones
+        let mut lines: Vec<Line2D> = (0..6)
+            .map(|i| Line2D::new_flat(i as f32 * 3f32, 0f32, 20f32 - (i * i) as f32, 20f32))
+            .collect();
+        lines.extend((0..5).map(|i| Line2D::new_flat(-1f32, 2f32 + i as f32 * 3.5f32, 17f32, 1f32 + i as f32 * 4f32)));
+
+        assert_matches_naive(&lines);
+    }
+
+    #[test]
+    fn test_not_finite_and_traced() {
+        assert!(line_sweep_intersection(&[Line2D::new_flat(0f32, 0f32, f32::NAN, 1f32)], f32::EPSILON).is_none());
+
+        let mut recorder: SceneRecorder = SceneRecorder::new();
+        let lines: Vec<Line2D> = vec![Line2D::new_flat(0f32, 0f32, 4f32, 4f32), Line2D::new_flat(0f32, 4f32, 4f32, 0f32)];
+        line_sweep_intersection_traced(&lines, f32::EPSILON, &mut recorder);
+
+        assert!(recorder.labels().contains(&"queue"));
+        assert_eq!(1, recorder.labels().iter().filter(|l| **l == "intersection").count());
+    }
+}
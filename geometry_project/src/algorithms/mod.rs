@@ -3,3 +3,5 @@ pub mod line_intersection;
 pub mod mixed_increment;
 pub mod permutation;
 pub mod random_geometry;
+pub mod tracer;
+pub mod triangulation;
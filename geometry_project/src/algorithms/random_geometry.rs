@@ -1,18 +1,39 @@
+use std::{cell::RefCell, ops::Range};
+
 use crate::entities::{line2d::Line2D, point2d::Point2d, rectangle2d::Rectangle2D};
-use rand::random_range;
+use rand::{
+    distr::uniform::SampleUniform,
+    rngs::StdRng,
+    Rng, SeedableRng,
+};
+
+thread_local! {
+    //Every random entity comes from here so a run can be repeated from its seed
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_os_rng());
+}
 
 pub struct Random2D {}
 
 impl Random2D {
-    
+    pub fn seed(seed: u64) {
+        RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+    }
+
+    pub fn random_range<T>(range: Range<T>) -> T
+    where
+        T: SampleUniform + PartialOrd,
+    {
+        RNG.with(|rng| rng.borrow_mut().random_range(range))
+    }
+
     //Get random point within rectangle
     pub fn random_point(range: &Rectangle2D) -> Point2d {
         if range.is_nan() {
             return Point2d::nan();
         }
 
-        let x = random_range(range.min.x..range.max.x);
-        let y = random_range(range.min.y..range.max.y);
+        let x = Random2D::random_range(range.min.x..range.max.x);
+        let y = Random2D::random_range(range.min.y..range.max.y);
         Point2d { x, y }
     }
 
@@ -21,8 +42,8 @@ impl Random2D {
             return Point2d::nan();
         }
 
-        let x = random_range(range.min.x..range.max.x).round();
-        let y = random_range(range.min.y..range.max.y).round();
+        let x = Random2D::random_range(range.min.x..range.max.x).round();
+        let y = Random2D::random_range(range.min.y..range.max.y).round();
 
         Point2d { x, y }
     }
@@ -107,3 +128,21 @@ impl<T: Sized> Iterator for RandomEntitiesIter<T> {
         Some((self.generator)(&self.bounds))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_repeats_points() {
+        let range: Rectangle2D = Rectangle2D::new_width_height(100f32, 100f32);
+
+        Random2D::seed(7);
+        let first: Vec<Point2d> = Random2D::random_points(range, 5).into_iter().collect();
+
+        Random2D::seed(7);
+        let second: Vec<Point2d> = Random2D::random_points(range, 5).into_iter().collect();
+
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,112 @@
+use std::panic::Location;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{display::scene::Scene, scene_logger::scene_logger::SceneLogger};
+
+//Receives labelled snapshots while an algorithm runs. Algorithms take a tracer
+//by generic parameter, so with NoTracer every trace call and the scene it
+//would have built compile away
+pub trait Tracer {
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    fn record(&mut self, step: &str, location: &'static Location<'static>, scene: Scene);
+
+    //The scene is only built when the tracer is enabled
+    #[track_caller]
+    fn trace<F>(&mut self, step: &str, scene: F)
+    where
+        F: FnOnce() -> Scene,
+        Self: Sized,
+    {
+        if self.is_enabled() {
+            self.record(step, Location::caller(), scene());
+        }
+    }
+}
+
+pub struct NoTracer;
+
+impl Tracer for NoTracer {
+    #[inline(always)]
+    fn is_enabled(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn record(&mut self, _step: &str, _location: &'static Location<'static>, _scene: Scene) {}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceStep {
+    pub label: String,
+    pub file: String,
+    pub line: u32,
+    pub scene: Scene,
+}
+
+//Keeps every step in memory, used by tests and the step debugger
+#[derive(Debug, Clone, Default)]
+pub struct SceneRecorder {
+    pub steps: Vec<TraceStep>,
+}
+
+impl SceneRecorder {
+    pub fn new() -> Self {
+        SceneRecorder { steps: Vec::new() }
+    }
+
+    pub fn labels(&self) -> Vec<&str> {
+        self.steps.iter().map(|s| s.label.as_str()).collect()
+    }
+}
+
+impl Tracer for SceneRecorder {
+    fn record(&mut self, step: &str, location: &'static Location<'static>, scene: Scene) {
+        self.steps.push(TraceStep {
+            label: step.to_string(),
+            file: location.file().to_string(),
+            line: location.line(),
+            scene,
+        });
+    }
+}
+
+//Writes every step to the log so it can be replayed later
+impl Tracer for SceneLogger {
+    fn is_enabled(&self) -> bool {
+        SceneLogger::is_enabled(self)
+    }
+
+    fn record(&mut self, step: &str, location: &'static Location<'static>, scene: Scene) {
+        self.log_step(step, scene, location);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_tracer_does_not_build_scene() {
+        let mut built: bool = false;
+
+        NoTracer.trace("step", || {
+            built = true;
+            Scene::new()
+        });
+
+        assert!(!built);
+    }
+
+    #[test]
+    fn test_recorder_keeps_location() {
+        let mut recorder: SceneRecorder = SceneRecorder::new();
+        recorder.trace("step", Scene::new);
+
+        assert_eq!(vec!["step"], recorder.labels());
+        assert!(recorder.steps[0].file.ends_with("tracer.rs"));
+    }
+}
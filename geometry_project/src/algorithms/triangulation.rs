@@ -0,0 +1,166 @@
+use crate::{
+    algorithms::tracer::{NoTracer, Tracer},
+    display::{rgb::RGB, scene::Scene, style::PointStyle},
+    entities::{line2d::Line2D, point2d::Point2d, polygon2d::Polygon2D},
+    numerics::floating_comparisons::{approx_equal, approx_equal_greater, approx_greater},
+};
+
+const TURN_EPSILON: f32 = 0.000001;
+
+//The polygon, the triangles cut off so far and the tip of the last ear
+fn triangulation_scene(polygon: &Polygon2D, triangles: &[Polygon2D], ear: Option<Point2d>) -> Scene {
+    let mut scene: Scene = Scene::new();
+
+    scene.layer("polygon").push_polygon(polygon, RGB::white());
+    scene
+        .layer("triangles")
+        .push_lines_color(triangles.iter().flat_map(|t| t.lines()).map(|l: Line2D| (l, RGB::green())));
+
+    if let Some(point) = ear {
+        scene.layer("ear").push_point_styled(point, PointStyle::new(RGB::red(), 5));
+        scene.push_note(format!("ear {}", point));
+    }
+
+    scene.push_note(format!("triangles ({})", triangles.len()));
+
+    scene
+}
+
+//Twice the signed area, positive when a, b, c turn left
+fn turn(a: Point2d, b: Point2d, c: Point2d) -> f32 {
+    (b - a).cross(&(c - a))
+}
+
+fn signed_area(points: &[Point2d]) -> f32 {
+    let n: usize = points.len();
+
+    (0..n)
+        .map(|i| points[i].x * points[(i + 1) % n].y - points[(i + 1) % n].x * points[i].y)
+        .sum::<f32>()
+        / 2f32
+}
+
+fn corner(ring: &[Point2d], i: usize) -> (Point2d, Point2d, Point2d) {
+    let n: usize = ring.len();
+    (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n])
+}
+
+//Inside or on the border of the counter clockwise triangle a, b, c
+fn in_triangle(p: Point2d, a: Point2d, b: Point2d, c: Point2d) -> bool {
+    approx_equal_greater(turn(a, b, p), 0f32, TURN_EPSILON)
+        && approx_equal_greater(turn(b, c, p), 0f32, TURN_EPSILON)
+        && approx_equal_greater(turn(c, a, p), 0f32, TURN_EPSILON)
+}
+
+//A convex corner whose triangle holds none of the other vertices
+fn is_ear(ring: &[Point2d], i: usize) -> bool {
+    let (a, b, c) = corner(ring, i);
+
+    if !approx_greater(turn(a, b, c), 0f32, TURN_EPSILON) {
+        return false;
+    }
+
+    !ring
+        .iter()
+        .filter(|p| **p != a && **p != b && **p != c)
+        .any(|p| in_triangle(*p, a, b, c))
+}
+
+pub fn ear_clipping(polygon: &Polygon2D) -> Option<Vec<Polygon2D>> {
+    ear_clipping_traced(polygon, &mut NoTracer)
+}
+
+//Cuts off one ear at a time until a triangle is left, the triangles are
+//counter clockwise whatever the order of the input. Steps are "ear" for every
+//triangle cut off. None for fewer than 3 vertices or a polygon that is not
+//simple, where at some point no corner is an ear
+pub fn ear_clipping_traced<T: Tracer>(polygon: &Polygon2D, tracer: &mut T) -> Option<Vec<Polygon2D>> {
+    if polygon.is_empty() {
+        return None;
+    }
+
+    let mut ring: Vec<Point2d> = polygon.points.clone();
+    if signed_area(&ring) < 0f32 {
+        ring.reverse();
+    }
+
+    let mut triangles: Vec<Polygon2D> = Vec::new();
+
+    while ring.len() > 3 {
+        match (0..ring.len()).find(|i| is_ear(&ring, *i)) {
+            Some(i) => {
+                let (a, b, c) = corner(&ring, i);
+                triangles.push(Polygon2D::new(vec![a, b, c]));
+                ring.remove(i);
+
+                tracer.trace("ear", || triangulation_scene(polygon, &triangles, Some(b)));
+            }
+            None => {
+                //a corner on a straight edge cuts off nothing, anything else
+                //means the edges cross
+                let flat: usize = (0..ring.len()).find(|i| {
+                    let (a, b, c) = corner(&ring, *i);
+                    approx_equal(turn(a, b, c), 0f32, TURN_EPSILON)
+                })?;
+
+                ring.remove(flat);
+            }
+        }
+    }
+
+    if approx_greater(turn(ring[0], ring[1], ring[2]), 0f32, TURN_EPSILON) {
+        triangles.push(Polygon2D::new(ring.clone()));
+        tracer.trace("ear", || triangulation_scene(polygon, &triangles, Some(ring[1])));
+    }
+
+    Some(triangles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::tracer::SceneRecorder;
+
+    fn polygon(points: &[(f32, f32)]) -> Polygon2D {
+        Polygon2D::new(points.iter().map(|(x, y)| Point2d::new(*x, *y)).collect())
+    }
+
+    fn area(triangles: &[Polygon2D]) -> f32 {
+        triangles.iter().map(|t| signed_area(&t.points)).sum()
+    }
+
+    #[test]
+    fn test_square() {
+        let triangles: Vec<Polygon2D> = ear_clipping(&polygon(&[(0f32, 0f32), (4f32, 0f32), (4f32, 4f32), (0f32, 4f32)])).unwrap();
+
+        assert_eq!(2, triangles.len());
+        assert_eq!(16f32, area(&triangles));
+    }
+
+    #[test]
+    fn test_clockwise_concave() {
+        //an L, clockwise
+        let l_shape: Polygon2D = polygon(&[(0f32, 0f32), (0f32, 4f32), (2f32, 4f32), (2f32, 2f32), (4f32, 2f32), (4f32, 0f32)]);
+        let triangles: Vec<Polygon2D> = ear_clipping(&l_shape).unwrap();
+
+        assert_eq!(4, triangles.len());
+        assert_eq!(12f32, area(&triangles));
+        assert!(triangles.iter().all(|t| signed_area(&t.points) > 0f32));
+    }
+
+    #[test]
+    fn test_collinear_and_degenerate() {
+        let with_flat_corner: Polygon2D = polygon(&[(0f32, 0f32), (2f32, 0f32), (4f32, 0f32), (4f32, 4f32), (0f32, 4f32)]);
+        assert_eq!(16f32, area(&ear_clipping(&with_flat_corner).unwrap()));
+
+        assert!(ear_clipping(&polygon(&[(0f32, 0f32), (1f32, 1f32)])).is_none());
+    }
+
+    #[test]
+    fn test_traced_ears() {
+        let mut recorder: SceneRecorder = SceneRecorder::new();
+        ear_clipping_traced(&polygon(&[(0f32, 0f32), (4f32, 0f32), (4f32, 4f32), (0f32, 4f32)]), &mut recorder).unwrap();
+
+        assert_eq!(vec!["ear", "ear"], recorder.labels());
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    fmt, fs,
+    io::{self, Read, Write},
+};
+
+use geometry_project::{
+    algorithms::{
+        convex_hull::convex_hull,
+        line_intersection::{line_sweep_intersection, naive_line_intersection},
+        triangulation::ear_clipping,
+    },
+    display::{
+        animation::{self, AnimationFormat, AnimationFrame, AnimationOptions},
+        rgb::RGB,
+        scene::Scene,
+    },
+    entities::{
+        algorithms::liang_barsky_clip, line2d::Line2D, point2d::Point2d, polygon2d::Polygon2D, rectangle2d::Rectangle2D,
+    },
+    formats::scenario_serializer::{
+        read_scenario, write_scenario, ScenarioEntities, ScenarioFile, ScenarioFileError, ScenarioFormat,
+    },
+};
+
+use crate::cli::{BatchJob, IntersectMethod, Options};
+
+const WKT_TYPES: [&str; 7] = [
+    "POINT",
+    "LINESTRING",
+    "POLYGON",
+    "MULTIPOINT",
+    "MULTILINESTRING",
+    "MULTIPOLYGON",
+    "GEOMETRYCOLLECTION",
+];
+
+#[derive(Debug)]
+pub enum BatchError {
+    //A format that can not be read or written
+    Usage(String),
+    //Input that could not be read, did not parse or has nothing the job needs
+    Input(String),
+    //Writing the result failed, usually a closed pipe
+    Output(io::Error),
+}
+
+impl BatchError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            BatchError::Input(_) => 1,
+            BatchError::Usage(_) => 2,
+            BatchError::Output(_) => 3,
+        }
+    }
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Usage(message) | BatchError::Input(message) => write!(f, "{}", message),
+            BatchError::Output(e) => write!(f, "Failed to write the result: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Entities(ScenarioFormat),
+    Image(AnimationFormat),
+}
+
+fn job_name(job: &BatchJob) -> &'static str {
+    match job {
+        BatchJob::Hull => "hull",
+        BatchJob::Intersect(_) => "intersect",
+        BatchJob::Clip(_) => "clip",
+        BatchJob::Triangulate => "triangulate",
+        BatchJob::Render => "render",
+    }
+}
+
+//Checked before the input is read, so a typo does not wait on stdin
+fn output_format(job: &BatchJob, to: Option<&str>) -> Result<OutputFormat, BatchError> {
+    match (job, to) {
+        (BatchJob::Render, None) => Ok(OutputFormat::Image(AnimationFormat::Png)),
+        (BatchJob::Render, Some(name)) => AnimationFormat::from_name(name)
+            .filter(|f| *f != AnimationFormat::Gif)
+            .map(OutputFormat::Image)
+            .ok_or_else(|| BatchError::Usage(format!("render writes png or ppm, not {}", name))),
+        (_, None) => Ok(OutputFormat::Entities(ScenarioFormat::Json)),
+        (_, Some(name)) => ScenarioFormat::from_name(name)
+            .filter(|f| !matches!(f, ScenarioFormat::Dxf | ScenarioFormat::Svg))
+            .map(OutputFormat::Entities)
+            .ok_or_else(|| BatchError::Usage(format!("results are written as json, wkt, geojson or csv, not {}", name))),
+    }
+}
+
+//What a pipe holds when --from does not say
+pub fn sniff_format(text: &str) -> ScenarioFormat {
+    let start: &str = text.trim_start();
+    let mut lines = start.lines().map(|l| l.trim());
+    let word: String = start
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_uppercase();
+
+    match start.chars().next() {
+        Some('{') | Some('[') => ScenarioFormat::Json,
+        Some('<') => ScenarioFormat::Svg,
+        _ if WKT_TYPES.contains(&word.as_str()) => ScenarioFormat::Wkt,
+        _ if lines.next() == Some("0") && lines.next() == Some("SECTION") => ScenarioFormat::Dxf,
+        _ => ScenarioFormat::Csv,
+    }
+}
+
+fn input_error(source: &str, error: impl fmt::Display) -> BatchError {
+    BatchError::Input(format!("{}: {}", source, error))
+}
+
+//A file by its extension or stdin by what it looks like, unless --from says
+pub fn read_input(input: Option<&str>, from: Option<&str>) -> Result<ScenarioEntities, BatchError> {
+    let format: Option<ScenarioFormat> = match from {
+        None => None,
+        Some(name) => Some(
+            ScenarioFormat::from_name(name).ok_or_else(|| BatchError::Usage(format!("Unknown input format {}", name)))?,
+        ),
+    };
+
+    let (text, source) = match input {
+        Some(path) => (fs::read_to_string(path).map_err(|e| input_error(path, e))?, path),
+        None => {
+            let mut text: String = String::new();
+            io::stdin().read_to_string(&mut text).map_err(|e| input_error("stdin", e))?;
+            (text, "stdin")
+        }
+    };
+
+    let format: ScenarioFormat = format.unwrap_or_else(|| {
+        input
+            .and_then(|path| path.rsplit_once('.'))
+            .and_then(|(_, extension)| ScenarioFormat::from_name(extension))
+            .unwrap_or_else(|| sniff_format(&text))
+    });
+
+    let file: ScenarioFile = read_scenario(&text, format).map_err(|e| input_error(source, e))?;
+
+    //stdout is the result, what the drawing lost goes with the other messages
+    for warning in &file.warnings {
+        eprintln!("{} {}", source, warning);
+    }
+
+    Ok(file.entities)
+}
+
+fn segments(entities: &ScenarioEntities) -> Result<&Vec<Line2D>, BatchError> {
+    match entities.lines.is_empty() {
+        true => Err(BatchError::Input("the input has no segments".to_string())),
+        false => Ok(&entities.lines),
+    }
+}
+
+//Segment crossings are found with f32::EPSILON, scaled up when the input is
+//far from the origin for its size and the coordinates have lost precision
+fn intersect_epsilon(lines: &[Line2D]) -> f32 {
+    let Some(bounds) = Rectangle2D::from_points(lines.iter().flat_map(|l| [l.start, l.end])) else {
+        return f32::EPSILON;
+    };
+
+    let magnitude: f32 = [bounds.min.x, bounds.min.y, bounds.max.x, bounds.max.y]
+        .iter()
+        .fold(0f32, |m, c| m.max(c.abs()));
+    let extent: f32 = bounds.width().max(bounds.height());
+
+    match extent > 0f32 {
+        true => f32::EPSILON * (magnitude / extent).max(1f32),
+        false => f32::EPSILON,
+    }
+}
+
+fn clip(window: Rectangle2D, lines: &[Line2D]) -> Vec<Line2D> {
+    lines.iter().filter_map(|l| liang_barsky_clip(window, *l)).collect()
+}
+
+//Polygons are triangulated one by one, without any the points are taken as
+//the vertices of one polygon in the order they are given
+fn triangulate(entities: ScenarioEntities) -> Result<Vec<Polygon2D>, BatchError> {
+    let polygons: Vec<Polygon2D> = match entities.polygons.is_empty() {
+        true => vec![Polygon2D::new(entities.points)],
+        false => entities.polygons,
+    };
+
+    let mut triangles: Vec<Polygon2D> = Vec::new();
+
+    for (i, polygon) in polygons.iter().enumerate() {
+        triangles.extend(ear_clipping(polygon).ok_or_else(|| {
+            BatchError::Input(format!("polygon {} is not simple or has fewer than 3 vertices", i + 1))
+        })?);
+    }
+
+    Ok(triangles)
+}
+
+//Render draws what it is given, the other jobs replace the input with their result
+pub fn execute(job: &BatchJob, entities: ScenarioEntities) -> Result<ScenarioEntities, BatchError> {
+    match job {
+        BatchJob::Hull => {
+            let mut points: Vec<Point2d> = entities.points;
+            let count: usize = points.len();
+            let hull: Polygon2D = convex_hull(&mut points)
+                .ok_or_else(|| BatchError::Input(format!("hull needs at least 3 points, got {}", count)))?;
+
+            Ok(ScenarioEntities {
+                polygons: vec![hull],
+                ..Default::default()
+            })
+        }
+        BatchJob::Intersect(method) => {
+            let lines: &Vec<Line2D> = segments(&entities)?;
+            let epsilon: f32 = intersect_epsilon(lines);
+            let points: Vec<Point2d> = match method {
+                IntersectMethod::Naive => naive_line_intersection(lines, epsilon),
+                IntersectMethod::Sweep => line_sweep_intersection(lines, epsilon)
+                    .ok_or_else(|| BatchError::Input("the sweep needs finite segments".to_string()))?,
+            };
+
+            Ok(ScenarioEntities {
+                points,
+                ..Default::default()
+            })
+        }
+        BatchJob::Clip(window) => Ok(ScenarioEntities {
+            lines: clip(*window, segments(&entities)?),
+            ..Default::default()
+        }),
+        BatchJob::Triangulate => Ok(ScenarioEntities {
+            polygons: triangulate(entities)?,
+            ..Default::default()
+        }),
+        BatchJob::Render => Ok(entities),
+    }
+}
+
+fn render_scene(entities: &ScenarioEntities) -> Scene {
+    let mut scene: Scene = Scene::new();
+
+    for polygon in &entities.polygons {
+        scene.layer("polygons").push_polygon(polygon, RGB::blue());
+    }
+
+    scene.layer("lines").push_lines(entities.lines.iter().copied());
+    scene.layer("points").push_points(entities.points.iter().copied());
+
+    scene
+}
+
+fn write_image<W: Write>(entities: &ScenarioEntities, format: AnimationFormat, options: &Options, out: W) -> io::Result<()> {
+    let frame: AnimationFrame = AnimationFrame {
+        caption: String::new(),
+        scene: render_scene(entities),
+    };
+
+    let animation_options: AnimationOptions = AnimationOptions {
+        width: options.window.0,
+        height: options.window.1,
+        delay_ms: options.delay_ms,
+        captions: false,
+    };
+
+    let view: Rectangle2D = animation::frame_view(std::slice::from_ref(&frame));
+    let canvas = animation::render_frame(&frame, view, 0, 1, &animation_options);
+
+    match format {
+        AnimationFormat::Ppm => animation::write_ppm(&canvas, out),
+        _ => animation::write_png(&canvas, out),
+    }
+}
+
+//Reads the input, runs the job and writes the result to out
+pub fn run<W: Write>(job: &BatchJob, input: Option<&str>, options: &Options, out: W) -> Result<(), BatchError> {
+    let output: OutputFormat = output_format(job, options.to.as_deref())?;
+    let result: ScenarioEntities = execute(job, read_input(input, options.from.as_deref())?)?;
+
+    match output {
+        OutputFormat::Image(format) => write_image(&result, format, options, out).map_err(BatchError::Output),
+        OutputFormat::Entities(format) => {
+            write_scenario(&ScenarioFile::new(job_name(job), result), format, out).map_err(|e| match e {
+                ScenarioFileError::Io(e) => BatchError::Output(e),
+                other => BatchError::Output(io::Error::other(other.to_string())),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geometry_project::formats::scenario_serializer::parse_scenario;
+
+    fn square() -> Vec<Point2d> {
+        vec![
+            Point2d::new(0f32, 0f32),
+            Point2d::new(4f32, 0f32),
+            Point2d::new(4f32, 4f32),
+            Point2d::new(0f32, 4f32),
+        ]
+    }
+
+    fn cross() -> Vec<Line2D> {
+        vec![
+            Line2D::new(Point2d::new(0f32, 0f32), Point2d::new(4f32, 4f32)),
+            Line2D::new(Point2d::new(0f32, 4f32), Point2d::new(4f32, 0f32)),
+        ]
+    }
+
+    fn run_on_file(job: BatchJob, name: &str, text: &str, options: &Options) -> Result<Vec<u8>, BatchError> {
+        let path: String = std::env::temp_dir()
+            .join(format!("batch_{}_{}", std::process::id(), name))
+            .to_string_lossy()
+            .to_string();
+        fs::write(&path, text).unwrap();
+
+        let mut out: Vec<u8> = Vec::new();
+        let result: Result<(), BatchError> = run(&job, Some(&path), options, &mut out);
+        fs::remove_file(&path).ok();
+
+        result.map(|_| out)
+    }
+
+    #[test]
+    fn test_hull() {
+        let mut points: Vec<Point2d> = square();
+        points.push(Point2d::new(2f32, 2f32));
+
+        let result: ScenarioEntities = execute(&BatchJob::Hull, ScenarioEntities { points, ..Default::default() }).unwrap();
+        assert_eq!(4, result.polygons[0].points.len());
+
+        let error: BatchError = execute(&BatchJob::Hull, ScenarioEntities::default()).unwrap_err();
+        assert_eq!(1, error.exit_code());
+    }
+
+    #[test]
+    fn test_intersect() {
+        let naive: ScenarioEntities =
+            execute(&BatchJob::Intersect(IntersectMethod::Naive), ScenarioEntities { lines: cross(), ..Default::default() }).unwrap();
+        assert_eq!(vec![Point2d::new(2f32, 2f32)], naive.points);
+
+        let sweep: ScenarioEntities =
+            execute(&BatchJob::Intersect(IntersectMethod::Sweep), ScenarioEntities { lines: cross(), ..Default::default() }).unwrap();
+        assert_eq!(naive.points, sweep.points);
+
+        let unbounded: ScenarioEntities = ScenarioEntities {
+            lines: vec![Line2D::new(Point2d::new(0f32, 0f32), Point2d::new(f32::INFINITY, 1f32))],
+            ..Default::default()
+        };
+        assert!(execute(&BatchJob::Intersect(IntersectMethod::Sweep), unbounded).is_err());
+        assert!(execute(&BatchJob::Intersect(IntersectMethod::Sweep), ScenarioEntities::default()).is_err());
+    }
+
+    #[test]
+    fn test_intersect_far_from_origin() {
+        //the second segment ends on the first, a thousand units out
+        let first: Line2D = Line2D::new(Point2d::new(1000.1f32, 1000.3f32), Point2d::new(1003.7f32, 1002.9f32));
+        let end: Point2d = Point2d::new(
+            first.start.x + 0.37f32 * (first.end.x - first.start.x),
+            first.start.y + 0.37f32 * (first.end.y - first.start.y),
+        );
+        let lines: Vec<Line2D> = vec![first, Line2D::new(Point2d::new(1000.5f32, 1003.1f32), end)];
+
+        assert!(naive_line_intersection(&lines, f32::EPSILON).is_empty());
+        assert!(intersect_epsilon(&lines) > f32::EPSILON);
+
+        let naive: ScenarioEntities =
+            execute(&BatchJob::Intersect(IntersectMethod::Naive), ScenarioEntities { lines, ..Default::default() }).unwrap();
+        assert_eq!(1, naive.points.len());
+        assert_eq!(f32::EPSILON, intersect_epsilon(&cross()));
+    }
+
+    #[test]
+    fn test_clip_and_triangulate() {
+        let window: Rectangle2D = Rectangle2D::new(Point2d::new(1f32, 1f32), Point2d::new(3f32, 3f32));
+        let clipped: ScenarioEntities =
+            execute(&BatchJob::Clip(window), ScenarioEntities { lines: cross(), ..Default::default() }).unwrap();
+        assert_eq!(Point2d::new(1f32, 1f32), clipped.lines[0].start);
+
+        let triangles: ScenarioEntities =
+            execute(&BatchJob::Triangulate, ScenarioEntities { points: square(), ..Default::default() }).unwrap();
+        assert_eq!(2, triangles.polygons.len());
+    }
+
+    #[test]
+    fn test_run_csv_to_json() {
+        let out: Vec<u8> = run_on_file(BatchJob::Hull, "points.csv", "x,y\n0,0\n4,0\n4,4\n0,4\n2,2\n", &Options::default()).unwrap();
+        let file: ScenarioFile = parse_scenario(&String::from_utf8(out).unwrap()).unwrap();
+
+        assert_eq!("hull", file.metadata.scenario);
+        assert_eq!(4, file.entities.polygons[0].points.len());
+    }
+
+    #[test]
+    fn test_run_render_and_errors() {
+        let options: Options = Options {
+            window: (16, 8),
+            to: Some("ppm".to_string()),
+            ..Default::default()
+        };
+        let image: Vec<u8> = run_on_file(BatchJob::Render, "lines.wkt", "LINESTRING (0 0, 4 4)", &options).unwrap();
+        assert!(image.starts_with(b"P6\n16 8\n255\n"));
+
+        let bad_row: BatchError = run_on_file(BatchJob::Hull, "bad.csv", "1,2\n3,x\n", &Options::default()).unwrap_err();
+        assert_eq!(1, bad_row.exit_code());
+        assert!(bad_row.to_string().contains("line 2"));
+
+        let bad_format: Options = Options {
+            to: Some("dxf".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(2, run(&BatchJob::Hull, Some("missing.csv"), &bad_format, io::sink()).unwrap_err().exit_code());
+        assert_eq!(1, run(&BatchJob::Hull, Some("missing.csv"), &Options::default(), io::sink()).unwrap_err().exit_code());
+    }
+
+    #[test]
+    fn test_sniff_format() {
+        assert_eq!(ScenarioFormat::Json, sniff_format("  [{\"x\": 1, \"y\": 2}]"));
+        assert_eq!(ScenarioFormat::Wkt, sniff_format("MultiPoint (1 2)"));
+        assert_eq!(ScenarioFormat::Svg, sniff_format("<svg></svg>"));
+        assert_eq!(ScenarioFormat::Dxf, sniff_format("  0\nSECTION\n  2\nENTITIES"));
+        assert_eq!(ScenarioFormat::Csv, sniff_format("x,y\n1,2"));
+    }
+}
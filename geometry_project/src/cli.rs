@@ -0,0 +1,362 @@
+use geometry_project::{
+    display::scenario::{ScenarioConfig, DEFAULT_SCENARIO},
+    entities::{point2d::Point2d, rectangle2d::Rectangle2D},
+};
+
+pub const DEFAULT_WINDOW: (usize, usize) = (512, 512);
+pub const DEFAULT_LOG_FILE: &str = "log_output.txt";
+pub const DEFAULT_DELAY_MS: u32 = 500;
+
+pub const USAGE: &str = "Usage:
+  geometry_project [options]             run a scenario
+  geometry_project replay [file]         step through logged scenes or a trace file
+  geometry_project diff <before> <after> show the difference between two scene files
+  geometry_project export <file> <out>   render logged scenes or a trace to .gif, .png or .ppm frames
+
+Batch commands, read a file or stdin when it is left out or -, write to stdout:
+  geometry_project hull [file]                       convex hull of the points
+  geometry_project intersect [naive|sweep] [file]    intersections of the segments, naive by default
+  geometry_project clip <x0,y0,x1,y1> [file]         segments clipped to the rectangle
+  geometry_project triangulate [file]                triangles of the polygons, or of the points as one polygon
+  geometry_project render [file]                     picture of the input, --window sets its size
+They exit with 1 for invalid input, 2 for bad arguments and 3 when the output can not be written
+
+Options:
+  -s, --scenario <name>           scenario to run, see --list
+  -n, --count <n>                 number of points or lines
+  -b, --bounds <x0,y0,x1,y1>      rectangle random entities are placed in
+      --seed <n>                  seed for the random entities
+  -w, --window <width>x<height>   window size in pixels
+  -i, --input <file>              read the scenario input from a .json, .wkt, .geojson,
+                                  .dxf or .svg file
+      --trace <file>              write algorithm steps to a .ndjson or .gtrace file
+      --delay <ms>                time between exported gif frames
+      --no-captions               leave the step names out of exported frames
+      --from <format>             batch input format: json, wkt, geojson, csv, dxf or svg,
+                                  by default the extension or what stdin looks like
+      --to <format>               batch output format: json, wkt, geojson or csv, png or ppm
+                                  for render. json and png by default
+      --list                      list the available scenarios
+  -h, --help                      show this message";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntersectMethod {
+    Naive,
+    Sweep,
+}
+
+//Commands that run an algorithm on a file without a window
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchJob {
+    Hull,
+    Intersect(IntersectMethod),
+    Clip(Rectangle2D),
+    Triangulate,
+    Render,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run,
+    Replay(String),
+    Diff(String, String),
+    Export(String, String),
+    //No file reads stdin
+    Batch(BatchJob, Option<String>),
+    List,
+    Help,
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub command: Command,
+    pub scenario: String,
+    pub config: ScenarioConfig,
+    pub window: (usize, usize),
+    pub delay_ms: u32,
+    pub captions: bool,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            command: Command::Run,
+            scenario: DEFAULT_SCENARIO.to_string(),
+            config: ScenarioConfig {
+                count: None,
+                bounds: Rectangle2D::new(Point2d::new(25f32, 25f32), Point2d::new(75f32, 75f32)),
+                seed: None,
+                input: None,
+                trace: None,
+            },
+            window: DEFAULT_WINDOW,
+            delay_ms: DEFAULT_DELAY_MS,
+            captions: true,
+            from: None,
+            to: None,
+        }
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid value '{}' for {}", value, flag))
+}
+
+fn parse_bounds(flag: &str, value: &str) -> Result<Rectangle2D, String> {
+    let numbers: Vec<f32> = value
+        .split(',')
+        .map(|v| parse_number(flag, v))
+        .collect::<Result<Vec<f32>, String>>()?;
+
+    if numbers.len() != 4 || numbers.iter().any(|n| !n.is_finite()) {
+        return Err(format!("{} takes x0,y0,x1,y1, got '{}'", flag, value));
+    }
+
+    if numbers[0] >= numbers[2] || numbers[1] >= numbers[3] {
+        return Err(format!("{} minimum has to be below the maximum, got '{}'", flag, value));
+    }
+
+    Ok(Rectangle2D::new(
+        Point2d::new(numbers[0], numbers[1]),
+        Point2d::new(numbers[2], numbers[3]),
+    ))
+}
+
+fn parse_window(value: &str) -> Result<(usize, usize), String> {
+    let (width, height) = value
+        .split_once('x')
+        .ok_or_else(|| format!("--window takes <width>x<height>, got '{}'", value))?;
+
+    let size: (usize, usize) = (parse_number("--window", width)?, parse_number("--window", height)?);
+
+    if size.0 == 0 || size.1 == 0 {
+        return Err("--window size has to be greater than 0".to_string());
+    }
+
+    Ok(size)
+}
+
+//The job and the file after it, no file or - reads stdin
+fn parse_batch(name: &str, args: &[String]) -> Result<(BatchJob, Option<String>), String> {
+    let mut args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+
+    let job: BatchJob = match name {
+        "hull" => BatchJob::Hull,
+        "triangulate" => BatchJob::Triangulate,
+        "render" => BatchJob::Render,
+        "intersect" => match args.first() {
+            Some(&"naive") => {
+                args.remove(0);
+                BatchJob::Intersect(IntersectMethod::Naive)
+            }
+            Some(&"sweep") => {
+                args.remove(0);
+                BatchJob::Intersect(IntersectMethod::Sweep)
+            }
+            _ => BatchJob::Intersect(IntersectMethod::Naive),
+        },
+        "clip" => match args.is_empty() {
+            true => {
+                return Err("clip takes a rectangle x0,y0,x1,y1".to_string());
+            }
+            false => BatchJob::Clip(parse_bounds("clip", args.remove(0))?),
+        },
+        other => {
+            return Err(format!("Unknown command {}", other));
+        }
+    };
+
+    match args.as_slice() {
+        [] | ["-"] => Ok((job, None)),
+        [file] => Ok((job, Some(file.to_string()))),
+        _ => Err(format!("{} takes at most one file", name)),
+    }
+}
+
+//Positional, not a flag: "-" for stdin and negative numbers like a clip rectangle
+fn is_positional(arg: &str) -> bool {
+    match arg.strip_prefix('-') {
+        None => true,
+        Some(rest) => rest.is_empty() || rest.starts_with(|c: char| c.is_ascii_digit() || c == '.'),
+    }
+}
+
+//args excludes the program name
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options: Options = Options::default();
+    let mut rest = args.iter();
+    let mut positional: Vec<String> = Vec::new();
+
+    while let Some(arg) = rest.next() {
+        let flag: &str = arg.as_str();
+
+        if is_positional(flag) {
+            positional.push(arg.clone());
+            continue;
+        }
+
+        match flag {
+            "--list" => {
+                options.command = Command::List;
+                continue;
+            }
+            "-h" | "--help" => {
+                options.command = Command::Help;
+                continue;
+            }
+            "--no-captions" => {
+                options.captions = false;
+                continue;
+            }
+            _ => {}
+        }
+
+        let value: &str = rest
+            .next()
+            .ok_or_else(|| format!("Missing value for {}", flag))?
+            .as_str();
+
+        match flag {
+            "-s" | "--scenario" => options.scenario = value.to_string(),
+            "-n" | "--count" => options.config.count = Some(parse_number(flag, value)?),
+            "-b" | "--bounds" => options.config.bounds = parse_bounds(flag, value)?,
+            "--seed" => options.config.seed = Some(parse_number(flag, value)?),
+            "-w" | "--window" => options.window = parse_window(value)?,
+            "-i" | "--input" => options.config.input = Some(value.to_string()),
+            "--trace" => options.config.trace = Some(value.to_string()),
+            "--delay" => options.delay_ms = parse_number(flag, value)?,
+            "--from" => options.from = Some(value.to_string()),
+            "--to" => options.to = Some(value.to_string()),
+            _ => {
+                return Err(format!("Unknown option {}", flag));
+            }
+        }
+    }
+
+    if matches!(options.command, Command::List | Command::Help) {
+        return Ok(options);
+    }
+
+    options.command = match positional.first().map(|p| p.as_str()) {
+        None => Command::Run,
+        Some("replay") => match positional.len() {
+            1 => Command::Replay(DEFAULT_LOG_FILE.to_string()),
+            2 => Command::Replay(positional[1].clone()),
+            _ => {
+                return Err("replay takes at most one file".to_string());
+            }
+        },
+        Some("diff") => match positional.len() {
+            3 => Command::Diff(positional[1].clone(), positional[2].clone()),
+            _ => {
+                return Err("diff takes two scene files".to_string());
+            }
+        },
+        Some("export") => match positional.len() {
+            3 => Command::Export(positional[1].clone(), positional[2].clone()),
+            _ => {
+                return Err("export takes a scene log or trace and an output file".to_string());
+            }
+        },
+        Some(other) => {
+            let (job, input) = parse_batch(other, &positional[1..])?;
+            Command::Batch(job, input)
+        }
+    };
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn test_defaults() {
+        let options: Options = parse(&[]).unwrap();
+
+        assert_eq!(Command::Run, options.command);
+        assert_eq!(DEFAULT_SCENARIO, options.scenario);
+        assert_eq!(DEFAULT_WINDOW, options.window);
+    }
+
+    #[test]
+    fn test_scenario_options() {
+        let options: Options = parse(&[
+            "-s", "convex-hull", "--count", "25", "--bounds", "-10,-5,10,5", "--seed", "42", "--window", "800x600",
+        ])
+        .unwrap();
+
+        assert_eq!("convex-hull", options.scenario);
+        assert_eq!(Some(25), options.config.count);
+        assert_eq!(Some(42), options.config.seed);
+        assert_eq!((800, 600), options.window);
+        assert_eq!(Point2d::new(-10f32, -5f32), options.config.bounds.min);
+        assert_eq!(Point2d::new(10f32, 5f32), options.config.bounds.max);
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(Command::Replay(DEFAULT_LOG_FILE.to_string()), parse(&["replay"]).unwrap().command);
+        assert_eq!(
+            Command::Diff("a.json".to_string(), "b.json".to_string()),
+            parse(&["diff", "a.json", "b.json"]).unwrap().command
+        );
+        assert_eq!(Command::List, parse(&["--list"]).unwrap().command);
+    }
+
+    #[test]
+    fn test_export_options() {
+        let options: Options = parse(&["export", "hull.ndjson", "hull.gif", "--delay", "120", "--no-captions"]).unwrap();
+
+        assert_eq!(Command::Export("hull.ndjson".to_string(), "hull.gif".to_string()), options.command);
+        assert_eq!(120, options.delay_ms);
+        assert!(!options.captions);
+        assert!(parse(&["export", "hull.ndjson"]).is_err());
+    }
+
+    #[test]
+    fn test_batch_commands() {
+        let hull: Options = parse(&["hull", "points.csv", "--to", "wkt"]).unwrap();
+        assert_eq!(Command::Batch(BatchJob::Hull, Some("points.csv".to_string())), hull.command);
+        assert_eq!(Some("wkt".to_string()), hull.to);
+
+        assert_eq!(
+            Command::Batch(BatchJob::Intersect(IntersectMethod::Sweep), None),
+            parse(&["intersect", "sweep", "-"]).unwrap().command
+        );
+        assert_eq!(
+            Command::Batch(BatchJob::Intersect(IntersectMethod::Naive), Some("lines.json".to_string())),
+            parse(&["intersect", "lines.json"]).unwrap().command
+        );
+
+        let window: Rectangle2D = Rectangle2D::new(Point2d::new(-10f32, -5f32), Point2d::new(10f32, 5f32));
+        assert_eq!(Command::Batch(BatchJob::Clip(window), None), parse(&["clip", "-10,-5,10,5"]).unwrap().command);
+
+        assert!(parse(&["clip"]).is_err());
+        assert!(parse(&["clip", "1,1,0,0"]).is_err());
+        assert!(parse(&["hull", "a.json", "b.json"]).is_err());
+        assert!(parse(&["frobnicate"]).is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(parse(&["--count"]).is_err());
+        assert!(parse(&["--count", "many"]).is_err());
+        assert!(parse(&["--bounds", "1,2,3"]).is_err());
+        assert!(parse(&["--bounds", "5,5,1,1"]).is_err());
+        assert!(parse(&["--window", "0x10"]).is_err());
+        assert!(parse(&["--frobnicate", "1"]).is_err());
+        assert!(parse(&["diff", "a.json"]).is_err());
+    }
+}
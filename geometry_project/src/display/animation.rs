@@ -0,0 +1,327 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use data_structures::vec2d::Vec2D;
+use memory_math::memory_index2d::MemIndex2D;
+
+use crate::{
+    algorithms::tracer::TraceStep,
+    display::{camera::Camera, rgb::RGB, scene::Scene},
+    entities::rectangle2d::Rectangle2D,
+    scene_logger::scene_log_reader::LoggedScene,
+};
+
+//gif frame delays are counted in hundredths of a second
+const GIF_DELAY_UNIT_MS: u32 = 10;
+//1 is the best and slowest quantizer, 10 is the gif crate's default trade off
+const GIF_QUANTIZER_SPEED: i32 = 10;
+//Margin around the scenes as a fraction of their extent
+const VIEW_MARGIN: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationFormat {
+    //One animated file
+    Gif,
+    //Numbered files, out.png becomes out_0000.png, out_0001.png, ..
+    Png,
+    Ppm,
+}
+
+impl AnimationFormat {
+    pub fn from_path(path: &str) -> Option<AnimationFormat> {
+        AnimationFormat::from_name(path.rsplit_once('.')?.1)
+    }
+
+    //An extension without the dot, in any case
+    pub fn from_name(name: &str) -> Option<AnimationFormat> {
+        match name.to_ascii_lowercase().as_str() {
+            "gif" => Some(AnimationFormat::Gif),
+            "png" => Some(AnimationFormat::Png),
+            "ppm" => Some(AnimationFormat::Ppm),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationOptions {
+    pub width: usize,
+    pub height: usize,
+    //Only used by gifs, frame sequences leave the timing to whatever plays them
+    pub delay_ms: u32,
+    pub captions: bool,
+}
+
+//One scene of the animation and the text shown with it
+#[derive(Debug, Clone)]
+pub struct AnimationFrame {
+    pub caption: String,
+    pub scene: Scene,
+}
+
+impl From<TraceStep> for AnimationFrame {
+    fn from(step: TraceStep) -> Self {
+        AnimationFrame {
+            caption: step.label,
+            scene: step.scene,
+        }
+    }
+}
+
+impl From<LoggedScene> for AnimationFrame {
+    fn from(logged: LoggedScene) -> Self {
+        AnimationFrame {
+            caption: logged.step.clone().unwrap_or_else(|| logged.location()),
+            scene: logged.scene,
+        }
+    }
+}
+
+//Every frame is drawn with the same view so the picture does not jump between steps
+pub fn frame_view(frames: &[AnimationFrame]) -> Rectangle2D {
+    let bounds: Option<Rectangle2D> = Rectangle2D::from_points(
+        frames
+            .iter()
+            .filter_map(|f| f.scene.bounds())
+            .flat_map(|b| [b.min, b.max]),
+    );
+
+    match bounds {
+        Some(b) => b.expand((b.width().max(b.height()) * VIEW_MARGIN).max(1f32)),
+        None => Rectangle2D::new_width_height(100f32, 100f32),
+    }
+}
+
+//Draws one frame with the headless camera, the caption goes in the status box
+pub fn render_frame(frame: &AnimationFrame, view: Rectangle2D, index: usize, count: usize, options: &AnimationOptions) -> Vec2D<RGB> {
+    let mut camera: Camera = Camera::new(view.width(), view.height());
+    camera.view_port = view;
+    camera.push_scene(frame.scene.clone());
+
+    if options.captions {
+        camera.push_status(format!("{}/{} {}", index + 1, count, frame.caption));
+    }
+
+    let mut canvas: Vec2D<RGB> = Vec2D::new_from_flatpack(
+        vec![RGB::black(); options.width * options.height],
+        options.width,
+        options.height,
+    )
+    .expect("width height unexpected");
+
+    camera.draw(&mut canvas);
+    canvas
+}
+
+//Row major r, g, b bytes
+pub fn rgb_bytes(canvas: &Vec2D<RGB>) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(canvas.width() * canvas.height() * 3);
+
+    for row in 0..canvas.height() {
+        for col in 0..canvas.width() {
+            let (r, g, b) = canvas[MemIndex2D::new(row, col)].channels();
+            bytes.extend([r, g, b]);
+        }
+    }
+
+    bytes
+}
+
+//Binary P6 with 8 bits per channel
+pub fn write_ppm<W: Write>(canvas: &Vec2D<RGB>, mut out: W) -> io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", canvas.width(), canvas.height())?;
+    out.write_all(&rgb_bytes(canvas))?;
+    out.flush()
+}
+
+pub fn write_png<W: Write>(canvas: &Vec2D<RGB>, out: W) -> io::Result<()> {
+    let mut encoder = png::Encoder::new(out, canvas.width() as u32, canvas.height() as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&rgb_bytes(canvas))?;
+    writer.finish()?;
+
+    Ok(())
+}
+
+fn gif_error(error: gif::EncodingError) -> io::Error {
+    match error {
+        gif::EncodingError::Io(e) => e,
+        other => io::Error::new(io::ErrorKind::InvalidData, other.to_string()),
+    }
+}
+
+//Loops forever, every frame gets its own palette
+pub fn write_gif<W: Write>(frames: &[AnimationFrame], options: &AnimationOptions, out: W) -> io::Result<()> {
+    let too_large = || io::Error::new(io::ErrorKind::InvalidInput, "Gifs are at most 65535 pixels wide and high");
+    let width: u16 = u16::try_from(options.width).map_err(|_| too_large())?;
+    let height: u16 = u16::try_from(options.height).map_err(|_| too_large())?;
+    let delay: u16 = u16::try_from((options.delay_ms + GIF_DELAY_UNIT_MS / 2) / GIF_DELAY_UNIT_MS).unwrap_or(u16::MAX);
+
+    let view: Rectangle2D = frame_view(frames);
+    let mut encoder = gif::Encoder::new(out, width, height, &[]).map_err(gif_error)?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_error)?;
+
+    for (i, frame) in frames.iter().enumerate() {
+        let canvas: Vec2D<RGB> = render_frame(frame, view, i, frames.len(), options);
+
+        let mut gif_frame = gif::Frame::from_rgb_speed(width, height, &rgb_bytes(&canvas), GIF_QUANTIZER_SPEED);
+        gif_frame.delay = delay;
+
+        encoder.write_frame(&gif_frame).map_err(gif_error)?;
+    }
+
+    encoder.into_inner().map_err(gif_error)?.flush()
+}
+
+//out.png and 3 give out_0003.png, a path without an extension just gets the number
+pub fn frame_path(path: &str, index: usize) -> String {
+    match path.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() && !extension.contains(['/', '\\']) => {
+            format!("{}_{:04}.{}", stem, index, extension)
+        }
+        _ => format!("{}_{:04}", path, index),
+    }
+}
+
+//Writes a gif or one file per frame depending on the extension of path,
+//returns how many files were written
+pub fn export(frames: &[AnimationFrame], path: &str, options: &AnimationOptions) -> io::Result<usize> {
+    if frames.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "There are no frames to export"));
+    }
+
+    let format: AnimationFormat = AnimationFormat::from_path(path).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "Animations are written as .gif, .png or .ppm")
+    })?;
+
+    if format == AnimationFormat::Gif {
+        write_gif(frames, options, BufWriter::new(File::create(path)?))?;
+        return Ok(1);
+    }
+
+    let view: Rectangle2D = frame_view(frames);
+
+    for (i, frame) in frames.iter().enumerate() {
+        let canvas: Vec2D<RGB> = render_frame(frame, view, i, frames.len(), options);
+        let out: BufWriter<File> = BufWriter::new(File::create(frame_path(path, i))?);
+
+        match format {
+            AnimationFormat::Png => write_png(&canvas, out)?,
+            _ => write_ppm(&canvas, out)?,
+        }
+    }
+
+    Ok(frames.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{line2d::Line2D, point2d::Point2d};
+
+    fn options() -> AnimationOptions {
+        AnimationOptions {
+            width: 16,
+            height: 12,
+            delay_ms: 250,
+            captions: true,
+        }
+    }
+
+    fn frames() -> Vec<AnimationFrame> {
+        let mut first: Scene = Scene::new();
+        first.push_point(Point2d::new(0f32, 0f32));
+
+        let mut second: Scene = Scene::new();
+        second.push_line(Line2D::new_flat(0f32, 0f32, 40f32, 20f32));
+
+        vec![
+            AnimationFrame { caption: "start".to_string(), scene: first },
+            AnimationFrame { caption: "line".to_string(), scene: second },
+        ]
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(Some(AnimationFormat::Gif), AnimationFormat::from_path("hull.GIF"));
+        assert_eq!(Some(AnimationFormat::Ppm), AnimationFormat::from_path("frames/step.ppm"));
+        assert_eq!(None, AnimationFormat::from_path("hull.ndjson"));
+        assert_eq!(None, AnimationFormat::from_path("hull"));
+    }
+
+    #[test]
+    fn test_frame_path() {
+        assert_eq!("out_0003.png", frame_path("out.png", 3));
+        assert_eq!("frames/step_0012.ppm", frame_path("frames/step.ppm", 12));
+        assert_eq!("./frames/out_0000", frame_path("./frames/out", 0));
+    }
+
+    #[test]
+    fn test_view_covers_every_frame() {
+        let view: Rectangle2D = frame_view(&frames());
+
+        assert!(view.min.x < 0f32 && view.min.y < 0f32);
+        assert!(view.max.x > 40f32 && view.max.y > 20f32);
+    }
+
+    #[test]
+    fn test_ppm_header_and_size() {
+        let frames: Vec<AnimationFrame> = frames();
+        let canvas: Vec2D<RGB> = render_frame(&frames[1], frame_view(&frames), 1, 2, &options());
+
+        let mut bytes: Vec<u8> = Vec::new();
+        write_ppm(&canvas, &mut bytes).unwrap();
+
+        let header: &[u8] = b"P6\n16 12\n255\n";
+        assert!(bytes.starts_with(header));
+        assert_eq!(header.len() + 16 * 12 * 3, bytes.len());
+    }
+
+    #[test]
+    fn test_png_signature() {
+        let frames: Vec<AnimationFrame> = frames();
+        let canvas: Vec2D<RGB> = render_frame(&frames[0], frame_view(&frames), 0, 2, &options());
+
+        let mut bytes: Vec<u8> = Vec::new();
+        write_png(&canvas, &mut bytes).unwrap();
+
+        assert!(bytes.starts_with(&[0x89, b'P', b'N', b'G']));
+    }
+
+    #[test]
+    fn test_gif_frames_and_delay() {
+        let mut bytes: Vec<u8> = Vec::new();
+        write_gif(&frames(), &options(), &mut bytes).unwrap();
+
+        assert!(bytes.starts_with(b"GIF89a"));
+        assert_eq!(16, u16::from_le_bytes([bytes[6], bytes[7]]));
+
+        //graphic control extensions, one per frame, delay is in hundredths
+        let controls: Vec<usize> = bytes.windows(3).enumerate().filter(|(_, w)| *w == [0x21, 0xf9, 0x04]).map(|(i, _)| i).collect();
+        assert_eq!(2, controls.len());
+        assert_eq!(25, u16::from_le_bytes([bytes[controls[0] + 4], bytes[controls[0] + 5]]));
+    }
+
+    #[test]
+    fn test_captions_are_optional() {
+        let frames: Vec<AnimationFrame> = frames();
+        let view: Rectangle2D = frame_view(&frames);
+        let plain: AnimationOptions = AnimationOptions { captions: false, width: 64, height: 48, ..options() };
+        let captioned: AnimationOptions = AnimationOptions { width: 64, height: 48, ..options() };
+
+        let without: Vec<u8> = rgb_bytes(&render_frame(&frames[0], view, 0, 2, &plain));
+        let with: Vec<u8> = rgb_bytes(&render_frame(&frames[0], view, 0, 2, &captioned));
+
+        assert_ne!(without, with);
+    }
+
+    #[test]
+    fn test_export_without_frames() {
+        assert!(export(&[], "empty.gif", &options()).is_err());
+    }
+}
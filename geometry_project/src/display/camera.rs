@@ -1,6 +1,8 @@
-use core::{f32, panic};
+use core::f32;
+use std::ops::Range;
 
 use crate::display::rgb::RGB;
+use crate::display::rgba::RGBA;
 use crate::entities::affine_matrix2d::Matrix2D;
 use crate::entities::algorithms;
 use crate::entities::polygon2d::Polygon2D;
@@ -9,59 +11,89 @@ use crate::entities::vect2d::Vector2D;
 use crate::entities::{line2d::Line2D, point2d::Point2d};
 use crate::numerics::floating_comparisons::approx_equal;
 use data_structures::vec2d::Vec2D;
+#[cfg(feature = "log-statements")]
 use log_statement::def_log;
 use memory_math::memory_extents2d::MemExtents2D;
 use memory_math::memory_index2d::MemIndex2D;
 use memory_math::memory_line::MemLine2D;
 
-use super::bresnehem::BresnehemIter;
-
-//TODO: Implement point size = points into squares
+use super::bitmap_font::{text_pixels, text_size, GLYPH_HEIGHT, LINE_HEIGHT};
+use super::label::{Label, LegendEntry, LABEL_OFFSET};
+use super::overlays::{
+    format_tick, grid_values, nice_floor, nice_step, Overlays, AXES_COLOR, LATTICE_COLOR,
+    LATTICE_MIN_PIXELS, MAJOR_DIVISIONS, MAJOR_GRID_COLOR, MINOR_GRID_COLOR, MINOR_PER_MAJOR,
+    SCALE_BAR_PIXELS, TICK_LABEL_COLOR,
+};
+use super::line_style::{arrow_head_polygon, disk_polygon, thick_segment_polygon, LineStyle};
+use super::scanline::{scanline_fill, FillRule, Span};
+use super::scene::{Layer, Primitive, Scene};
+use super::style::{FillStyle, PointStyle, DEFAULT_LINE_COLOR};
+use super::viewport::{ViewTransform, YAxis};
+use super::xiaolin_wu::{xiaolin_wu, WuPixel};
+
+//Draws a layered scene, layers are toggled by name so hiding one survives a redraw
 pub struct Camera {
     pub view_port: Rectangle2D,
-    points: Vec<Point2d>,
-    lines: Vec<Line2D>,
-    point_colors: Vec<RGB>,
-    line_colors: Vec<RGB>,
-    point_size: u8
+    pub overlays: Overlays,
+    scene: Scene,
+    hidden_layers: Vec<String>,
+    hud: Vec<String>,
+    status: Vec<String>,
+    help: Vec<String>,
+    y_axis: YAxis,
+    preserve_aspect: bool
 }
 
+#[cfg(feature = "log-statements")]
 const LOGGING_ENABLED: bool = true;
+#[cfg(feature = "log-statements")]
 def_log!(Camera, LOGGING_ENABLED);
+#[cfg(not(feature = "log-statements"))]
+use crate::no_log as camera_log;
+
+const LETTERBOX_COLOR: RGB = RGB(0x181818);
+const OVERLAY_BACKGROUND: RGBA = RGBA(0xb0000000);
+const OVERLAY_MARGIN: i64 = 4;
+const LEGEND_SWATCH: i64 = 7;
 
 impl Camera {
     pub fn new(width: f32, height: f32) -> Self {
         Camera {
             view_port: Rectangle2D::new_width_height(width, height),
-            points: Vec::new(),
-            lines: Vec::new(),
-            point_colors: Vec::new(),
-            line_colors: Vec::new(),
-            point_size: 1
+            overlays: Overlays::default(),
+            scene: Scene::new(),
+            hidden_layers: Vec::new(),
+            hud: Vec::new(),
+            status: Vec::new(),
+            help: Vec::new(),
+            y_axis: YAxis::Up,
+            preserve_aspect: true
         }
     }
 
     pub fn clear(&mut self) {
-        self.points.clear();
-        self.lines.clear();
-        self.point_colors.clear();
-        self.line_colors.clear();
+        self.scene.clear();
+        self.hud.clear();
+        self.status.clear();
+        self.help.clear();
     }
 
-    fn point_into_index(value: Point2d) -> MemIndex2D {
+    //Screen point to pixel, None when it is not finite or left/above the canvas.
+    //Points a rounding error below zero still land on the first row/column
+    fn point_into_index(value: Point2d) -> Option<MemIndex2D> {
         if !value.y.is_finite() || !value.x.is_finite() {
-            panic!("point not finite: {}", value);
+            return None;
         }
 
-        if value.y < 0f32 || value.x < 0f32 {
-            panic!("Point is negative");
+        if value.y < -0.5f32 || value.x < -0.5f32 {
+            return None;
         }
 
-        let row: usize = value.y as usize;
+        let row: usize = value.y.max(0f32) as usize;
 
-        let col: usize = value.x as usize;
+        let col: usize = value.x.max(0f32) as usize;
 
-        MemIndex2D::new(row, col)
+        Some(MemIndex2D::new(row, col))
     }
 
     fn clip_line(&self, line: Line2D) -> Option<Line2D> {
@@ -72,7 +104,7 @@ impl Camera {
 
         camera_log!("Plotting Line {:?}", line);
         let min: MemIndex2D;
-        if let Ok(m) = MemIndex2D::try_from(Into::<(f32, f32)>::into(line.start))
+        if let Some(m) = Self::point_into_index(line.start)
         {
             min = m;
         }
@@ -82,7 +114,7 @@ impl Camera {
         }
 
         let max: MemIndex2D;
-        if let Ok(m) = MemIndex2D::try_from(Into::<(f32, f32)>::into(line.end))
+        if let Some(m) = Self::point_into_index(line.end)
         {
             max = m;
         }
@@ -101,165 +133,727 @@ impl Camera {
         }
     }
 
-    fn draw_lines(&self, canvas: &mut Vec2D<RGB>, skew: Matrix2D) {
-        camera_log!("Drawing lines: {:?}", || &self.lines);
+    fn blend_pixel(canvas: &mut Vec2D<RGB>, pixel: WuPixel, color: RGB) {
+        if pixel.row < 0 || pixel.col < 0 {
+            return;
+        }
+
+        let index: MemIndex2D = MemIndex2D::new(pixel.row as usize, pixel.col as usize);
 
-        let mut idx: usize = 0;
-        for line in &self.lines {
-            //println!("Draw line at {}", line);
-            let clipped: Line2D = match self.clip_line(line.to_owned()) {
-                Some(l) => l,
-                None => {
-                    continue;
-                }
-            };
+        if canvas.index2d_in_bounds(index) {
+            canvas[index] = RGBA::from_rgb_opacity(color, pixel.coverage).blend_over(canvas[index]);
+        }
+    }
+
+    fn draw_line_anti_aliased(&self, line: Line2D, color: RGB, canvas: &mut Vec2D<RGB>) {
+        camera_log!("Plotting anti aliased line {:?}", line);
+
+        for pixel in xiaolin_wu(line) {
+            Self::blend_pixel(canvas, pixel, color);
+        }
+    }
 
-            if approx_equal(clipped.len(), 0f32, f32::EPSILON) {
+    //Line is in screen space, thick segments get a disk at both ends for round caps and joins
+    fn stroke_line(&self, line: Line2D, color: RGB, style: LineStyle, canvas: &mut Vec2D<RGB>) {
+        for segment in style.dash_segments(line) {
+            if !style.is_thick() {
+                match style.anti_aliased {
+                    true => self.draw_line_anti_aliased(segment, color, canvas),
+                    false => self.draw_line(segment, color, canvas),
+                }
                 continue;
             }
 
-            let scaled_line: Line2D = clipped * skew;
+            if let Some(quad) = thick_segment_polygon(segment, style.width) {
+                self.fill_screen_polygon(&quad, FillRule::NonZero, color.into(), canvas);
+
+                if style.anti_aliased {
+                    let edges: Vec<Line2D> = quad.lines();
+                    self.draw_line_anti_aliased(edges[0], color, canvas);
+                    self.draw_line_anti_aliased(edges[2], color, canvas);
+                }
+            }
+
+            let radius: f32 = style.width / 2f32;
+            self.fill_screen_polygon(&disk_polygon(segment.start, radius), FillRule::NonZero, color.into(), canvas);
+            self.fill_screen_polygon(&disk_polygon(segment.end, radius), FillRule::NonZero, color.into(), canvas);
+        }
+    }
+
+    fn draw_clipped_line(&self, line: Line2D, color: RGB, style: LineStyle, canvas: &mut Vec2D<RGB>, skew: Matrix2D) {
+        let clipped: Line2D = match self.clip_line(line) {
+            Some(l) => l,
+            None => {
+                return;
+            }
+        };
+
+        if approx_equal(clipped.len(), 0f32, f32::EPSILON) {
+            return;
+        }
 
-            let color: RGB = match self.line_colors.len() > idx {
-                true => self.line_colors[idx],
-                false => RGB::red(),
-            };
-            idx += 1;
+        let screen_line: Line2D = clipped * skew;
+        self.stroke_line(screen_line, color, style, canvas);
+
+        //only point at the end when it is actually on screen
+        if style.arrow_head && self.view_port.contains_closed(&line.end, f32::EPSILON) {
+            if let Some(arrow) = arrow_head_polygon(screen_line, style.arrow_head_size()) {
+                self.fill_screen_polygon(&arrow, FillRule::NonZero, color.into(), canvas);
+            }
+        }
+    }
 
-            self.draw_line(scaled_line, color, canvas);
+    //Translucent fills are composited source over whatever is already on the canvas,
+    //spans are cut to the view port so fills stay off the letterbox
+    fn fill_screen_polygon(&self, polygon: &Polygon2D, rule: FillRule, color: RGBA, canvas: &mut Vec2D<RGB>) {
+        let spans: Vec<Span> = scanline_fill(polygon, rule, canvas.width(), canvas.height());
+        let transform: ViewTransform = self.view_transform(canvas.width(), canvas.height());
+        let (rows, cols): (Range<usize>, Range<usize>) = Self::screen_pixels(canvas, &transform);
+
+        camera_log!("Filling polygon with {} spans", spans.len());
+        for span in spans.iter().filter(|s| rows.contains(&s.row)) {
+            for col in span.col_start.max(cols.start)..span.col_end.min(cols.end) {
+                let index: MemIndex2D = MemIndex2D::new(span.row, col);
+                canvas[index] = color.blend_over(canvas[index]);
+            }
         }
     }
 
-    fn draw_points(&self, canvas: &mut Vec2D<RGB>, skew: Matrix2D)
+    fn fill_polygon(&self, polygon: &Polygon2D, style: FillStyle, canvas: &mut Vec2D<RGB>, skew: Matrix2D) {
+        let screen_polygon: Polygon2D = polygon * skew;
+
+        self.fill_screen_polygon(&screen_polygon, style.rule, style.fill, canvas);
+    }
+
+    fn draw_point(&self, point: Point2d, style: PointStyle, canvas: &mut Vec2D<RGB>, skew: Matrix2D)
     {
-        camera_log!("Drawing points: {:?}", || &self.points);
-        for i in 0..self.points.len(){
+        if !self.view_port.contains_closed(&point, f32::EPSILON)
+        {
+            return;
+        }
 
-            if !self.view_port.contains_closed(&self.points[i], f32::EPSILON)
-            {
-                continue;
+        let normalized: Point2d = point * skew;
+
+        let coord: MemIndex2D = match Self::point_into_index(normalized) {
+            Some(c) => c,
+            None => {
+                return;
             }
+        };
 
-            let color: RGB = match i < self.point_colors.len()
-            {
-                true => self.point_colors[i].clone(),
-                false => RGB::white()
-            };
+        if style.size <= 1
+        {
+            if canvas.index2d_in_bounds(coord) {
+                canvas[coord] = style.color;
+            }
+            return;
+        }
+
+        let row_min: usize = coord.row.saturating_sub((style.size / 2) as usize);
+        let col_min: usize = coord.col.saturating_sub((style.size / 2) as usize);
+
+        let row_max: usize = usize::min(canvas.height(), coord.row + (style.size - style.size / 2) as usize);
+        let col_max: usize = usize::min(canvas.width(), coord.col + (style.size - style.size / 2) as usize);
 
-            let normalized: Point2d = self.points[i].clone() * skew;
+        camera_log!("Drawing point as rect {:?}", || MemExtents2D::new_from_usize(row_min, col_min, row_max, col_max));
 
-            if self.point_size == 1
+        for row in row_min..row_max
+        {
+            for col in col_min..col_max
             {
-                canvas[Self::point_into_index(normalized)] = color;
+                canvas[MemIndex2D::new(row, col)] = style.color;
+            }
+        }
+    }
+
+    fn draw_label(&self, label: &Label, canvas: &mut Vec2D<RGB>, skew: Matrix2D) {
+        if !self.view_port.contains_closed(&label.position, f32::EPSILON) {
+            return;
+        }
+
+        let screen: Point2d = label.position * skew;
+        let left: i64 = screen.x.floor() as i64 + LABEL_OFFSET;
+        let top: i64 = screen.y.floor() as i64 - LABEL_OFFSET - GLYPH_HEIGHT as i64;
+
+        Self::draw_text(canvas, &label.text, left, top, label.color);
+    }
+
+    //Inside a layer fills go down first, then outlines and lines, then points and labels
+    fn draw_layer(&self, layer: &Layer, canvas: &mut Vec2D<RGB>, skew: Matrix2D) {
+        camera_log!("Drawing layer {} with {} primitives", layer.name, layer.primitives.len());
+
+        for primitive in &layer.primitives {
+            if let Primitive::Polygon { polygon, style } = primitive {
+                self.fill_polygon(polygon, *style, canvas, skew);
+            }
+        }
+
+        for primitive in &layer.primitives {
+            match primitive {
+                Primitive::Polygon { polygon, style: FillStyle { stroke: Some(stroke), .. } } => {
+                    for line in polygon.lines() {
+                        self.draw_clipped_line(line, *stroke, LineStyle::default(), canvas, skew);
+                    }
+                }
+                Primitive::Line { line, color, style } => {
+                    self.draw_clipped_line(*line, *color, *style, canvas, skew);
+                }
+                _ => {}
+            }
+        }
+
+        for primitive in &layer.primitives {
+            match primitive {
+                Primitive::Point { point, style } => self.draw_point(*point, *style, canvas, skew),
+                Primitive::Label(label) => self.draw_label(label, canvas, skew),
+                _ => {}
+            }
+        }
+    }
+
+    fn is_layer_visible(&self, layer: &Layer) -> bool {
+        layer.visible && !self.hidden_layers.contains(&layer.name)
+    }
+
+    fn draw_layers(&self, canvas: &mut Vec2D<RGB>, skew: Matrix2D) {
+        for layer in self.scene.sorted_layers() {
+            if self.is_layer_visible(layer) {
+                self.draw_layer(layer, canvas, skew);
+            }
+        }
+    }
+
+    fn draw_text(canvas: &mut Vec2D<RGB>, text: &str, left: i64, top: i64, color: RGB) {
+        for (row, col) in text_pixels(text, 1) {
+            let row: i64 = top + row as i64;
+            let col: i64 = left + col as i64;
+
+            if row < 0 || col < 0 {
                 continue;
             }
 
-            let coord: MemIndex2D = Self::point_into_index(normalized);
-            let row_min: usize = coord.row.checked_sub((self.point_size / 2) as usize).unwrap_or(0);
-            let col_min: usize = coord.col.checked_sub((self.point_size / 2) as usize).unwrap_or(0);
+            let index: MemIndex2D = MemIndex2D::new(row as usize, col as usize);
+            if canvas.index2d_in_bounds(index) {
+                canvas[index] = color;
+            }
+        }
+    }
+
+    fn fill_screen_rect(canvas: &mut Vec2D<RGB>, left: i64, top: i64, width: i64, height: i64, color: RGBA) {
+        let row_min: usize = top.max(0) as usize;
+        let col_min: usize = left.max(0) as usize;
+        let row_max: usize = usize::min(canvas.height(), (top + height).max(0) as usize);
+        let col_max: usize = usize::min(canvas.width(), (left + width).max(0) as usize);
+
+        for row in row_min..row_max {
+            for col in col_min..col_max {
+                let index: MemIndex2D = MemIndex2D::new(row, col);
+                canvas[index] = color.blend_over(canvas[index]);
+            }
+        }
+    }
 
-            let row_max: usize = usize::min(canvas.height(), coord.row + (self.point_size - self.point_size / 2) as usize);
-            let col_max: usize = usize::min(canvas.width(), coord.col + (self.point_size - self.point_size / 2) as usize);
-        
-            camera_log!("Drawing point as rect {:?}", || MemExtents2D::new_from_usize(row_min, col_min, row_max, col_max));
+    //Legend sits in the top right corner, one swatch and line of text per entry
+    fn draw_legend(&self, canvas: &mut Vec2D<RGB>) {
+        let legend: &Vec<LegendEntry> = &self.scene.legend;
 
-            for row in row_min..row_max
-            {
-                for col in col_min..col_max
-                {
-                    canvas[MemIndex2D::new(row, col)] = color;
+        if legend.is_empty() {
+            return;
+        }
+
+        let text_width: i64 = legend
+            .iter()
+            .map(|e| text_size(&e.text, 1).0 as i64)
+            .max()
+            .unwrap_or(0);
+
+        let width: i64 = LEGEND_SWATCH + OVERLAY_MARGIN + text_width + 2 * OVERLAY_MARGIN;
+        let height: i64 = legend.len() as i64 * LINE_HEIGHT as i64 - 2 + 2 * OVERLAY_MARGIN;
+        let left: i64 = canvas.width() as i64 - width - OVERLAY_MARGIN;
+        let top: i64 = OVERLAY_MARGIN;
+
+        Self::fill_screen_rect(canvas, left, top, width, height, OVERLAY_BACKGROUND);
+
+        for (i, entry) in legend.iter().enumerate() {
+            let row: i64 = top + OVERLAY_MARGIN + i as i64 * LINE_HEIGHT as i64;
+            let col: i64 = left + OVERLAY_MARGIN;
+
+            Self::fill_screen_rect(canvas, col, row, LEGEND_SWATCH, LEGEND_SWATCH, entry.color.into());
+            Self::draw_text(canvas, &entry.text, col + LEGEND_SWATCH + OVERLAY_MARGIN, row, RGB::white());
+        }
+    }
+
+    //Scene notes go in a panel on the right, below the legend
+    fn draw_notes(&self, canvas: &mut Vec2D<RGB>) {
+        let notes: &Vec<String> = &self.scene.notes;
+
+        if notes.is_empty() {
+            return;
+        }
+
+        let top: i64 = match self.scene.legend.len() {
+            0 => OVERLAY_MARGIN,
+            n => n as i64 * LINE_HEIGHT as i64 - 2 + 4 * OVERLAY_MARGIN,
+        };
+
+        let text: String = notes.join("\n");
+        let (width, height) = text_size(&text, 1);
+        let left: i64 = canvas.width() as i64 - width as i64 - 3 * OVERLAY_MARGIN;
+
+        Self::fill_screen_rect(
+            canvas,
+            left,
+            top,
+            width as i64 + 2 * OVERLAY_MARGIN,
+            height as i64 + 2 * OVERLAY_MARGIN,
+            OVERLAY_BACKGROUND,
+        );
+
+        Self::draw_text(canvas, &text, left + OVERLAY_MARGIN, top + OVERLAY_MARGIN, RGB::white());
+    }
+
+    //Status lines from the scenario go below the viewer's own lines
+    fn draw_hud(&self, canvas: &mut Vec2D<RGB>) {
+        let lines: Vec<&str> = self.hud.iter().chain(self.status.iter()).map(|l| l.as_str()).collect();
+
+        if lines.is_empty() {
+            return;
+        }
+
+        let text: String = lines.join("\n");
+        let (width, height) = text_size(&text, 1);
+
+        Self::fill_screen_rect(
+            canvas,
+            OVERLAY_MARGIN,
+            OVERLAY_MARGIN,
+            width as i64 + 2 * OVERLAY_MARGIN,
+            height as i64 + 2 * OVERLAY_MARGIN,
+            OVERLAY_BACKGROUND,
+        );
+
+        Self::draw_text(canvas, &text, 2 * OVERLAY_MARGIN, 2 * OVERLAY_MARGIN, RGB::white());
+    }
+
+    //Centered over everything else, only while the help overlay is on
+    fn draw_help(&self, canvas: &mut Vec2D<RGB>) {
+        if !self.overlays.help || self.help.is_empty() {
+            return;
+        }
+
+        let text: String = self.help.join("\n");
+        let (width, height) = text_size(&text, 1);
+        let left: i64 = (canvas.width() as i64 - width as i64) / 2;
+        let top: i64 = (canvas.height() as i64 - height as i64) / 2;
+
+        Self::fill_screen_rect(
+            canvas,
+            left - OVERLAY_MARGIN,
+            top - OVERLAY_MARGIN,
+            width as i64 + 2 * OVERLAY_MARGIN,
+            height as i64 + 2 * OVERLAY_MARGIN,
+            OVERLAY_BACKGROUND,
+        );
+
+        Self::draw_text(canvas, &text, left, top, RGB::white());
+    }
+
+    fn major_grid_step(&self) -> Option<f32> {
+        nice_step(f32::max(self.view_port.width(), self.view_port.height()), MAJOR_DIVISIONS)
+    }
+
+    fn draw_grid_lines(&self, canvas: &mut Vec2D<RGB>, skew: Matrix2D, step: f32, color: RGB) {
+        let view: Rectangle2D = self.view_port;
+
+        for x in grid_values(view.min.x, view.max.x, step) {
+            self.draw_clipped_line(Line2D::new_flat(x, view.min.y, x, view.max.y), color, LineStyle::default(), canvas, skew);
+        }
+
+        for y in grid_values(view.min.y, view.max.y, step) {
+            self.draw_clipped_line(Line2D::new_flat(view.min.x, y, view.max.x, y), color, LineStyle::default(), canvas, skew);
+        }
+    }
+
+    fn draw_integer_lattice(&self, canvas: &mut Vec2D<RGB>, transform: &ViewTransform) {
+        let spacing: f32 = transform.scale().x;
+
+        if spacing < LATTICE_MIN_PIXELS {
+            return;
+        }
+
+        //a small cross once there is room, a single pixel otherwise
+        let arm: i64 = match spacing >= 12f32 {
+            true => 1,
+            false => 0,
+        };
+
+        let skew: Matrix2D = transform.matrix();
+        let view: Rectangle2D = self.view_port;
+
+        for x in grid_values(view.min.x, view.max.x, 1f32) {
+            for y in grid_values(view.min.y, view.max.y, 1f32) {
+                let screen: Point2d = Point2d::new(x, y) * skew;
+                let row: i64 = screen.y.floor() as i64;
+                let col: i64 = screen.x.floor() as i64;
+
+                Self::fill_screen_rect(canvas, col - arm, row, 2 * arm + 1, 1, LATTICE_COLOR.into());
+                Self::fill_screen_rect(canvas, col, row - arm, 1, 2 * arm + 1, LATTICE_COLOR.into());
+            }
+        }
+    }
+
+    //Grid, axes and lattice sit underneath all of the geometry
+    fn draw_background_overlays(&self, canvas: &mut Vec2D<RGB>, transform: &ViewTransform) {
+        let skew: Matrix2D = transform.matrix();
+        let view: Rectangle2D = self.view_port;
+
+        if self.overlays.grid {
+            if let Some(major) = self.major_grid_step() {
+                self.draw_grid_lines(canvas, skew, major / MINOR_PER_MAJOR, MINOR_GRID_COLOR);
+                self.draw_grid_lines(canvas, skew, major, MAJOR_GRID_COLOR);
+            }
+        }
+
+        if self.overlays.integer_lattice {
+            self.draw_integer_lattice(canvas, transform);
+        }
+
+        if self.overlays.axes {
+            if view.min.x <= 0f32 && 0f32 <= view.max.x {
+                self.draw_clipped_line(Line2D::new_flat(0f32, view.min.y, 0f32, view.max.y), AXES_COLOR, LineStyle::default(), canvas, skew);
+            }
+
+            if view.min.y <= 0f32 && 0f32 <= view.max.y {
+                self.draw_clipped_line(Line2D::new_flat(view.min.x, 0f32, view.max.x, 0f32), AXES_COLOR, LineStyle::default(), canvas, skew);
+            }
+        }
+    }
+
+    //X ticks along the bottom of the world area, y ticks along its left edge
+    fn draw_tick_labels(&self, canvas: &mut Vec2D<RGB>, transform: &ViewTransform) {
+        let step: f32 = match self.major_grid_step() {
+            Some(s) => s,
+            None => {
+                return;
+            }
+        };
+
+        let skew: Matrix2D = transform.matrix();
+        let area: Rectangle2D = transform.screen_area();
+        let view: Rectangle2D = self.view_port;
+
+        for x in grid_values(view.min.x, view.max.x, step) {
+            let screen: Point2d = Point2d::new(x, view.min.y) * skew;
+            let top: i64 = area.max.y.floor() as i64 - GLYPH_HEIGHT as i64 - 2;
+
+            Self::draw_text(canvas, &format_tick(x, step), screen.x.floor() as i64 + 2, top, TICK_LABEL_COLOR);
+        }
+
+        for y in grid_values(view.min.y, view.max.y, step) {
+            let screen: Point2d = Point2d::new(view.min.x, y) * skew;
+            let top: i64 = screen.y.floor() as i64 - GLYPH_HEIGHT as i64 - 1;
+
+            Self::draw_text(canvas, &format_tick(y, step), area.min.x.floor() as i64 + 2, top, TICK_LABEL_COLOR);
+        }
+    }
+
+    fn draw_scale_bar(&self, canvas: &mut Vec2D<RGB>, transform: &ViewTransform) {
+        let pixels_per_unit: f32 = transform.scale().x;
+
+        let length: f32 = match nice_floor(SCALE_BAR_PIXELS / pixels_per_unit) {
+            Some(l) => l,
+            None => {
+                return;
+            }
+        };
+
+        let bar_pixels: f32 = length * pixels_per_unit;
+        let area: Rectangle2D = transform.screen_area();
+        let right: f32 = area.max.x - 3f32 * OVERLAY_MARGIN as f32;
+        let left: f32 = right - bar_pixels;
+        let y: f32 = area.max.y - 4f32 * OVERLAY_MARGIN as f32;
+
+        let style: LineStyle = LineStyle::solid().with_width(2f32);
+        self.stroke_line(Line2D::new_flat(left, y, right, y), RGB::white(), style, canvas);
+        self.stroke_line(Line2D::new_flat(left, y - 3f32, left, y + 3f32), RGB::white(), LineStyle::default(), canvas);
+        self.stroke_line(Line2D::new_flat(right, y - 3f32, right, y + 3f32), RGB::white(), LineStyle::default(), canvas);
+
+        let text: String = format_tick(length, length);
+        Self::draw_text(canvas, &text, left as i64, y as i64 - GLYPH_HEIGHT as i64 - OVERLAY_MARGIN, RGB::white());
+    }
+
+    pub fn view_transform(&self, width: usize, height: usize) -> ViewTransform {
+        ViewTransform::new(self.view_port, width, height, self.y_axis, self.preserve_aspect)
+    }
+
+    pub fn world_to_screen(&self, width: usize, height: usize) -> Matrix2D {
+        self.view_transform(width, height).matrix()
+    }
+
+    pub fn screen_to_world(&self, screen: Point2d, width: usize, height: usize) -> Option<Point2d> {
+        self.world_to_screen(width, height)
+            .inverse()
+            .map(|inverse| screen * inverse)
+    }
+
+    //Bounding box of everything pushed since the last clear
+    pub fn bounds(&self) -> Option<Rectangle2D> {
+        self.scene.bounds()
+    }
+
+    //Scale the view port by factor keeping anchor at the same place on screen, factor < 1 zooms in
+    pub fn zoom_about(&mut self, anchor: Point2d, factor: f32) {
+        if !factor.is_finite() || factor <= 0f32 {
+            return;
+        }
+
+        let min: Point2d = self.view_port.min;
+        let max: Point2d = self.view_port.max;
+
+        self.view_port = Rectangle2D::new(
+            Point2d::new(anchor.x - (anchor.x - min.x) * factor, anchor.y - (anchor.y - min.y) * factor),
+            Point2d::new(anchor.x + (max.x - anchor.x) * factor, anchor.y + (max.y - anchor.y) * factor),
+        );
+    }
+
+    pub fn pan(&mut self, offset: Vector2D) {
+        self.view_port = Rectangle2D::new(
+            offset.translate_point(self.view_port.min),
+            offset.translate_point(self.view_port.max),
+        );
+    }
+
+    //Rows and columns of the canvas the view port covers, the rest is letterbox
+    fn screen_pixels(canvas: &Vec2D<RGB>, transform: &ViewTransform) -> (Range<usize>, Range<usize>) {
+        let area: Rectangle2D = transform.screen_area();
+
+        let col_min: usize = area.min.x.round().max(0f32) as usize;
+        let row_min: usize = area.min.y.round().max(0f32) as usize;
+        let col_max: usize = usize::min(canvas.width(), area.max.x.round().max(0f32) as usize);
+        let row_max: usize = usize::min(canvas.height(), area.max.y.round().max(0f32) as usize);
+
+        (row_min..row_max, col_min..col_max)
+    }
+
+    fn draw_letterbox(&self, canvas: &mut Vec2D<RGB>, transform: &ViewTransform) {
+        let (rows, cols): (Range<usize>, Range<usize>) = Self::screen_pixels(canvas, transform);
+
+        for row in 0..canvas.height() {
+            for col in 0..canvas.width() {
+                if rows.contains(&row) && cols.contains(&col) {
+                    continue;
                 }
+
+                canvas[MemIndex2D::new(row, col)] = LETTERBOX_COLOR;
             }
         }
     }
 
     pub fn draw(&self, canvas: &mut Vec2D<RGB>) {
         camera_log!("Starting draw");
-        
-        let scale: Vector2D = Vector2D {
-            x: canvas.width() as f32 / self.view_port.width(),
-            y: canvas.height() as f32 / self.view_port.height(),
-        };
 
-        let translate: Vector2D = Vector2D::from(self.view_port.min);
-
-        let skew: Matrix2D = Matrix2D::skew(translate, scale);
+        let transform: ViewTransform = self.view_transform(canvas.width(), canvas.height());
+        let skew: Matrix2D = transform.matrix();
 
         camera_log!("Skew, {:?}", || skew);
 
-        self.draw_lines(canvas, skew);
-        self.draw_points(canvas, skew);
+        if self.preserve_aspect {
+            self.draw_letterbox(canvas, &transform);
+        }
+
+        self.draw_background_overlays(canvas, &transform);
+        self.draw_layers(canvas, skew);
+
+        if self.overlays.tick_labels {
+            self.draw_tick_labels(canvas, &transform);
+        }
+
+        if self.overlays.scale_bar {
+            self.draw_scale_bar(canvas, &transform);
+        }
+
+        self.draw_legend(canvas);
+        self.draw_notes(canvas);
+        self.draw_hud(canvas);
+        self.draw_help(canvas);
     }
 
-    pub fn set_point_size(&mut self, point_size: u8)
+    pub fn set_y_axis(&mut self, y_axis: YAxis)
     {
-        self.point_size = point_size;
+        self.y_axis = y_axis;
+    }
+
+    //When false the view port is stretched over the whole canvas
+    pub fn set_preserve_aspect(&mut self, preserve_aspect: bool)
+    {
+        self.preserve_aspect = preserve_aspect;
+    }
+
+    pub fn scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    //Layer with the given name, created on top of the others when missing
+    pub fn layer(&mut self, name: &str) -> &mut Layer {
+        self.scene.layer(name)
+    }
+
+    pub fn push_scene(&mut self, scene: Scene) {
+        self.scene.merge(scene);
+    }
+
+    //Layer names bottom to top, the first one is toggled by key 1
+    pub fn layer_names(&self) -> Vec<String> {
+        self.scene.sorted_layers().iter().map(|l| l.name.clone()).collect()
+    }
+
+    pub fn is_layer_hidden(&self, name: &str) -> bool {
+        self.hidden_layers.iter().any(|n| n == name)
+    }
+
+    //Returns false when there is no layer at index
+    pub fn toggle_layer(&mut self, index: usize) -> bool {
+        let name: String = match self.layer_names().into_iter().nth(index) {
+            Some(n) => n,
+            None => {
+                return false;
+            }
+        };
+
+        match self.hidden_layers.iter().position(|n| *n == name) {
+            Some(i) => {
+                self.hidden_layers.remove(i);
+            }
+            None => self.hidden_layers.push(name),
+        }
+
+        true
     }
 
     pub fn push_polygon(&mut self, polygon: Polygon2D) {
-        for line in polygon.lines() {
-            self.push_line(line);
+        self.scene.default_layer().push_polygon(&polygon, DEFAULT_LINE_COLOR);
+    }
+
+    pub fn push_filled_polygon(&mut self, polygon: Polygon2D, fill: RGBA, stroke: Option<RGB>) {
+        self.scene.push_filled_polygon(polygon, fill, stroke);
+    }
+
+    pub fn push_filled_polygons<T>(&mut self, polygons: T)
+    where
+        T: IntoIterator<Item = (Polygon2D, RGBA, Option<RGB>)>,
+    {
+        for (polygon, fill, stroke) in polygons {
+            self.push_filled_polygon(polygon, fill, stroke);
         }
     }
 
     pub fn push_line(&mut self, line: Line2D) {
-        self.lines.push(line);
+        self.scene.push_line(line);
     }
 
     pub fn push_point(&mut self, point: Point2d) {
-        self.points.push(point);
+        self.scene.push_point(point);
     }
 
-    pub fn set_point_color(&mut self, color: RGB) {
-        for _ in 0..self.points.len() {
-            self.point_colors.push(color.clone());
-        }
+    pub fn push_point_styled(&mut self, point: Point2d, style: PointStyle) {
+        self.scene.default_layer().push_point_styled(point, style);
     }
 
     pub fn push_points<T>(&mut self, points: T)
     where
         T: IntoIterator<Item = Point2d>,
     {
-        for point in points {
-            self.points.push(point);
-        }
+        self.scene.push_points(points);
     }
 
-    pub fn set_point_colors(&mut self, point_colors: Vec<RGB>)
+    pub fn push_points_styled<T>(&mut self, points: T, style: PointStyle)
+    where
+        T: IntoIterator<Item = Point2d>,
     {
-        self.point_colors = point_colors;
+        self.scene.default_layer().push_points_styled(points, style);
     }
 
     pub fn push_point_colors<T>(&mut self, points_colors: T)
     where
         T: IntoIterator<Item = (Point2d, RGB)>,
     {
-        for (point, color) in points_colors {
-            self.points.push(point);
-            self.point_colors.push(color);
-        }
+        self.scene.push_point_colors(points_colors);
     }
 
     pub fn push_lines<T>(&mut self, lines: T)
     where
         T: IntoIterator<Item = Line2D>,
     {
-        for line in lines {
-            self.lines.push(line);
-        }
+        self.scene.push_lines(lines);
     }
 
-    pub fn push_line_color(&mut self, line_color: RGB) {
-        self.line_colors.push(line_color);
+    pub fn push_label(&mut self, label: Label) {
+        self.scene.push_label(label);
+    }
+
+    pub fn push_labels<T>(&mut self, labels: T)
+    where
+        T: IntoIterator<Item = Label>,
+    {
+        self.scene.default_layer().push_labels(labels);
+    }
+
+    pub fn push_legend_entry(&mut self, entry: LegendEntry) {
+        self.scene.push_legend_entry(entry);
+    }
+
+    //Screen space text in the top left corner, replaced every time it is set
+    pub fn set_hud(&mut self, lines: Vec<String>) {
+        self.hud = lines;
+    }
+
+    //Lines of the help overlay, drawn when overlays.help is on
+    pub fn set_help(&mut self, lines: Vec<String>) {
+        self.help = lines;
+    }
+
+    //Scenario text shown under the HUD, kept until the next clear
+    pub fn push_status(&mut self, line: String) {
+        self.status.push(line);
+    }
+
+    pub fn push_line_styled(&mut self, line: Line2D, color: RGB, style: LineStyle) {
+        self.scene.push_line_styled(line, color, style);
     }
 
     pub fn push_lines_color<T>(&mut self, lines_colors: T)
     where
         T: IntoIterator<Item = (Line2D, RGB)>,
     {
-        for (line, color) in lines_colors {
-            self.lines.push(line);
-            self.line_colors.push(color);
-        }
+        self.scene.push_lines_color(lines_colors);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_stays_off_letterbox() {
+        //a square view port on a wide canvas leaves five columns of letterbox on each side
+        let mut camera: Camera = Camera::new(10f32, 10f32);
+        let fill: RGBA = RGBA::new(255, 0, 0, 255);
+        camera.push_filled_polygon(
+            Polygon2D::new(vec![
+                Point2d::new(-10f32, -10f32),
+                Point2d::new(20f32, -10f32),
+                Point2d::new(20f32, 20f32),
+                Point2d::new(-10f32, 20f32),
+            ]),
+            fill,
+            None,
+        );
+
+        let mut canvas: Vec2D<RGB> = Vec2D::new_from_flatpack(vec![RGB::black(); 20 * 10], 20, 10).unwrap();
+        camera.draw(&mut canvas);
+
+        assert_eq!(LETTERBOX_COLOR, canvas[MemIndex2D::new(4, 2)]);
+        assert_eq!(LETTERBOX_COLOR, canvas[MemIndex2D::new(4, 17)]);
+        assert_eq!(RGB::new(255, 0, 0), canvas[MemIndex2D::new(4, 8)]);
     }
 }
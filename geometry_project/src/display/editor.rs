@@ -0,0 +1,358 @@
+use crate::{
+    display::input::{InputFrame, Key, MouseButton},
+    entities::{line2d::Line2D, point2d::Point2d},
+};
+
+//How close the cursor has to be to grab something, in screen pixels
+pub const PICK_PIXELS: f32 = 6f32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Drag {
+    Point(usize),
+    Endpoint { line: usize, end: usize },
+    //A segment being drawn, dropped on release when it is too short
+    NewSegment(usize),
+}
+
+//Mouse editing of scenario input. Left click adds a point, left drag moves
+//one and right click deletes it. For segments left drag from empty space draws
+//a new one, dragging an endpoint moves it and right click deletes a segment.
+//Space + left drag is left to the view controller for panning
+pub struct Editor {
+    drag: Option<Drag>,
+    snap: bool,
+}
+
+fn distance_to_segment(line: &Line2D, point: Point2d) -> f32 {
+    let dx: f32 = line.end.x - line.start.x;
+    let dy: f32 = line.end.y - line.start.y;
+    let len_squared: f32 = dx * dx + dy * dy;
+
+    let t: f32 = match len_squared > 0f32 {
+        true => (((point.x - line.start.x) * dx + (point.y - line.start.y) * dy) / len_squared).clamp(0f32, 1f32),
+        false => 0f32,
+    };
+
+    (point - Point2d::new(line.start.x + t * dx, line.start.y + t * dy)).len()
+}
+
+//Index of the closest candidate within radius
+fn nearest<I: Iterator<Item = f32>>(distances: I, radius: f32) -> Option<usize> {
+    distances
+        .enumerate()
+        .filter(|(_, d)| *d <= radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Editor::new()
+    }
+}
+
+impl Editor {
+    pub fn new() -> Self {
+        Editor {
+            drag: None,
+            snap: false,
+        }
+    }
+
+    //Round edited coordinates to integers, for scenarios on the integer grid
+    pub fn with_snap(mut self, snap: bool) -> Self {
+        self.snap = snap;
+        self
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    //Scenarios call this when they replace what is being edited, the index
+    //being dragged means nothing in the new points or segments
+    pub fn cancel(&mut self) {
+        self.drag = None;
+    }
+
+    fn cursor(&self, input: &InputFrame) -> Option<(Point2d, f32)> {
+        if input.is_key_down(Key::Space) {
+            return None;
+        }
+
+        let world: Point2d = input.mouse_world()?;
+        let radius: f32 = PICK_PIXELS * input.world_per_pixel().unwrap_or(1f32);
+
+        match self.snap {
+            true => Some((Point2d::new(world.x.round(), world.y.round()), radius)),
+            false => Some((world, radius)),
+        }
+    }
+
+    //Returns true when the points changed
+    pub fn edit_points(&mut self, input: &InputFrame, points: &mut Vec<Point2d>) -> bool {
+        if input.is_mouse_released(MouseButton::Left) {
+            self.drag = None;
+        }
+
+        let (cursor, radius) = match self.cursor(input) {
+            Some(c) => c,
+            None => {
+                return false;
+            }
+        };
+
+        let picked: Option<usize> = nearest(points.iter().map(|p| (*p - cursor).len()), radius);
+
+        if input.is_mouse_pressed(MouseButton::Right) {
+            if let Some(i) = picked {
+                points.remove(i);
+                self.drag = None;
+                return true;
+            }
+        }
+
+        if input.is_mouse_pressed(MouseButton::Left) {
+            match picked {
+                Some(i) => self.drag = Some(Drag::Point(i)),
+                None => {
+                    points.push(cursor);
+                    self.drag = Some(Drag::Point(points.len() - 1));
+                    return true;
+                }
+            }
+        }
+
+        let i: usize = match self.drag {
+            Some(Drag::Point(i)) if input.is_mouse_down(MouseButton::Left) => i,
+            _ => {
+                return false;
+            }
+        };
+
+        //the points can have been replaced while dragging
+        match points.get_mut(i) {
+            Some(point) if *point != cursor => {
+                *point = cursor;
+                true
+            }
+            Some(_) => false,
+            None => {
+                self.drag = None;
+                false
+            }
+        }
+    }
+
+    //Returns true when the segments changed
+    pub fn edit_segments(&mut self, input: &InputFrame, lines: &mut Vec<Line2D>) -> bool {
+        let mut changed: bool = false;
+
+        if input.is_mouse_released(MouseButton::Left) {
+            if let Some(Drag::NewSegment(i)) = self.drag {
+                let radius: f32 = PICK_PIXELS * input.world_per_pixel().unwrap_or(1f32);
+
+                if lines.get(i).is_some_and(|l| l.len() < radius) {
+                    lines.remove(i);
+                    changed = true;
+                }
+            }
+
+            self.drag = None;
+        }
+
+        let (cursor, radius) = match self.cursor(input) {
+            Some(c) => c,
+            None => {
+                return changed;
+            }
+        };
+
+        if input.is_mouse_pressed(MouseButton::Right) {
+            if let Some(i) = nearest(lines.iter().map(|l| distance_to_segment(l, cursor)), radius) {
+                lines.remove(i);
+                self.drag = None;
+                return true;
+            }
+        }
+
+        if input.is_mouse_pressed(MouseButton::Left) {
+            let endpoints = lines.iter().flat_map(|l| [(l.start - cursor).len(), (l.end - cursor).len()]);
+
+            self.drag = match nearest(endpoints, radius) {
+                Some(i) => Some(Drag::Endpoint { line: i / 2, end: i % 2 }),
+                None => {
+                    lines.push(Line2D::new(cursor, cursor));
+                    changed = true;
+                    Some(Drag::NewSegment(lines.len() - 1))
+                }
+            };
+        }
+
+        if !input.is_mouse_down(MouseButton::Left) {
+            return changed;
+        }
+
+        let (line, start) = match self.drag {
+            Some(Drag::Endpoint { line, end }) => (line, end == 0),
+            Some(Drag::NewSegment(line)) => (line, false),
+            _ => {
+                return changed;
+            }
+        };
+
+        //the segments can have been replaced while dragging
+        let segment: &mut Line2D = match lines.get_mut(line) {
+            Some(l) => l,
+            None => {
+                self.drag = None;
+                return changed;
+            }
+        };
+
+        let end: &mut Point2d = match start {
+            true => &mut segment.start,
+            false => &mut segment.end,
+        };
+
+        if *end != cursor {
+            *end = cursor;
+            changed = true;
+        }
+
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::{
+        camera::Camera,
+        input::{InputEvent, InputState},
+    };
+
+    //A 100x100 camera on a 100x100 screen, world y grows upwards
+    struct Mouse {
+        state: InputState,
+        camera: Camera,
+    }
+
+    impl Mouse {
+        fn new() -> Self {
+            Mouse {
+                state: InputState::new(),
+                camera: Camera::new(100f32, 100f32),
+            }
+        }
+
+        fn frame(&mut self, events: Vec<InputEvent>) -> InputFrame {
+            let mut frame: InputFrame = self.state.next_frame(events);
+            frame.locate(&self.camera, 100, 100);
+            frame
+        }
+
+        fn at(&mut self, x: f32, y: f32, button: Option<InputEvent>) -> InputFrame {
+            let mut events: Vec<InputEvent> = vec![InputEvent::MouseMoved(Point2d::new(x, 100f32 - y))];
+            events.extend(button);
+            self.frame(events)
+        }
+    }
+
+    fn close(a: Point2d, b: Point2d) -> bool {
+        a.approx_equals(&b, 1f32)
+    }
+
+    #[test]
+    fn test_add_move_delete_point() {
+        let mut mouse: Mouse = Mouse::new();
+        let mut editor: Editor = Editor::new();
+        let mut points: Vec<Point2d> = Vec::new();
+
+        assert!(editor.edit_points(&mouse.at(20f32, 20f32, Some(InputEvent::MousePressed(MouseButton::Left))), &mut points));
+        editor.edit_points(&mouse.frame(vec![InputEvent::MouseReleased(MouseButton::Left)]), &mut points);
+        assert_eq!(1, points.len());
+        assert!(close(Point2d::new(20f32, 20f32), points[0]));
+
+        editor.edit_points(&mouse.at(21f32, 21f32, Some(InputEvent::MousePressed(MouseButton::Left))), &mut points);
+        assert!(editor.edit_points(&mouse.at(60f32, 40f32, None), &mut points));
+        editor.edit_points(&mouse.frame(vec![InputEvent::MouseReleased(MouseButton::Left)]), &mut points);
+        assert_eq!(1, points.len());
+        assert!(close(Point2d::new(60f32, 40f32), points[0]));
+
+        assert!(!editor.edit_points(&mouse.at(10f32, 90f32, Some(InputEvent::MousePressed(MouseButton::Right))), &mut points));
+        assert!(editor.edit_points(&mouse.at(60f32, 40f32, Some(InputEvent::MousePressed(MouseButton::Right))), &mut points));
+        assert!(points.is_empty());
+    }
+
+    #[test]
+    fn test_draw_and_delete_segment() {
+        let mut mouse: Mouse = Mouse::new();
+        let mut editor: Editor = Editor::new().with_snap(true);
+        let mut lines: Vec<Line2D> = Vec::new();
+
+        editor.edit_segments(&mouse.at(10f32, 10f32, Some(InputEvent::MousePressed(MouseButton::Left))), &mut lines);
+        editor.edit_segments(&mouse.at(50f32, 30f32, None), &mut lines);
+        editor.edit_segments(&mouse.frame(vec![InputEvent::MouseReleased(MouseButton::Left)]), &mut lines);
+
+        assert_eq!(1, lines.len());
+        assert_eq!(lines[0].start.x, lines[0].start.x.round());
+        assert!(close(Point2d::new(50f32, 30f32), lines[0].end));
+
+        //grab the end and move it
+        editor.edit_segments(&mouse.at(50f32, 31f32, Some(InputEvent::MousePressed(MouseButton::Left))), &mut lines);
+        editor.edit_segments(&mouse.at(70f32, 70f32, None), &mut lines);
+        editor.edit_segments(&mouse.frame(vec![InputEvent::MouseReleased(MouseButton::Left)]), &mut lines);
+        assert_eq!(1, lines.len());
+        assert!(close(Point2d::new(70f32, 70f32), lines[0].end));
+
+        assert!(editor.edit_segments(&mouse.at(40f32, 40f32, Some(InputEvent::MousePressed(MouseButton::Right))), &mut lines));
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn test_click_without_drag_adds_no_segment() {
+        let mut mouse: Mouse = Mouse::new();
+        let mut editor: Editor = Editor::new();
+        let mut lines: Vec<Line2D> = Vec::new();
+
+        editor.edit_segments(&mouse.at(10f32, 10f32, Some(InputEvent::MousePressed(MouseButton::Left))), &mut lines);
+        editor.edit_segments(&mouse.frame(vec![InputEvent::MouseReleased(MouseButton::Left)]), &mut lines);
+
+        assert!(lines.is_empty());
+        assert!(!editor.is_dragging());
+    }
+
+    #[test]
+    fn test_replaced_while_dragging() {
+        let mut mouse: Mouse = Mouse::new();
+        let mut editor: Editor = Editor::new();
+        let mut points: Vec<Point2d> = vec![Point2d::new(10f32, 10f32), Point2d::new(20f32, 20f32)];
+
+        editor.edit_points(&mouse.at(20f32, 20f32, Some(InputEvent::MousePressed(MouseButton::Left))), &mut points);
+        points.truncate(1);
+
+        assert!(!editor.edit_points(&mouse.at(40f32, 40f32, None), &mut points));
+        assert!(!editor.is_dragging());
+
+        let mut lines: Vec<Line2D> = Vec::new();
+        assert!(editor.edit_segments(&mouse.at(10f32, 10f32, Some(InputEvent::MousePressed(MouseButton::Left))), &mut lines));
+        lines.clear();
+
+        assert!(!editor.edit_segments(&mouse.at(50f32, 50f32, None), &mut lines));
+        editor.edit_segments(&mouse.frame(vec![InputEvent::MouseReleased(MouseButton::Left)]), &mut lines);
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn test_space_is_left_for_panning() {
+        let mut mouse: Mouse = Mouse::new();
+        let mut editor: Editor = Editor::new();
+        let mut points: Vec<Point2d> = Vec::new();
+
+        mouse.frame(vec![InputEvent::KeyPressed { key: Key::Space, repeat: false }]);
+        editor.edit_points(&mouse.at(20f32, 20f32, Some(InputEvent::MousePressed(MouseButton::Left))), &mut points);
+
+        assert!(points.is_empty());
+    }
+}
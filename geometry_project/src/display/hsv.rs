@@ -1,5 +1,6 @@
-use crate::{display::rgb::RGB, entities::angle::Angle};
+use crate::{algorithms::random_geometry::Random2D, display::rgb::RGB, entities::angle::Angle};
 
+#[derive(Clone, Copy, Debug)]
 pub struct HSV
 {
     pub hue: Angle,
@@ -10,6 +11,18 @@ pub struct HSV
 pub const PHI: f64 = 1.618033988749894848204586834365638118_f64; //golden ratio
 pub const GOLDEN_CONJUGATE: f64 = 0.618033988749895_f64;
 
+impl From<RGB> for HSV {
+    fn from(value: RGB) -> Self {
+        HSV::from_rgb(value)
+    }
+}
+
+impl From<HSV> for RGB {
+    fn from(value: HSV) -> Self {
+        value.to_rgb()
+    }
+}
+
 impl HSV {
 
     pub fn new(hue: Angle, saturation: f32, value: f32) -> Self
@@ -17,6 +30,34 @@ impl HSV {
         HSV { hue, saturation, value }
     }
 
+    pub fn from_rgb(rgb: RGB) -> Self {
+        let (r, g, b) = rgb.channels();
+        let r: f32 = r as f32 / 255.0;
+        let g: f32 = g as f32 / 255.0;
+        let b: f32 = b as f32 / 255.0;
+
+        let max: f32 = r.max(g).max(b);
+        let min: f32 = r.min(g).min(b);
+        let delta: f32 = max - min;
+
+        let hue_degrees: f32 = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        let saturation: f32 = match max == 0.0 {
+            true => 0.0,
+            false => delta / max,
+        };
+
+        HSV::new(Angle::from_degrees(hue_degrees as f64), saturation, max)
+    }
+
     pub fn to_rgb(&self) -> RGB {
         let h = self.hue.degrees() % 360.0;
         let s = self.saturation.clamp(0.0, 1.0);
@@ -43,7 +84,7 @@ impl HSV {
     }
 
     pub fn random_color(saturation: f32, value: f32) -> HSV {
-        let hue =  Angle(rand::random_range(0f64..2f64));
+        let hue =  Angle(Random2D::random_range(0f64..2f64));
         HSV 
         {
             hue,
@@ -53,7 +94,7 @@ impl HSV {
     }
 
     pub fn random_colors(count: usize, saturation: f32, value: f32) -> Vec<HSV> {
-        let mut hue = Angle(rand::random_range(0f64..2f64));
+        let mut hue = Angle(Random2D::random_range(0f64..2f64));
         let mut colors: Vec<HSV> = Vec::with_capacity(count);
 
         for _ in 0..count
@@ -80,4 +121,22 @@ mod tests {
         assert_eq!(rgb, hsv.to_rgb());
     }
 
+    #[test]
+    fn rgb_to_hsv_1() {
+        let hsv: HSV = HSV::from_rgb(RGB::new(204, 54, 41));
+
+        assert!((hsv.hue.degrees() - 5f64).abs() < 0.5, "hue was {}", hsv.hue.degrees());
+        assert!((hsv.saturation - 0.80).abs() < 0.01);
+        assert!((hsv.value - 0.80).abs() < 0.01);
+    }
+
+    #[test]
+    fn rgb_hsv_round_trip() {
+        let colors: [RGB; 4] = [RGB::red(), RGB::green(), RGB::new(12, 200, 240), RGB::new(90, 90, 90)];
+
+        for color in colors {
+            assert_eq!(color, HSV::from_rgb(color).to_rgb());
+        }
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{display::camera::Camera, entities::point2d::Point2d};
+
+//Window independent input. The window loop translates its events into these,
+//tests and scripts build them directly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Key {
+    A, B, C, D, E, F, G, H, I, J, K, L, M,
+    N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    Left, Right, Up, Down,
+    Home, End, PageUp, PageDown,
+    Enter, Tab, Space, Escape, Backspace, Delete,
+    LeftShift, RightShift, LeftCtrl, RightCtrl,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyRepeat {
+    Yes,
+    No,
+}
+
+//Mouse positions are in screen pixels, the wheel is positive away from the user
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    KeyPressed { key: Key, repeat: bool },
+    KeyReleased(Key),
+    MouseMoved(Point2d),
+    MouseLeft,
+    MousePressed(MouseButton),
+    MouseReleased(MouseButton),
+    Wheel(f32),
+}
+
+//What is held down between frames, events of the next frame are applied to it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputState {
+    keys_down: Vec<Key>,
+    buttons_down: Vec<MouseButton>,
+    mouse: Option<Point2d>,
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        InputState::default()
+    }
+
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    pub fn is_mouse_down(&self, button: MouseButton) -> bool {
+        self.buttons_down.contains(&button)
+    }
+
+    pub fn mouse_pos(&self) -> Option<Point2d> {
+        self.mouse
+    }
+
+    fn apply(&mut self, event: &InputEvent) {
+        match *event {
+            InputEvent::KeyPressed { key, .. } => {
+                if !self.keys_down.contains(&key) {
+                    self.keys_down.push(key);
+                }
+            }
+            InputEvent::KeyReleased(key) => self.keys_down.retain(|k| *k != key),
+            InputEvent::MouseMoved(p) => self.mouse = Some(p),
+            InputEvent::MouseLeft => self.mouse = None,
+            InputEvent::MousePressed(button) => {
+                if !self.buttons_down.contains(&button) {
+                    self.buttons_down.push(button);
+                }
+            }
+            InputEvent::MouseReleased(button) => self.buttons_down.retain(|b| *b != button),
+            InputEvent::Wheel(_) => {}
+        }
+    }
+
+    pub fn next_frame(&mut self, events: Vec<InputEvent>) -> InputFrame {
+        for event in &events {
+            self.apply(event);
+        }
+
+        InputFrame {
+            events,
+            state: self.clone(),
+            mouse_world: None,
+            world_per_pixel: None,
+        }
+    }
+}
+
+//Everything that happened since the last frame plus what is still held down
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputFrame {
+    events: Vec<InputEvent>,
+    state: InputState,
+    mouse_world: Option<Point2d>,
+    world_per_pixel: Option<f32>,
+}
+
+impl InputFrame {
+    pub fn events(&self) -> &[InputEvent] {
+        &self.events
+    }
+
+    //Repeats only count with KeyRepeat::Yes, like minifb's is_key_pressed
+    pub fn is_key_pressed(&self, key: Key, repeat: KeyRepeat) -> bool {
+        self.events.iter().any(|e| match *e {
+            InputEvent::KeyPressed { key: k, repeat: r } => k == key && (!r || repeat == KeyRepeat::Yes),
+            _ => false,
+        })
+    }
+
+    pub fn is_key_released(&self, key: Key) -> bool {
+        self.events.contains(&InputEvent::KeyReleased(key))
+    }
+
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.state.is_key_down(key)
+    }
+
+    pub fn is_mouse_pressed(&self, button: MouseButton) -> bool {
+        self.events.contains(&InputEvent::MousePressed(button))
+    }
+
+    pub fn is_mouse_released(&self, button: MouseButton) -> bool {
+        self.events.contains(&InputEvent::MouseReleased(button))
+    }
+
+    pub fn is_mouse_down(&self, button: MouseButton) -> bool {
+        self.state.is_mouse_down(button)
+    }
+
+    //Screen position, None while the cursor is outside the window
+    pub fn mouse_pos(&self) -> Option<Point2d> {
+        self.state.mouse_pos()
+    }
+
+    //World position, only known once the frame has been located
+    pub fn mouse_world(&self) -> Option<Point2d> {
+        self.mouse_world
+    }
+
+    //How far one screen pixel is in the world, for pick distances given in pixels
+    pub fn world_per_pixel(&self) -> Option<f32> {
+        self.world_per_pixel
+    }
+
+    pub fn scroll(&self) -> f32 {
+        self.events
+            .iter()
+            .map(|e| match e {
+                InputEvent::Wheel(delta) => *delta,
+                _ => 0f32,
+            })
+            .sum()
+    }
+
+    //Maps the cursor through the camera the scene is drawn with
+    pub fn locate(&mut self, camera: &Camera, width: usize, height: usize) {
+        self.mouse_world = self
+            .mouse_pos()
+            .and_then(|screen| camera.screen_to_world(screen, width, height));
+
+        let origin: Option<Point2d> = camera.screen_to_world(Point2d::new(0f32, 0f32), width, height);
+        let across: Option<Point2d> = camera.screen_to_world(Point2d::new(1f32, 0f32), width, height);
+
+        self.world_per_pixel = match (origin, across) {
+            (Some(a), Some(b)) => Some((b - a).len()),
+            _ => None,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pressed_ignores_repeats_unless_asked() {
+        let mut state: InputState = InputState::new();
+        let frame: InputFrame = state.next_frame(vec![InputEvent::KeyPressed { key: Key::Right, repeat: true }]);
+
+        assert!(!frame.is_key_pressed(Key::Right, KeyRepeat::No));
+        assert!(frame.is_key_pressed(Key::Right, KeyRepeat::Yes));
+    }
+
+    #[test]
+    fn test_state_carries_over_frames() {
+        let mut state: InputState = InputState::new();
+        state.next_frame(vec![
+            InputEvent::KeyPressed { key: Key::Space, repeat: false },
+            InputEvent::MousePressed(MouseButton::Left),
+            InputEvent::MouseMoved(Point2d::new(10f32, 20f32)),
+        ]);
+
+        let held: InputFrame = state.next_frame(vec![InputEvent::Wheel(1f32), InputEvent::Wheel(0.5f32)]);
+
+        assert!(held.is_key_down(Key::Space));
+        assert!(held.is_mouse_down(MouseButton::Left));
+        assert!(!held.is_mouse_pressed(MouseButton::Left));
+        assert_eq!(Some(Point2d::new(10f32, 20f32)), held.mouse_pos());
+        assert_eq!(1.5f32, held.scroll());
+
+        let released: InputFrame = state.next_frame(vec![InputEvent::KeyReleased(Key::Space), InputEvent::MouseLeft]);
+
+        assert!(!released.is_key_down(Key::Space));
+        assert!(released.is_key_released(Key::Space));
+        assert_eq!(None, released.mouse_pos());
+    }
+
+    #[test]
+    fn test_locate() {
+        let camera: Camera = Camera::new(100f32, 100f32);
+        let mut state: InputState = InputState::new();
+        let mut frame: InputFrame = state.next_frame(vec![InputEvent::MouseMoved(Point2d::new(50f32, 50f32))]);
+
+        frame.locate(&camera, 100, 100);
+        let world: Point2d = frame.mouse_world().unwrap();
+
+        assert!(world.approx_equals(&Point2d::new(50f32, 50f32), 1f32));
+        assert!((frame.world_per_pixel().unwrap() - 1f32).abs() < 0.01f32);
+    }
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    display::{
+        camera::Camera,
+        input::{InputEvent, InputFrame, InputState, Key, MouseButton},
+        scenario::Scenario,
+    },
+    entities::point2d::Point2d,
+};
+
+//A sequence of input frames replayed against a camera without a window. The
+//screen size is needed to map the mouse into the world like the viewer does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputScript {
+    pub width: usize,
+    pub height: usize,
+    pub frames: Vec<Vec<InputEvent>>,
+}
+
+impl InputScript {
+    pub fn new(width: usize, height: usize) -> Self {
+        InputScript {
+            width,
+            height,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<InputScript, &'static str> {
+        let text: String = std::fs::read_to_string(path).map_err(|_| "Could not read input script")?;
+
+        serde_json::from_str(&text).map_err(|_| "Could not parse input script")
+    }
+
+    pub fn frame(mut self, events: Vec<InputEvent>) -> Self {
+        self.frames.push(events);
+        self
+    }
+
+    //A frame where nothing happens, scenarios still get to redraw
+    pub fn idle(self) -> Self {
+        self.frame(Vec::new())
+    }
+
+    //Pressed in one frame and released in the next
+    pub fn press(self, key: Key) -> Self {
+        self.frame(vec![InputEvent::KeyPressed { key, repeat: false }])
+            .frame(vec![InputEvent::KeyReleased(key)])
+    }
+
+    pub fn move_mouse(self, screen: Point2d) -> Self {
+        self.frame(vec![InputEvent::MouseMoved(screen)])
+    }
+
+    pub fn click(self, button: MouseButton, screen: Point2d) -> Self {
+        self.frame(vec![InputEvent::MouseMoved(screen), InputEvent::MousePressed(button)])
+            .frame(vec![InputEvent::MouseReleased(button)])
+    }
+
+    pub fn wheel(self, delta: f32) -> Self {
+        self.frame(vec![InputEvent::Wheel(delta)])
+    }
+
+    //Same order as the window loop, returns how many frames were redrawn
+    pub fn run(&self, scenario: &mut dyn Scenario, camera: &mut Camera) -> usize {
+        let mut state: InputState = InputState::new();
+        let mut redraws: usize = 0;
+
+        for events in &self.frames {
+            let mut input: InputFrame = state.next_frame(events.clone());
+            input.locate(camera, self.width, self.height);
+
+            scenario.handle_input(&input);
+
+            if scenario.redraw() {
+                camera.clear();
+                scenario.process(camera);
+                redraws += 1;
+            }
+        }
+
+        redraws
+    }
+}
+
+//The scripts drive the scenarios, which are only built with the viewer
+#[cfg(all(test, feature = "viewer"))]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::rectangle2d::Rectangle2D,
+        scenarios::{convex_hull_scenario::ConvexHullScenario, right_turn_debug::RightTurnDebug},
+    };
+
+    fn bounds() -> Rectangle2D {
+        Rectangle2D::new(Point2d::new(0f32, 0f32), Point2d::new(100f32, 100f32))
+    }
+
+    #[test]
+    fn test_new_points_on_r() {
+        let mut scenario: ConvexHullScenario = ConvexHullScenario::new(8, bounds());
+        scenario.initialize().unwrap();
+        let mut camera: Camera = Camera::new(100f32, 100f32);
+
+        let script: InputScript = InputScript::new(100, 100).idle().press(Key::R).idle();
+        let redraws: usize = script.run(&mut scenario, &mut camera);
+
+        assert_eq!(2, redraws);
+        assert_eq!(8, camera.scene().find_layer("points").unwrap().points().count());
+    }
+
+    #[test]
+    fn test_unbound_keys_do_not_redraw() {
+        let mut scenario: ConvexHullScenario = ConvexHullScenario::new(5, bounds());
+        scenario.initialize().unwrap();
+        let mut camera: Camera = Camera::new(100f32, 100f32);
+
+        InputScript::new(100, 100).idle().run(&mut scenario, &mut camera);
+        let redraws: usize = InputScript::new(100, 100)
+            .press(Key::Q)
+            .click(MouseButton::Middle, Point2d::new(5f32, 5f32))
+            .run(&mut scenario, &mut camera);
+
+        assert_eq!(0, redraws);
+    }
+
+    #[test]
+    fn test_click_adds_hull_point() {
+        let points: Vec<Point2d> = vec![Point2d::new(10f32, 10f32), Point2d::new(90f32, 10f32), Point2d::new(50f32, 90f32)];
+        let mut scenario: ConvexHullScenario = ConvexHullScenario::new_specific(points, bounds());
+        scenario.initialize().unwrap();
+        let mut camera: Camera = Camera::new(100f32, 100f32);
+
+        let redraws: usize = InputScript::new(100, 100)
+            .idle()
+            .click(MouseButton::Left, Point2d::new(50f32, 50f32))
+            .run(&mut scenario, &mut camera);
+
+        assert_eq!(2, redraws);
+        assert_eq!(4, camera.scene().find_layer("points").unwrap().points().count());
+    }
+
+    #[test]
+    fn test_script_round_trip() {
+        let script: InputScript = InputScript::new(640, 480)
+            .press(Key::Right)
+            .click(MouseButton::Left, Point2d::new(1f32, 2f32))
+            .wheel(-1f32);
+
+        let json: String = serde_json::to_string(&script).unwrap();
+        let parsed: InputScript = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(script, parsed);
+    }
+
+    #[test]
+    fn test_repeats_are_replayed() {
+        let mut scenario: RightTurnDebug = RightTurnDebug::new(6, bounds());
+        scenario.initialize().unwrap();
+        let mut camera: Camera = Camera::new(100f32, 100f32);
+
+        //held keys only count once, RightTurnDebug ignores repeats
+        let redraws: usize = InputScript::new(100, 100)
+            .idle()
+            .frame(vec![InputEvent::KeyPressed { key: Key::Right, repeat: false }])
+            .frame(vec![InputEvent::KeyPressed { key: Key::Right, repeat: true }])
+            .run(&mut scenario, &mut camera);
+
+        assert_eq!(2, redraws);
+    }
+}
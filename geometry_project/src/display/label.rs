@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{display::rgb::RGB, entities::point2d::Point2d};
+
+//Screen pixels between a labelled point and its text
+pub const LABEL_OFFSET: i64 = 4;
+
+//Text attached to a world point, drawn up and to the right of it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Label {
+    pub position: Point2d,
+    pub text: String,
+    pub color: RGB,
+}
+
+impl Label {
+    pub fn new(position: Point2d, text: String, color: RGB) -> Self {
+        Label {
+            position,
+            text,
+            color,
+        }
+    }
+
+    //Point labelled with its index, used to find points in logs
+    pub fn index(position: Point2d, index: usize, color: RGB) -> Self {
+        Label::new(position, index.to_string(), color)
+    }
+
+    //Point labelled with its own coordinates
+    pub fn coordinates(position: Point2d, color: RGB) -> Self {
+        Label::new(position, format!("({:.2}, {:.2})", position.x, position.y), color)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LegendEntry {
+    pub color: RGB,
+    pub text: String,
+}
+
+impl LegendEntry {
+    pub fn new(color: RGB, text: &str) -> Self {
+        LegendEntry {
+            color,
+            text: text.to_string(),
+        }
+    }
+}
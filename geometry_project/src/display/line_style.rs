@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{line2d::Line2D, point2d::Point2d, polygon2d::Polygon2D};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinePattern {
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+//Width and pattern lengths are in screen pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LineStyle {
+    pub width: f32,
+    pub pattern: LinePattern,
+    pub anti_aliased: bool,
+    pub arrow_head: bool,
+}
+
+impl Default for LineStyle {
+    fn default() -> Self {
+        LineStyle::solid()
+    }
+}
+
+const DISK_SEGMENTS: usize = 12;
+
+impl LineStyle {
+    pub fn solid() -> Self {
+        LineStyle {
+            width: 1f32,
+            pattern: LinePattern::Solid,
+            anti_aliased: false,
+            arrow_head: false,
+        }
+    }
+
+    pub fn dashed() -> Self {
+        LineStyle {
+            pattern: LinePattern::Dashed,
+            ..LineStyle::solid()
+        }
+    }
+
+    pub fn dotted() -> Self {
+        LineStyle {
+            pattern: LinePattern::Dotted,
+            ..LineStyle::solid()
+        }
+    }
+
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = width.max(1f32);
+        self
+    }
+
+    pub fn with_arrow_head(mut self) -> Self {
+        self.arrow_head = true;
+        self
+    }
+
+    pub fn anti_aliased(mut self) -> Self {
+        self.anti_aliased = true;
+        self
+    }
+
+    pub fn is_thick(&self) -> bool {
+        self.width > 1f32
+    }
+
+    //(on, off) lengths of the pattern, None when the line is solid
+    pub fn dash_lengths(&self) -> Option<(f32, f32)> {
+        match self.pattern {
+            LinePattern::Solid => None,
+            LinePattern::Dashed => Some((6f32 * self.width, 4f32 * self.width)),
+            LinePattern::Dotted => Some((self.width, 2f32 * self.width)),
+        }
+    }
+
+    //Split a screen space line into the visible pieces of its pattern
+    pub fn dash_segments(&self, line: Line2D) -> Vec<Line2D> {
+        let (on, off) = match self.dash_lengths() {
+            Some(lengths) => lengths,
+            None => {
+                return vec![line];
+            }
+        };
+
+        let length: f32 = line.len();
+        let mut segments: Vec<Line2D> = Vec::new();
+
+        if length == 0f32 {
+            return segments;
+        }
+
+        let mut distance: f32 = 0f32;
+        while distance < length {
+            let dash_end: f32 = f32::min(distance + on, length);
+
+            segments.push(Line2D::new(
+                point_along(line, distance / length),
+                point_along(line, dash_end / length),
+            ));
+
+            distance += on + off;
+        }
+
+        segments
+    }
+
+    pub fn arrow_head_size(&self) -> f32 {
+        f32::max(6f32, 3f32 * self.width)
+    }
+}
+
+fn point_along(line: Line2D, t: f32) -> Point2d {
+    Point2d::new(
+        line.start.x + (line.end.x - line.start.x) * t,
+        line.start.y + (line.end.y - line.start.y) * t,
+    )
+}
+
+//Unit direction and unit normal of a line, None for degenerate lines
+fn direction_normal(line: Line2D) -> Option<((f32, f32), (f32, f32))> {
+    let length: f32 = line.len();
+
+    if length == 0f32 || !length.is_finite() {
+        return None;
+    }
+
+    let ux: f32 = (line.end.x - line.start.x) / length;
+    let uy: f32 = (line.end.y - line.start.y) / length;
+
+    Some(((ux, uy), (-uy, ux)))
+}
+
+//Quad covering a line of the given width, without caps
+pub fn thick_segment_polygon(line: Line2D, width: f32) -> Option<Polygon2D> {
+    let (_, (nx, ny)) = direction_normal(line)?;
+    let half: f32 = width / 2f32;
+
+    Some(Polygon2D::new(vec![
+        Point2d::new(line.start.x + nx * half, line.start.y + ny * half),
+        Point2d::new(line.end.x + nx * half, line.end.y + ny * half),
+        Point2d::new(line.end.x - nx * half, line.end.y - ny * half),
+        Point2d::new(line.start.x - nx * half, line.start.y - ny * half),
+    ]))
+}
+
+//Round cap, drawn at every vertex so consecutive thick segments get a round join
+pub fn disk_polygon(center: Point2d, radius: f32) -> Polygon2D {
+    let points: Vec<Point2d> = (0..DISK_SEGMENTS)
+        .map(|i| {
+            let angle: f32 = i as f32 * 2f32 * std::f32::consts::PI / DISK_SEGMENTS as f32;
+            Point2d::new(center.x + radius * angle.cos(), center.y + radius * angle.sin())
+        })
+        .collect();
+
+    Polygon2D::new(points)
+}
+
+//Triangle with its tip on the end of the line
+pub fn arrow_head_polygon(line: Line2D, size: f32) -> Option<Polygon2D> {
+    let ((ux, uy), (nx, ny)) = direction_normal(line)?;
+
+    let base_x: f32 = line.end.x - ux * size;
+    let base_y: f32 = line.end.y - uy * size;
+    let half: f32 = size / 2f32;
+
+    Some(Polygon2D::new(vec![
+        line.end,
+        Point2d::new(base_x + nx * half, base_y + ny * half),
+        Point2d::new(base_x - nx * half, base_y - ny * half),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solid_is_single_segment() {
+        let line: Line2D = Line2D::new_flat(0f32, 0f32, 10f32, 0f32);
+
+        let segments: Vec<Line2D> = LineStyle::solid().dash_segments(line);
+
+        assert_eq!(1, segments.len());
+        assert_eq!(line, segments[0]);
+    }
+
+    #[test]
+    fn test_dashed_segments() {
+        let line: Line2D = Line2D::new_flat(0f32, 0f32, 25f32, 0f32);
+
+        let segments: Vec<Line2D> = LineStyle::dashed().dash_segments(line);
+
+        assert_eq!(3, segments.len());
+        assert!(segments[0].approx_equals(&Line2D::new_flat(0f32, 0f32, 6f32, 0f32), 0.0001));
+        assert!(segments[2].approx_equals(&Line2D::new_flat(20f32, 0f32, 25f32, 0f32), 0.0001));
+    }
+
+    #[test]
+    fn test_arrow_head_tip() {
+        let line: Line2D = Line2D::new_flat(0f32, 0f32, 10f32, 0f32);
+
+        let arrow: Polygon2D = arrow_head_polygon(line, 4f32).unwrap();
+
+        assert_eq!(line.end, arrow.points[0]);
+        assert!(arrow.points[1].approx_equals(&Point2d::new(6f32, 2f32), 0.0001));
+        assert!(arrow.points[2].approx_equals(&Point2d::new(6f32, -2f32), 0.0001));
+    }
+
+    #[test]
+    fn test_degenerate_line_has_no_quad() {
+        let line: Line2D = Line2D::new_flat(3f32, 3f32, 3f32, 3f32);
+
+        assert!(thick_segment_polygon(line, 3f32).is_none());
+    }
+}
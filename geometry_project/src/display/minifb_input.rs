@@ -0,0 +1,111 @@
+use minifb::{MouseMode, Window};
+
+use crate::{
+    display::input::{InputEvent, InputFrame, InputState, Key, MouseButton},
+    entities::point2d::Point2d,
+};
+
+//Turns minifb's polled window state into input events, the previous frame is
+//kept to tell presses from held buttons and to only report mouse moves
+pub struct MinifbInput {
+    state: InputState,
+}
+
+const BUTTONS: [(MouseButton, minifb::MouseButton); 3] = [
+    (MouseButton::Left, minifb::MouseButton::Left),
+    (MouseButton::Middle, minifb::MouseButton::Middle),
+    (MouseButton::Right, minifb::MouseButton::Right),
+];
+
+fn translate_key(key: minifb::Key) -> Option<Key> {
+    use minifb::Key as M;
+
+    let key: Key = match key {
+        M::A => Key::A, M::B => Key::B, M::C => Key::C, M::D => Key::D, M::E => Key::E,
+        M::F => Key::F, M::G => Key::G, M::H => Key::H, M::I => Key::I, M::J => Key::J,
+        M::K => Key::K, M::L => Key::L, M::M => Key::M, M::N => Key::N, M::O => Key::O,
+        M::P => Key::P, M::Q => Key::Q, M::R => Key::R, M::S => Key::S, M::T => Key::T,
+        M::U => Key::U, M::V => Key::V, M::W => Key::W, M::X => Key::X, M::Y => Key::Y,
+        M::Z => Key::Z,
+        M::Key0 => Key::Key0, M::Key1 => Key::Key1, M::Key2 => Key::Key2, M::Key3 => Key::Key3,
+        M::Key4 => Key::Key4, M::Key5 => Key::Key5, M::Key6 => Key::Key6, M::Key7 => Key::Key7,
+        M::Key8 => Key::Key8, M::Key9 => Key::Key9,
+        M::F1 => Key::F1, M::F2 => Key::F2, M::F3 => Key::F3, M::F4 => Key::F4,
+        M::F5 => Key::F5, M::F6 => Key::F6, M::F7 => Key::F7, M::F8 => Key::F8,
+        M::F9 => Key::F9, M::F10 => Key::F10, M::F11 => Key::F11, M::F12 => Key::F12,
+        M::Left => Key::Left, M::Right => Key::Right, M::Up => Key::Up, M::Down => Key::Down,
+        M::Home => Key::Home, M::End => Key::End, M::PageUp => Key::PageUp, M::PageDown => Key::PageDown,
+        M::Enter => Key::Enter, M::NumPadEnter => Key::Enter, M::Tab => Key::Tab, M::Space => Key::Space,
+        M::Escape => Key::Escape, M::Backspace => Key::Backspace, M::Delete => Key::Delete,
+        M::LeftShift => Key::LeftShift, M::RightShift => Key::RightShift,
+        M::LeftCtrl => Key::LeftCtrl, M::RightCtrl => Key::RightCtrl,
+        _ => {
+            return None;
+        }
+    };
+
+    Some(key)
+}
+
+impl Default for MinifbInput {
+    fn default() -> Self {
+        MinifbInput::new()
+    }
+}
+
+impl MinifbInput {
+    pub fn new() -> Self {
+        MinifbInput {
+            state: InputState::new(),
+        }
+    }
+
+    fn events(&self, window: &Window) -> Vec<InputEvent> {
+        let mut events: Vec<InputEvent> = Vec::new();
+
+        let fresh: Vec<minifb::Key> = window.get_keys_pressed(minifb::KeyRepeat::No);
+        for key in window.get_keys_pressed(minifb::KeyRepeat::Yes) {
+            if let Some(k) = translate_key(key) {
+                events.push(InputEvent::KeyPressed {
+                    key: k,
+                    repeat: !fresh.contains(&key),
+                });
+            }
+        }
+
+        events.extend(window.get_keys_released().into_iter().filter_map(translate_key).map(InputEvent::KeyReleased));
+
+        let mouse: Option<Point2d> = window
+            .get_mouse_pos(MouseMode::Discard)
+            .map(|(x, y)| Point2d::new(x, y));
+
+        match (mouse, self.state.mouse_pos()) {
+            (Some(now), before) if before != Some(now) => events.push(InputEvent::MouseMoved(now)),
+            (None, Some(_)) => events.push(InputEvent::MouseLeft),
+            _ => {}
+        }
+
+        for (button, minifb_button) in BUTTONS {
+            match (window.get_mouse_down(minifb_button), self.state.is_mouse_down(button)) {
+                (true, false) => events.push(InputEvent::MousePressed(button)),
+                (false, true) => events.push(InputEvent::MouseReleased(button)),
+                _ => {}
+            }
+        }
+
+        if let Some((_, scroll)) = window.get_scroll_wheel() {
+            if scroll != 0f32 {
+                events.push(InputEvent::Wheel(scroll));
+            }
+        }
+
+        events
+    }
+
+    //Call once per frame, after the window was updated
+    pub fn poll(&mut self, window: &Window) -> InputFrame {
+        let events: Vec<InputEvent> = self.events(window);
+
+        self.state.next_frame(events)
+    }
+}
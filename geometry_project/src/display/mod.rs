@@ -1,8 +1,26 @@
-mod bresnehem;
+pub mod animation;
+pub mod bitmap_font;
 pub mod camera;
+pub mod editor;
+pub mod input;
+pub mod input_script;
+pub mod label;
+pub mod line_style;
+#[cfg(feature = "viewer")]
+pub mod minifb_input;
+pub mod overlays;
+pub mod palette;
 pub mod rgb;
+pub mod rgba;
+pub mod scanline;
 pub mod scenario;
 pub mod scene;
+pub mod scene_diff;
 pub mod scene_proxy;
+pub mod snapshot;
 pub mod string_builder;
-pub mod hsv;
\ No newline at end of file
+pub mod style;
+pub mod view_controller;
+pub mod viewport;
+pub mod hsv;
+pub mod xiaolin_wu;
\ No newline at end of file
@@ -0,0 +1,164 @@
+use crate::display::rgb::RGB;
+
+//Reference overlays drawn by the camera, every flag is toggled from the viewer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overlays {
+    pub grid: bool,
+    pub axes: bool,
+    pub tick_labels: bool,
+    pub scale_bar: bool,
+    pub integer_lattice: bool,
+    pub help: bool,
+}
+
+impl Default for Overlays {
+    fn default() -> Self {
+        Overlays {
+            grid: false,
+            axes: true,
+            tick_labels: false,
+            scale_bar: true,
+            integer_lattice: false,
+            help: false,
+        }
+    }
+}
+
+pub const MINOR_GRID_COLOR: RGB = RGB(0x1e1e1e);
+pub const MAJOR_GRID_COLOR: RGB = RGB(0x3a3a3a);
+pub const AXES_COLOR: RGB = RGB(0x8a8a8a);
+pub const LATTICE_COLOR: RGB = RGB(0x4a7aa8);
+pub const TICK_LABEL_COLOR: RGB = RGB(0xa0a0a0);
+
+//Aim for this many major grid cells across the view
+pub const MAJOR_DIVISIONS: f32 = 5f32;
+pub const MINOR_PER_MAJOR: f32 = 5f32;
+//Scale bar length is the largest nice value at most this many pixels long
+pub const SCALE_BAR_PIXELS: f32 = 100f32;
+//Skip the lattice when integer points would be closer than this on screen
+pub const LATTICE_MIN_PIXELS: f32 = 4f32;
+//Guard against drawing thousands of grid lines for a degenerate view
+pub const MAX_GRID_LINES: usize = 500;
+
+fn decompose(value: f32) -> (f32, f32) {
+    let power: f32 = 10f32.powi(value.log10().floor() as i32);
+    (value / power, power)
+}
+
+//Round span / divisions to 1, 2 or 5 times a power of ten
+pub fn nice_step(span: f32, divisions: f32) -> Option<f32> {
+    let raw: f32 = span / divisions;
+
+    if !raw.is_finite() || raw <= 0f32 {
+        return None;
+    }
+
+    let (fraction, power) = decompose(raw);
+
+    let nice: f32 = if fraction < 1.5 {
+        1f32
+    } else if fraction < 3.5 {
+        2f32
+    } else if fraction < 7.5 {
+        5f32
+    } else {
+        10f32
+    };
+
+    Some(nice * power)
+}
+
+//Largest 1, 2 or 5 times a power of ten not above value
+pub fn nice_floor(value: f32) -> Option<f32> {
+    if !value.is_finite() || value <= 0f32 {
+        return None;
+    }
+
+    let (fraction, power) = decompose(value);
+
+    let nice: f32 = if fraction >= 5f32 {
+        5f32
+    } else if fraction >= 2f32 {
+        2f32
+    } else {
+        1f32
+    };
+
+    Some(nice * power)
+}
+
+//Multiples of step inside [min, max]
+pub fn grid_values(min: f32, max: f32, step: f32) -> Vec<f32> {
+    let mut values: Vec<f32> = Vec::new();
+
+    if step.is_nan() || step <= 0f32 || !min.is_finite() || !max.is_finite() {
+        return values;
+    }
+
+    let first: f32 = (min / step).ceil();
+
+    for k in 0..MAX_GRID_LINES {
+        let value: f32 = (first + k as f32) * step;
+
+        if value > max {
+            break;
+        }
+
+        values.push(value);
+    }
+
+    values
+}
+
+//Enough decimals to tell neighbouring ticks apart, never prints -0
+pub fn format_tick(value: f32, step: f32) -> String {
+    let decimals: usize = (-step.log10().floor()).max(0f32) as usize;
+
+    let value: f32 = match value.abs() < step / 2f32 {
+        true => 0f32,
+        false => value,
+    };
+
+    format!("{:.*}", decimals, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nice_step() {
+        assert_eq!(Some(20f32), nice_step(100f32, 5f32));
+        assert_eq!(Some(0.5f32), nice_step(2.4f32, 5f32));
+        assert_eq!(Some(100f32), nice_step(420f32, 5f32));
+        assert_eq!(None, nice_step(0f32, 5f32));
+    }
+
+    #[test]
+    fn test_nice_floor() {
+        assert_eq!(Some(50f32), nice_floor(73f32));
+        assert_eq!(Some(2f32), nice_floor(4.9f32));
+        assert_eq!(Some(1f32), nice_floor(1f32));
+    }
+
+    #[test]
+    fn test_grid_values_negative_range() {
+        let values: Vec<f32> = grid_values(-25f32, 12f32, 10f32);
+
+        assert_eq!(vec![-20f32, -10f32, 0f32, 10f32], values);
+    }
+
+    #[test]
+    fn test_grid_values_bad_step() {
+        assert!(grid_values(0f32, 10f32, f32::NAN).is_empty());
+        assert!(grid_values(0f32, 10f32, 0f32).is_empty());
+        assert!(grid_values(0f32, 10f32, -1f32).is_empty());
+    }
+
+    #[test]
+    fn test_format_tick() {
+        assert_eq!("25", format_tick(25f32, 5f32));
+        assert_eq!("0.50", format_tick(0.5f32, 0.05f32));
+        assert_eq!("0", format_tick(-0.0000001f32, 1f32));
+    }
+}
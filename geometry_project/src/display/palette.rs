@@ -0,0 +1,154 @@
+use crate::display::rgb::RGB;
+
+//Discrete palettes for telling categories apart, colors repeat past the end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    Category10,
+    Set1,
+    Dark2,
+}
+
+const CATEGORY10: [u32; 10] = [
+    0x1f77b4, 0xff7f0e, 0x2ca02c, 0xd62728, 0x9467bd, 0x8c564b, 0xe377c2, 0x7f7f7f, 0xbcbd22, 0x17becf,
+];
+
+const SET1: [u32; 9] = [
+    0xe41a1c, 0x377eb8, 0x4daf4a, 0x984ea3, 0xff7f00, 0xffff33, 0xa65628, 0xf781bf, 0x999999,
+];
+
+const DARK2: [u32; 8] = [
+    0x1b9e77, 0xd95f02, 0x7570b3, 0xe7298a, 0x66a61e, 0xe6ab02, 0xa6761d, 0x666666,
+];
+
+impl Palette {
+    fn table(&self) -> &'static [u32] {
+        match self {
+            Palette::Category10 => &CATEGORY10,
+            Palette::Set1 => &SET1,
+            Palette::Dark2 => &DARK2,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.table().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table().is_empty()
+    }
+
+    pub fn color(&self, index: usize) -> RGB {
+        let table: &[u32] = self.table();
+        RGB(table[index % table.len()])
+    }
+
+    pub fn colors(&self, count: usize) -> Vec<RGB> {
+        (0..count).map(|i| self.color(i)).collect()
+    }
+}
+
+//Continuous colormaps for scalar per element values, sampled from evenly spaced stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colormap {
+    Viridis,
+    Magma,
+    Plasma,
+    Inferno,
+    Grayscale,
+}
+
+const VIRIDIS: [u32; 9] = [
+    0x440154, 0x472c7a, 0x3b518b, 0x2c718e, 0x21908d, 0x27ad81, 0x5cc863, 0xaadc32, 0xfde725,
+];
+
+const MAGMA: [u32; 9] = [
+    0x000004, 0x1c1044, 0x4f127b, 0x812581, 0xb5367a, 0xe55964, 0xfb8761, 0xfec287, 0xfcfdbf,
+];
+
+const PLASMA: [u32; 9] = [
+    0x0d0887, 0x4c02a1, 0x7e03a8, 0xa92395, 0xcc4778, 0xe56b5d, 0xf89441, 0xfdc328, 0xf0f921,
+];
+
+const INFERNO: [u32; 9] = [
+    0x000004, 0x1b0c41, 0x4a0c6b, 0x781c6d, 0xa52c60, 0xcf4446, 0xed6925, 0xfb9b06, 0xfcffa4,
+];
+
+const GRAYSCALE: [u32; 2] = [0x000000, 0xffffff];
+
+impl Colormap {
+    fn stops(&self) -> &'static [u32] {
+        match self {
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Magma => &MAGMA,
+            Colormap::Plasma => &PLASMA,
+            Colormap::Inferno => &INFERNO,
+            Colormap::Grayscale => &GRAYSCALE,
+        }
+    }
+
+    //t is clamped into [0, 1], NaN maps to the low end
+    pub fn sample(&self, t: f32) -> RGB {
+        let stops: &[u32] = self.stops();
+        let t: f32 = match t.is_nan() {
+            true => 0f32,
+            false => t.clamp(0f32, 1f32),
+        };
+
+        let position: f32 = t * (stops.len() - 1) as f32;
+        let index: usize = (position.floor() as usize).min(stops.len() - 2);
+        let fraction: f32 = position - index as f32;
+
+        RGB(stops[index]).lerp(RGB(stops[index + 1]), fraction)
+    }
+
+    pub fn map(&self, value: f32, min: f32, max: f32) -> RGB {
+        if max <= min {
+            return self.sample(0f32);
+        }
+
+        self.sample((value - min) / (max - min))
+    }
+
+    //Normalize over the finite range of values and map each one
+    pub fn map_values(&self, values: &[f32]) -> Vec<RGB> {
+        let finite = values.iter().filter(|v| v.is_finite());
+        let min: f32 = finite.clone().fold(f32::MAX, |a, b| a.min(*b));
+        let max: f32 = finite.fold(f32::MIN, |a, b| a.max(*b));
+
+        values.iter().map(|v| self.map(*v, min, max)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_wraps() {
+        let palette: Palette = Palette::Category10;
+
+        assert_eq!(palette.color(0), palette.color(palette.len()));
+        assert_eq!(RGB(0x1f77b4), palette.color(0));
+        assert!(!palette.is_empty());
+    }
+
+    #[test]
+    fn test_viridis_ends() {
+        assert_eq!(RGB(0x440154), Colormap::Viridis.sample(0f32));
+        assert_eq!(RGB(0xfde725), Colormap::Viridis.sample(1f32));
+        assert_eq!(RGB(0xfde725), Colormap::Viridis.sample(4f32));
+    }
+
+    #[test]
+    fn test_grayscale_middle() {
+        assert_eq!(RGB::new(128, 128, 128), Colormap::Grayscale.sample(0.5f32));
+    }
+
+    #[test]
+    fn test_map_values_range() {
+        let colors: Vec<RGB> = Colormap::Grayscale.map_values(&[2f32, 4f32, 6f32]);
+
+        assert_eq!(RGB::black(), colors[0]);
+        assert_eq!(RGB::white(), colors[2]);
+    }
+}
@@ -24,7 +24,7 @@ impl RGB {
     }
 
     pub fn white() -> Self {
-        RGB(0xfffffff)
+        RGB(0xffffff)
     }
 
     pub fn red() -> Self {
@@ -34,6 +34,40 @@ impl RGB {
     pub fn green() -> Self {
         Self::new(0, 255, 0)
     }
+
+    pub fn blue() -> Self {
+        Self::new(0, 0, 255)
+    }
+
+    //Parse "#rrggbb" or "rrggbb"
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let digits: &str = hex.trim_start_matches('#');
+
+        if digits.len() != 6 {
+            return None;
+        }
+
+        u32::from_str_radix(digits, 16).ok().map(RGB)
+    }
+
+    pub fn channels(&self) -> (u8, u8, u8) {
+        (
+            ((self.0 >> 16) & 0xff) as u8,
+            ((self.0 >> 8) & 0xff) as u8,
+            (self.0 & 0xff) as u8,
+        )
+    }
+
+    //Linear interpolation towards other, t = 0 keeps self and t = 1 gives other
+    pub fn lerp(&self, other: RGB, t: f32) -> RGB {
+        let t: f32 = t.clamp(0f32, 1f32);
+        let (r0, g0, b0) = self.channels();
+        let (r1, g1, b1) = other.channels();
+
+        let mix = |a: u8, b: u8| -> u8 { (a as f32 + (b as f32 - a as f32) * t).round() as u8 };
+
+        RGB::new(mix(r0, r1), mix(g0, g1), mix(b0, b1))
+    }
 }
 
 #[cfg(test)]
@@ -47,4 +81,22 @@ mod tests {
 
         assert_eq!(expected, red.0);
     }
+
+    #[test]
+    fn test_white() {
+        assert_eq!((255, 255, 255), RGB::white().channels());
+    }
+
+    #[test]
+    fn test_from_hex() {
+        assert_eq!(Some(RGB::new(0x44, 0x01, 0x54)), RGB::from_hex("#440154"));
+        assert_eq!(None, RGB::from_hex("#4401"));
+    }
+
+    #[test]
+    fn test_lerp_half() {
+        let mixed: RGB = RGB::black().lerp(RGB::new(200, 100, 50), 0.5f32);
+
+        assert_eq!(RGB::new(100, 50, 25), mixed);
+    }
 }
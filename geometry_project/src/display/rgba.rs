@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+use crate::display::{hsv::HSV, rgb::RGB};
+
+//Packed as 0xAARRGGBB so the low 24 bits match RGB
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct RGBA(pub u32);
+
+impl From<RGB> for RGBA {
+    fn from(value: RGB) -> Self {
+        RGBA::from_rgb(value, 255)
+    }
+}
+
+impl From<RGBA> for RGB {
+    fn from(value: RGBA) -> Self {
+        value.rgb()
+    }
+}
+
+impl From<HSV> for RGBA {
+    fn from(value: HSV) -> Self {
+        RGBA::from(value.to_rgb())
+    }
+}
+
+impl From<RGBA> for HSV {
+    fn from(value: RGBA) -> Self {
+        HSV::from_rgb(value.rgb())
+    }
+}
+
+impl RGBA {
+    pub fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        RGBA((u32::from(alpha) << 24) | RGB::new(red, green, blue).0)
+    }
+
+    pub fn from_rgb(rgb: RGB, alpha: u8) -> Self {
+        RGBA((u32::from(alpha) << 24) | (rgb.0 & 0xffffff))
+    }
+
+    //Opacity in [0, 1]
+    pub fn from_rgb_opacity(rgb: RGB, opacity: f32) -> Self {
+        RGBA::from_rgb(rgb, (opacity.clamp(0f32, 1f32) * 255f32).round() as u8)
+    }
+
+    pub fn transparent() -> Self {
+        RGBA(0u32)
+    }
+
+    pub fn rgb(&self) -> RGB {
+        RGB(self.0 & 0xffffff)
+    }
+
+    pub fn alpha(&self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+
+    pub fn opacity(&self) -> f32 {
+        self.alpha() as f32 / 255f32
+    }
+
+    pub fn channels(&self) -> (u8, u8, u8, u8) {
+        let (r, g, b) = self.rgb().channels();
+        (r, g, b, self.alpha())
+    }
+
+    pub fn with_alpha(&self, alpha: u8) -> Self {
+        RGBA::from_rgb(self.rgb(), alpha)
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        self.alpha() == 255
+    }
+
+    //Source over compositing onto an opaque destination, as used by the canvas
+    pub fn blend_over(&self, destination: RGB) -> RGB {
+        match self.alpha() {
+            0 => destination,
+            255 => self.rgb(),
+            _ => destination.lerp(self.rgb(), self.opacity()),
+        }
+    }
+
+    //Porter Duff source over, both colors may be translucent
+    pub fn over(&self, destination: RGBA) -> RGBA {
+        let src_a: f32 = self.opacity();
+        let dst_a: f32 = destination.opacity();
+        let out_a: f32 = src_a + dst_a * (1f32 - src_a);
+
+        if out_a <= 0f32 {
+            return RGBA::transparent();
+        }
+
+        let (sr, sg, sb, _) = self.channels();
+        let (dr, dg, db, _) = destination.channels();
+
+        let mix = |s: u8, d: u8| -> u8 {
+            ((s as f32 * src_a + d as f32 * dst_a * (1f32 - src_a)) / out_a).round() as u8
+        };
+
+        RGBA::new(mix(sr, dr), mix(sg, dg), mix(sb, db), (out_a * 255f32).round() as u8)
+    }
+
+    pub fn to_hsv(&self) -> HSV {
+        HSV::from_rgb(self.rgb())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rgb_round_trip() {
+        let rgb: RGB = RGB::new(12, 34, 56);
+        let rgba: RGBA = rgb.into();
+
+        assert_eq!(255, rgba.alpha());
+        assert_eq!(rgb, RGB::from(rgba));
+    }
+
+    #[test]
+    fn test_blend_over_half() {
+        let red: RGBA = RGBA::new(255, 0, 0, 128);
+        let blended: RGB = red.blend_over(RGB::black());
+
+        let (r, g, b) = blended.channels();
+        assert_eq!((128, 0, 0), (r, g, b));
+    }
+
+    #[test]
+    fn test_blend_extremes() {
+        let destination: RGB = RGB::new(10, 20, 30);
+
+        assert_eq!(destination, RGBA::transparent().blend_over(destination));
+        assert_eq!(RGB::red(), RGBA::from(RGB::red()).blend_over(destination));
+    }
+
+    #[test]
+    fn test_over_translucent() {
+        let source: RGBA = RGBA::new(255, 0, 0, 128);
+        let destination: RGBA = RGBA::new(0, 0, 255, 128);
+
+        let result: RGBA = source.over(destination);
+
+        assert_eq!(192, result.alpha());
+        let (r, _, b, _) = result.channels();
+        assert!(r > b, "Source should dominate, r: {} b: {}", r, b);
+    }
+}
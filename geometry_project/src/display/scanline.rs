@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{point2d::Point2d, polygon2d::Polygon2D};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FillRule {
+    EvenOdd,
+    NonZero,
+}
+
+//Horizontal run of covered pixels on a single row, col_end is exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub row: usize,
+    pub col_start: usize,
+    pub col_end: usize,
+}
+
+struct Crossing {
+    x: f32,
+    winding: i32,
+}
+
+fn row_crossings(points: &[Point2d], y: f32) -> Vec<Crossing> {
+    let mut crossings: Vec<Crossing> = Vec::new();
+    let count: usize = points.len();
+
+    for i in 0..count {
+        let a: Point2d = points[i];
+        let b: Point2d = points[(i + 1) % count];
+
+        if a.y == b.y {
+            continue; //horizontal edges never cross a sample line
+        }
+
+        let (low, high, winding) = match a.y < b.y {
+            true => (a, b, 1),
+            false => (b, a, -1),
+        };
+
+        //half open so shared vertices are only counted once
+        if y < low.y || y >= high.y {
+            continue;
+        }
+
+        let x: f32 = low.x + (y - low.y) * (high.x - low.x) / (high.y - low.y);
+        crossings.push(Crossing { x, winding });
+    }
+
+    crossings.sort_by(|a, b| a.x.total_cmp(&b.x));
+    crossings
+}
+
+//Convert a covered interval [x_start, x_end) into pixel columns whose centers lie inside it
+fn push_span(spans: &mut Vec<Span>, row: usize, x_start: f32, x_end: f32, width: usize) {
+    let col_start: f32 = (x_start - 0.5f32).ceil().max(0f32);
+    let col_end: f32 = (x_end - 0.5f32).ceil().min(width as f32);
+
+    if col_end <= col_start {
+        return;
+    }
+
+    spans.push(Span {
+        row,
+        col_start: col_start as usize,
+        col_end: col_end as usize,
+    });
+}
+
+//Rasterize a polygon already in screen space, pixel (row, col) is covered when
+//its center (col + 0.5, row + 0.5) is inside the polygon under the fill rule
+pub fn scanline_fill(polygon: &Polygon2D, rule: FillRule, width: usize, height: usize) -> Vec<Span> {
+    let mut spans: Vec<Span> = Vec::new();
+
+    if polygon.is_empty() || polygon.points.iter().any(|p| !p.is_finite()) {
+        return spans;
+    }
+
+    let y_min: f32 = polygon.points.iter().map(|p| p.y).fold(f32::MAX, f32::min);
+    let y_max: f32 = polygon.points.iter().map(|p| p.y).fold(f32::MIN, f32::max);
+
+    let row_start: usize = (y_min - 0.5f32).ceil().max(0f32) as usize;
+    let row_end: usize = ((y_max - 0.5f32).ceil().max(0f32) as usize).min(height);
+
+    for row in row_start..row_end {
+        let y: f32 = row as f32 + 0.5f32;
+        let crossings: Vec<Crossing> = row_crossings(&polygon.points, y);
+
+        match rule {
+            FillRule::EvenOdd => {
+                for pair in crossings.chunks_exact(2) {
+                    push_span(&mut spans, row, pair[0].x, pair[1].x, width);
+                }
+            }
+            FillRule::NonZero => {
+                let mut winding: i32 = 0;
+                let mut start: f32 = 0f32;
+
+                for crossing in crossings {
+                    let previous: i32 = winding;
+                    winding += crossing.winding;
+
+                    if previous == 0 && winding != 0 {
+                        start = crossing.x;
+                    } else if previous != 0 && winding == 0 {
+                        push_span(&mut spans, row, start, crossing.x, width);
+                    }
+                }
+            }
+        }
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn covered(spans: &[Span], row: usize, col: usize) -> bool {
+        spans
+            .iter()
+            .any(|s| s.row == row && s.col_start <= col && col < s.col_end)
+    }
+
+    fn pentagram() -> Polygon2D {
+        let outer: Vec<Point2d> = (0..5)
+            .map(|i| {
+                let angle: f32 = std::f32::consts::FRAC_PI_2 + i as f32 * 2f32 * std::f32::consts::PI / 5f32;
+                Point2d::new(50f32 + 40f32 * angle.cos(), 50f32 + 40f32 * angle.sin())
+            })
+            .collect();
+
+        Polygon2D::new(vec![outer[0], outer[2], outer[4], outer[1], outer[3]])
+    }
+
+    #[test]
+    fn test_fill_square() {
+        let square: Polygon2D = Polygon2D::new(vec![
+            Point2d::new(2f32, 2f32),
+            Point2d::new(6f32, 2f32),
+            Point2d::new(6f32, 6f32),
+            Point2d::new(2f32, 6f32),
+        ]);
+
+        let spans: Vec<Span> = scanline_fill(&square, FillRule::EvenOdd, 10, 10);
+        let pixels: usize = spans.iter().map(|s| s.col_end - s.col_start).sum();
+
+        assert_eq!(16, pixels);
+        assert!(covered(&spans, 2, 2));
+        assert!(!covered(&spans, 6, 6));
+    }
+
+    #[test]
+    fn test_fill_clipped_to_canvas() {
+        let square: Polygon2D = Polygon2D::new(vec![
+            Point2d::new(-5f32, -5f32),
+            Point2d::new(5f32, -5f32),
+            Point2d::new(5f32, 5f32),
+            Point2d::new(-5f32, 5f32),
+        ]);
+
+        let spans: Vec<Span> = scanline_fill(&square, FillRule::NonZero, 4, 4);
+        let pixels: usize = spans.iter().map(|s| s.col_end - s.col_start).sum();
+
+        assert_eq!(16, pixels);
+    }
+
+    #[test]
+    fn test_fill_rules_differ_on_pentagram() {
+        let star: Polygon2D = pentagram();
+
+        let even_odd: Vec<Span> = scanline_fill(&star, FillRule::EvenOdd, 100, 100);
+        let non_zero: Vec<Span> = scanline_fill(&star, FillRule::NonZero, 100, 100);
+
+        assert!(!covered(&even_odd, 50, 50), "Even odd leaves the center empty");
+        assert!(covered(&non_zero, 50, 50), "Non zero fills the center");
+    }
+}
@@ -1,12 +1,69 @@
-use crate::Camera;
-use minifb::Window;
+use crate::{
+    display::{camera::Camera, input::InputFrame},
+    entities::{line2d::Line2D, point2d::Point2d, rectangle2d::Rectangle2D},
+};
+
+//The registered scenario that runs when none is named
+pub const DEFAULT_SCENARIO: &str = "line-intersection";
+
+//Everything a scenario can be configured with from the command line. Here and
+//not in the registry so the command line parses without the scenarios built
+#[derive(Debug, Clone)]
+pub struct ScenarioConfig {
+    pub count: Option<usize>,
+    pub bounds: Rectangle2D,
+    pub seed: Option<u64>,
+    pub input: Option<String>,
+    pub trace: Option<String>,
+}
+
+//A key or key combination and what it does, listed by the help overlay
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyBinding {
+    pub key: &'static str,
+    pub action: &'static str,
+}
+
+impl KeyBinding {
+    pub const fn new(key: &'static str, action: &'static str) -> Self {
+        KeyBinding { key, action }
+    }
+}
+
+//What a scenario was run on, saved as the entities of a scenario file so it
+//can be passed back with --input
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScenarioInput {
+    Points(Vec<Point2d>),
+    Lines(Vec<Line2D>),
+}
 
 pub trait Scenario {
     fn initialize(&mut self) -> Result<(), &'static str>;
 
-    fn handle_input(&mut self, window: &Window);
+    fn handle_input(&mut self, input: &InputFrame);
 
     fn process(&mut self, camera: &mut Camera);
 
     fn redraw(&mut self) -> bool;
+
+    //Keys handle_input reacts to
+    fn key_bindings(&self) -> Vec<KeyBinding> {
+        Vec::new()
+    }
+
+    //The entities the scenario currently works on, None when it has no input of its own
+    fn input(&self) -> Option<ScenarioInput> {
+        None
+    }
+}
+
+//Formats bindings as aligned "key  action" rows
+pub fn describe_bindings(bindings: &[KeyBinding]) -> Vec<String> {
+    let width: usize = bindings.iter().map(|b| b.key.len()).max().unwrap_or(0);
+
+    bindings
+        .iter()
+        .map(|b| format!("{:<width$}  {}", b.key, b.action, width = width))
+        .collect()
 }
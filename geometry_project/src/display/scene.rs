@@ -1,107 +1,531 @@
 use serde::{Deserialize, Serialize};
 
+use crate::display::label::{Label, LegendEntry};
+use crate::display::line_style::LineStyle;
 use crate::display::rgb::RGB;
-use crate::entities::{line2d::Line2D, point2d::Point2d};
+use crate::display::rgba::RGBA;
+use crate::display::style::{FillStyle, PointStyle, DEFAULT_LINE_COLOR};
+use crate::entities::{
+    line2d::Line2D, point2d::Point2d, polygon2d::Polygon2D, rectangle2d::Rectangle2D,
+};
 
-#[derive(Serialize, Deserialize, Debug)]
+//Layer everything goes to when no layer is named
+pub const DEFAULT_LAYER: &str = "default";
+
+//A single drawable, it carries its own style so nothing has to be kept in sync
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind")]
+pub enum Primitive {
+    Point {
+        point: Point2d,
+        style: PointStyle,
+    },
+    Line {
+        line: Line2D,
+        color: RGB,
+        style: LineStyle,
+    },
+    Polygon {
+        polygon: Polygon2D,
+        style: FillStyle,
+    },
+    Label(Label),
+}
+
+impl Primitive {
+    //World points that make up the primitive, used for bounds
+    pub fn points(&self) -> Vec<Point2d> {
+        match self {
+            Primitive::Point { point, .. } => vec![*point],
+            Primitive::Line { line, .. } => vec![line.start, line.end],
+            Primitive::Polygon { polygon, .. } => polygon.points.clone(),
+            Primitive::Label(label) => vec![label.position],
+        }
+    }
+}
+
+fn visible_default() -> bool {
+    true
+}
+
+//Named group of primitives. Layers with a higher z order are drawn on top
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Layer {
+    pub name: String,
+    #[serde(default)]
+    pub z_order: i32,
+    #[serde(default = "visible_default")]
+    pub visible: bool,
+    pub primitives: Vec<Primitive>,
+}
+
+impl Layer {
+    pub fn new(name: &str, z_order: i32) -> Self {
+        Layer {
+            name: name.to_string(),
+            z_order,
+            visible: true,
+            primitives: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.primitives.is_empty()
+    }
+
+    pub fn push(&mut self, primitive: Primitive) {
+        self.primitives.push(primitive);
+    }
+
+    pub fn push_point(&mut self, point: Point2d) {
+        self.push_point_styled(point, PointStyle::default());
+    }
+
+    pub fn push_point_styled(&mut self, point: Point2d, style: PointStyle) {
+        self.push(Primitive::Point { point, style });
+    }
+
+    pub fn push_points<T>(&mut self, points: T)
+    where
+        T: IntoIterator<Item = Point2d>,
+    {
+        self.push_points_styled(points, PointStyle::default());
+    }
+
+    pub fn push_points_styled<T>(&mut self, points: T, style: PointStyle)
+    where
+        T: IntoIterator<Item = Point2d>,
+    {
+        for point in points {
+            self.push_point_styled(point, style);
+        }
+    }
+
+    pub fn push_point_colors<T>(&mut self, points_colors: T)
+    where
+        T: IntoIterator<Item = (Point2d, RGB)>,
+    {
+        for (point, color) in points_colors {
+            self.push_point_styled(point, color.into());
+        }
+    }
+
+    pub fn push_line(&mut self, line: Line2D) {
+        self.push_line_colored(line, DEFAULT_LINE_COLOR);
+    }
+
+    pub fn push_line_colored(&mut self, line: Line2D, color: RGB) {
+        self.push_line_styled(line, color, LineStyle::default());
+    }
+
+    pub fn push_line_styled(&mut self, line: Line2D, color: RGB, style: LineStyle) {
+        self.push(Primitive::Line { line, color, style });
+    }
+
+    pub fn push_lines<T>(&mut self, lines: T)
+    where
+        T: IntoIterator<Item = Line2D>,
+    {
+        for line in lines {
+            self.push_line(line);
+        }
+    }
+
+    pub fn push_lines_color<T>(&mut self, lines_colors: T)
+    where
+        T: IntoIterator<Item = (Line2D, RGB)>,
+    {
+        for (line, color) in lines_colors {
+            self.push_line_colored(line, color);
+        }
+    }
+
+    //Outline only, one line per edge
+    pub fn push_polygon(&mut self, polygon: &Polygon2D, color: RGB) {
+        for line in polygon.lines() {
+            self.push_line_colored(line, color);
+        }
+    }
+
+    pub fn push_filled_polygon(&mut self, polygon: Polygon2D, fill: RGBA, stroke: Option<RGB>) {
+        self.push_polygon_styled(polygon, FillStyle::new(fill, stroke));
+    }
+
+    pub fn push_polygon_styled(&mut self, polygon: Polygon2D, style: FillStyle) {
+        self.push(Primitive::Polygon { polygon, style });
+    }
+
+    pub fn push_label(&mut self, label: Label) {
+        self.push(Primitive::Label(label));
+    }
+
+    pub fn push_labels<T>(&mut self, labels: T)
+    where
+        T: IntoIterator<Item = Label>,
+    {
+        for label in labels {
+            self.push_label(label);
+        }
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point2d> + '_ {
+        self.primitives.iter().filter_map(|p| match p {
+            Primitive::Point { point, .. } => Some(*point),
+            _ => None,
+        })
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Line2D> + '_ {
+        self.primitives.iter().filter_map(|p| match p {
+            Primitive::Line { line, .. } => Some(*line),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "SceneFormat")]
 pub struct Scene {
-    pub points: Vec<Point2d>,
-    pub lines: Vec<Line2D>,
-    pub point_colors: Vec<RGB>,
-    pub line_colors: Vec<RGB>,
+    pub layers: Vec<Layer>,
+    pub legend: Vec<LegendEntry>,
+    //Free text about the state the scene shows, like a stack or a queue
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
 }
 
-impl From<&Vec<Line2D>> for Scene{
+impl From<&Vec<Line2D>> for Scene {
     fn from(value: &Vec<Line2D>) -> Self {
-        Scene {
-            points: Vec::new(),
-            lines: value.clone(),
-            point_colors: Vec::new(),
-            line_colors: Vec::new()
-        }
+        Scene::from_lines(value.clone())
     }
 }
 
 impl Scene {
     pub fn new() -> Self {
         Scene {
-            points: Vec::new(),
-            lines: Vec::new(),
-            point_colors: Vec::new(),
-            line_colors: Vec::new(),
+            layers: Vec::new(),
+            legend: Vec::new(),
+            notes: Vec::new(),
         }
     }
 
     pub fn from_lines(lines: Vec<Line2D>) -> Self {
-        Scene {
-            points: Vec::new(),
-            lines,
-            point_colors: Vec::new(),
-            line_colors: Vec::new(),
-        }
+        let mut scene: Scene = Scene::new();
+        scene.push_lines(lines);
+
+        scene
+    }
+
+    //Scene serialized as JSON, either layered or in the flat SceneLogger form
+    pub fn load(path: &str) -> Result<Scene, &'static str> {
+        let text: String = std::fs::read_to_string(path).map_err(|_| "Could not read scene file")?;
+
+        serde_json::from_str(&text).map_err(|_| "Could not parse scene file")
     }
 
     pub fn clear(&mut self) {
-        self.points.clear();
-        self.lines.clear();
-        self.point_colors.clear();
-        self.line_colors.clear();
+        self.layers.clear();
+        self.legend.clear();
+        self.notes.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.legend.is_empty() && self.notes.is_empty() && self.layers.iter().all(|l| l.is_empty())
+    }
+
+    pub fn find_layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|l| l.name == name)
+    }
+
+    //Layer with the given name, new layers are put on top of the existing ones
+    pub fn layer(&mut self, name: &str) -> &mut Layer {
+        let index: usize = match self.layers.iter().position(|l| l.name == name) {
+            Some(i) => i,
+            None => {
+                let z_order: i32 = self.layers.iter().map(|l| l.z_order + 1).max().unwrap_or(0);
+                self.layers.push(Layer::new(name, z_order));
+                self.layers.len() - 1
+            }
+        };
+
+        &mut self.layers[index]
+    }
+
+    pub fn default_layer(&mut self) -> &mut Layer {
+        self.layer(DEFAULT_LAYER)
+    }
+
+    //Bottom to top, layers with the same z order keep their insertion order
+    pub fn sorted_layers(&self) -> Vec<&Layer> {
+        let mut layers: Vec<&Layer> = self.layers.iter().collect();
+        layers.sort_by_key(|l| l.z_order);
+
+        layers
+    }
+
+    pub fn set_layer_visible(&mut self, name: &str, visible: bool) {
+        for layer in self.layers.iter_mut().filter(|l| l.name == name) {
+            layer.visible = visible;
+        }
+    }
+
+    pub fn primitives(&self) -> impl Iterator<Item = &Primitive> + '_ {
+        self.layers.iter().flat_map(|l| l.primitives.iter())
+    }
+
+    pub fn points(&self) -> Vec<Point2d> {
+        self.layers.iter().flat_map(|l| l.points()).collect()
+    }
+
+    pub fn lines(&self) -> Vec<Line2D> {
+        self.layers.iter().flat_map(|l| l.lines()).collect()
+    }
+
+    //Bounding box of every primitive, hidden layers included
+    pub fn bounds(&self) -> Option<Rectangle2D> {
+        Rectangle2D::from_points(self.primitives().flat_map(|p| p.points()))
+    }
+
+    //Layers are matched by name, primitives of a matching layer are appended
+    pub fn merge(&mut self, other: Scene) {
+        for mut layer in other.layers {
+            match self.layers.iter_mut().find(|l| l.name == layer.name) {
+                Some(existing) => existing.primitives.append(&mut layer.primitives),
+                None => self.layers.push(layer),
+            }
+        }
+
+        self.legend.extend(other.legend);
+        self.notes.extend(other.notes);
     }
 
     pub fn push_line(&mut self, line: Line2D) {
-        self.lines.push(line);
+        self.default_layer().push_line(line);
     }
 
     pub fn push_point(&mut self, point: Point2d) {
-        self.points.push(point);
+        self.default_layer().push_point(point);
+    }
+
+    pub fn push_label(&mut self, label: Label) {
+        self.default_layer().push_label(label);
     }
 
-    pub fn merge(&mut self, mut other: Scene) {
-        self.lines.append(&mut other.lines);
-        self.points.append(&mut other.points);
-        self.point_colors.append(&mut other.point_colors);
-        self.line_colors.append(&mut other.line_colors);
+    pub fn push_legend_entry(&mut self, entry: LegendEntry) {
+        self.legend.push(entry);
+    }
+
+    pub fn push_note(&mut self, note: String) {
+        self.notes.push(note);
+    }
+
+    pub fn push_filled_polygon(&mut self, polygon: Polygon2D, fill: RGBA, stroke: Option<RGB>) {
+        self.default_layer().push_filled_polygon(polygon, fill, stroke);
     }
 
     pub fn push_points<T>(&mut self, points: T)
     where
         T: IntoIterator<Item = Point2d>,
     {
-        for point in points {
-            self.points.push(point);
-        }
+        self.default_layer().push_points(points);
     }
 
     pub fn push_point_colors<T>(&mut self, points_colors: T)
     where
         T: IntoIterator<Item = (Point2d, RGB)>,
     {
-        for (point, color) in points_colors {
-            self.points.push(point);
-            self.point_colors.push(color);
-        }
+        self.default_layer().push_point_colors(points_colors);
     }
 
     pub fn push_lines<T>(&mut self, lines: T)
     where
         T: IntoIterator<Item = Line2D>,
     {
-        for line in lines {
-            self.lines.push(line);
-        }
+        self.default_layer().push_lines(lines);
     }
 
-    pub fn push_line_color(&mut self, line_color: RGB) {
-        self.line_colors.push(line_color);
+    pub fn push_line_styled(&mut self, line: Line2D, color: RGB, style: LineStyle) {
+        self.default_layer().push_line_styled(line, color, style);
     }
 
     pub fn push_lines_color<T>(&mut self, lines_colors: T)
     where
         T: IntoIterator<Item = (Line2D, RGB)>,
     {
-        for (line, color) in lines_colors {
-            self.lines.push(line);
-            self.line_colors.push(color);
+        self.default_layer().push_lines_color(lines_colors);
+    }
+}
+
+//Scenes are read either in the layered form or in the flat form with parallel
+//colour vectors that older SceneLogger output was written in. The flat form
+//takes no other fields, a layered scene that does not parse must not turn into
+//an empty flat one
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SceneFormat {
+    Layered(LayeredScene),
+    Flat(FlatScene),
+}
+
+#[derive(Deserialize)]
+struct LayeredScene {
+    layers: Vec<Layer>,
+    #[serde(default)]
+    legend: Vec<LegendEntry>,
+    #[serde(default)]
+    notes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FlatScene {
+    #[serde(default)]
+    points: Vec<Point2d>,
+    #[serde(default)]
+    lines: Vec<Line2D>,
+    #[serde(default)]
+    point_colors: Vec<RGB>,
+    #[serde(default)]
+    line_colors: Vec<RGB>,
+    #[serde(default)]
+    line_styles: Vec<LineStyle>,
+    #[serde(default)]
+    polygons: Vec<Polygon2D>,
+    #[serde(default)]
+    polygon_fill_colors: Vec<RGBA>,
+    #[serde(default)]
+    polygon_stroke_colors: Vec<Option<RGB>>,
+    #[serde(default)]
+    labels: Vec<Label>,
+    #[serde(default)]
+    legend: Vec<LegendEntry>,
+}
+
+//Missing colours fall back to the defaults the old renderer used
+impl From<FlatScene> for Scene {
+    fn from(flat: FlatScene) -> Self {
+        let mut scene: Scene = Scene::new();
+        let layer: &mut Layer = scene.default_layer();
+
+        for (i, polygon) in flat.polygons.into_iter().enumerate() {
+            let fill: RGBA = flat.polygon_fill_colors.get(i).copied().unwrap_or(RGBA::transparent());
+            let stroke: Option<RGB> = flat.polygon_stroke_colors.get(i).copied().flatten();
+
+            layer.push_filled_polygon(polygon, fill, stroke);
+        }
+
+        for (i, line) in flat.lines.into_iter().enumerate() {
+            let color: RGB = flat.line_colors.get(i).copied().unwrap_or(DEFAULT_LINE_COLOR);
+            let style: LineStyle = flat.line_styles.get(i).copied().unwrap_or_default();
+
+            layer.push_line_styled(line, color, style);
+        }
+
+        for (i, point) in flat.points.into_iter().enumerate() {
+            match flat.point_colors.get(i) {
+                Some(color) => layer.push_point_styled(point, (*color).into()),
+                None => layer.push_point(point),
+            }
+        }
+
+        layer.push_labels(flat.labels);
+        scene.legend = flat.legend;
+
+        scene
+    }
+}
+
+impl From<SceneFormat> for Scene {
+    fn from(format: SceneFormat) -> Self {
+        match format {
+            SceneFormat::Layered(layered) => Scene {
+                layers: layered.layers,
+                legend: layered.legend,
+                notes: layered.notes,
+            },
+            SceneFormat::Flat(flat) => flat.into(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layers_sorted_by_z_order() {
+        let mut scene: Scene = Scene::new();
+        scene.layer("top").push_point(Point2d::new(1f32, 1f32));
+        scene.layer("bottom").push_point(Point2d::new(2f32, 2f32));
+        scene.layer("top").z_order = 5;
+
+        let names: Vec<&str> = scene.sorted_layers().iter().map(|l| l.name.as_str()).collect();
+
+        assert_eq!(vec!["bottom", "top"], names);
+    }
+
+    #[test]
+    fn test_merge_by_layer_name() {
+        let mut a: Scene = Scene::new();
+        a.layer("hull").push_point(Point2d::new(1f32, 1f32));
+
+        let mut b: Scene = Scene::new();
+        b.layer("hull").push_point(Point2d::new(2f32, 2f32));
+        b.layer("input").push_point(Point2d::new(3f32, 3f32));
+
+        a.merge(b);
+
+        assert_eq!(2, a.layers.len());
+        assert_eq!(2, a.find_layer("hull").unwrap().primitives.len());
+    }
+
+    #[test]
+    fn test_layered_round_trip() {
+        let mut scene: Scene = Scene::new();
+        scene.layer("segments").push_line_colored(Line2D::new_flat(0f32, 0f32, 1f32, 1f32), RGB::green());
+        scene.layer("points").push_point_styled(Point2d::new(0.5f32, 0.5f32), PointStyle::new(RGB::blue(), 3));
+        scene.push_legend_entry(LegendEntry::new(RGB::green(), "segment"));
+        scene.push_note("stack: 2".to_string());
+
+        let json: String = serde_json::to_string(&scene).unwrap();
+        let read: Scene = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(scene, read);
+    }
+
+    #[test]
+    fn test_reads_flat_scene_logger_output() {
+        let json: &str = r#"{
+            "points": [{"x": 1.0, "y": 2.0}, {"x": 3.0, "y": 4.0}],
+            "lines": [{"start": {"x": 0.0, "y": 0.0}, "end": {"x": 1.0, "y": 0.0}}],
+            "point_colors": [65280],
+            "line_colors": []
+        }"#;
+
+        let scene: Scene = serde_json::from_str(json).unwrap();
+        let layer: &Layer = scene.find_layer(DEFAULT_LAYER).unwrap();
+
+        assert_eq!(3, layer.primitives.len());
+        assert!(layer.primitives.contains(&Primitive::Line {
+            line: Line2D::new_flat(0f32, 0f32, 1f32, 0f32),
+            color: DEFAULT_LINE_COLOR,
+            style: LineStyle::default(),
+        }));
+        assert!(layer.primitives.contains(&Primitive::Point {
+            point: Point2d::new(1f32, 2f32),
+            style: PointStyle::new(RGB(65280), 1),
+        }));
+        assert!(layer.primitives.contains(&Primitive::Point {
+            point: Point2d::new(3f32, 4f32),
+            style: PointStyle::default(),
+        }));
+    }
+
+    #[test]
+    fn test_broken_layered_scene_is_an_error() {
+        let json: &str = r#"{
+            "layers": [{"name": "hull", "z_order": 0, "visible": true, "primitives": [{"kind": "Point", "point": {"x": 1.0}}]}]
+        }"#;
+
+        assert!(serde_json::from_str::<Scene>(json).is_err());
+    }
+}
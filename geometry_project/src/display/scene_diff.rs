@@ -0,0 +1,177 @@
+use crate::{
+    display::{
+        label::LegendEntry,
+        rgb::RGB,
+        scene::Scene,
+        style::PointStyle,
+    },
+    entities::{line2d::Line2D, point2d::Point2d},
+};
+
+pub const COMMON_COLOR: RGB = RGB(0x707070);
+pub const REMOVED_COLOR: RGB = RGB(0xe04040);
+pub const ADDED_COLOR: RGB = RGB(0x40d040);
+
+//Removed elements are only in the scene diff was called on, added ones only
+//in the other scene
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SceneDiff {
+    pub added_points: Vec<Point2d>,
+    pub removed_points: Vec<Point2d>,
+    pub common_points: Vec<Point2d>,
+    pub added_lines: Vec<Line2D>,
+    pub removed_lines: Vec<Line2D>,
+    pub common_lines: Vec<Line2D>,
+}
+
+//Every element of after is matched at most once, so duplicates are counted
+fn match_elements<T, F>(before: Vec<T>, after: Vec<T>, equal: F) -> (Vec<T>, Vec<T>, Vec<T>)
+where
+    T: Copy,
+    F: Fn(&T, &T) -> bool,
+{
+    let mut unmatched: Vec<Option<T>> = after.into_iter().map(Some).collect();
+    let mut removed: Vec<T> = Vec::new();
+    let mut common: Vec<T> = Vec::new();
+
+    for element in before {
+        let found: Option<&mut Option<T>> = unmatched
+            .iter_mut()
+            .find(|o| matches!(o, Some(other) if equal(&element, other)));
+
+        match found {
+            Some(slot) => {
+                *slot = None;
+                common.push(element);
+            }
+            None => removed.push(element),
+        }
+    }
+
+    let added: Vec<T> = unmatched.into_iter().flatten().collect();
+
+    (added, removed, common)
+}
+
+impl Scene {
+    //Points and lines of all layers, lines match regardless of direction
+    pub fn diff(&self, other: &Scene, epsilon: f32) -> SceneDiff {
+        let (added_points, removed_points, common_points) =
+            match_elements(self.points(), other.points(), |a, b| a.approx_equals(b, epsilon));
+
+        let (added_lines, removed_lines, common_lines) =
+            match_elements(self.lines(), other.lines(), |a, b| a.approx_equals(b, epsilon));
+
+        SceneDiff {
+            added_points,
+            removed_points,
+            common_points,
+            added_lines,
+            removed_lines,
+            common_lines,
+        }
+    }
+}
+
+impl SceneDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_points.is_empty()
+            && self.removed_points.is_empty()
+            && self.added_lines.is_empty()
+            && self.removed_lines.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "points +{} -{} ={}, lines +{} -{} ={}",
+            self.added_points.len(),
+            self.removed_points.len(),
+            self.common_points.len(),
+            self.added_lines.len(),
+            self.removed_lines.len(),
+            self.common_lines.len()
+        )
+    }
+
+    //Common elements in grey underneath, removed in red and added in green on top
+    pub fn to_scene(&self) -> Scene {
+        let mut scene: Scene = Scene::new();
+
+        let layers: [(&str, &Vec<Point2d>, &Vec<Line2D>, RGB); 3] = [
+            ("common", &self.common_points, &self.common_lines, COMMON_COLOR),
+            ("removed", &self.removed_points, &self.removed_lines, REMOVED_COLOR),
+            ("added", &self.added_points, &self.added_lines, ADDED_COLOR),
+        ];
+
+        for (name, points, lines, color) in layers {
+            let layer = scene.layer(name);
+            layer.push_lines_color(lines.iter().map(|l| (*l, color)));
+            layer.push_points_styled(points.iter().copied(), PointStyle::new(color, 3));
+        }
+
+        scene.push_legend_entry(LegendEntry::new(COMMON_COLOR, "common"));
+        scene.push_legend_entry(LegendEntry::new(REMOVED_COLOR, "removed"));
+        scene.push_legend_entry(LegendEntry::new(ADDED_COLOR, "added"));
+
+        scene
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 0.001;
+
+    #[test]
+    fn test_diff_points_within_epsilon() {
+        let mut before: Scene = Scene::new();
+        before.push_points([Point2d::new(1f32, 1f32), Point2d::new(2f32, 2f32)]);
+
+        let mut after: Scene = Scene::new();
+        after.layer("other").push_points([Point2d::new(1.0001f32, 1f32), Point2d::new(3f32, 3f32)]);
+
+        let diff: SceneDiff = before.diff(&after, EPSILON);
+
+        assert_eq!(vec![Point2d::new(1f32, 1f32)], diff.common_points);
+        assert_eq!(vec![Point2d::new(2f32, 2f32)], diff.removed_points);
+        assert_eq!(vec![Point2d::new(3f32, 3f32)], diff.added_points);
+    }
+
+    #[test]
+    fn test_diff_lines_ignore_direction() {
+        let before: Scene = Scene::from_lines(vec![Line2D::new_flat(0f32, 0f32, 1f32, 1f32)]);
+        let after: Scene = Scene::from_lines(vec![
+            Line2D::new_flat(1f32, 1f32, 0f32, 0f32),
+            Line2D::new_flat(0f32, 1f32, 1f32, 0f32),
+        ]);
+
+        let diff: SceneDiff = before.diff(&after, EPSILON);
+
+        assert_eq!(1, diff.common_lines.len());
+        assert_eq!(1, diff.added_lines.len());
+        assert!(diff.removed_lines.is_empty());
+    }
+
+    #[test]
+    fn test_duplicates_match_once() {
+        let mut before: Scene = Scene::new();
+        before.push_points([Point2d::new(1f32, 1f32), Point2d::new(1f32, 1f32)]);
+
+        let mut after: Scene = Scene::new();
+        after.push_point(Point2d::new(1f32, 1f32));
+
+        let diff: SceneDiff = before.diff(&after, EPSILON);
+
+        assert_eq!(1, diff.common_points.len());
+        assert_eq!(1, diff.removed_points.len());
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn test_identical_scenes() {
+        let scene: Scene = Scene::from_lines(vec![Line2D::new_flat(0f32, 0f32, 1f32, 1f32)]);
+
+        assert!(scene.diff(&scene, EPSILON).is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use data_structures::vec2d::Vec2D;
+use serde::Serialize;
+
+use crate::{
+    display::{
+        animation,
+        camera::Camera,
+        input::{InputFrame, Key, KeyRepeat},
+        rgb::RGB,
+        scenario::{KeyBinding, Scenario},
+    },
+    formats::scenario_serializer::{self, ScenarioFile},
+};
+
+//F1 - F9 belong to the scenario menu, the letters to the scenarios
+pub const SCREENSHOT_KEY: Key = Key::F10;
+pub const SCENE_KEY: Key = Key::F11;
+pub const INPUT_KEY: Key = Key::F12;
+
+pub const SNAPSHOT_KEY_BINDINGS: [KeyBinding; 3] = [
+    KeyBinding::new("F10", "save a screenshot"),
+    KeyBinding::new("F11", "save the drawn scene as json"),
+    KeyBinding::new("F12", "save the scenario input"),
+];
+
+//Milliseconds since the epoch, keeps two saves in the same second apart
+pub fn timestamp() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+//screenshot_1718000000000.png, input files can be passed back with --input
+pub fn snapshot_path(kind: &str, timestamp: u128, extension: &str) -> String {
+    format!("{}_{}.{}", kind, timestamp, extension)
+}
+
+pub fn save_screenshot(buffer: &Vec2D<RGB>, path: &str) -> io::Result<()> {
+    animation::write_png(buffer, BufWriter::new(File::create(path)?))
+}
+
+pub fn save_json<T: Serialize>(value: &T, path: &str) -> io::Result<()> {
+    serde_json::to_writer_pretty(BufWriter::new(File::create(path)?), value).map_err(io::Error::from)
+}
+
+fn describe<E: std::fmt::Display>(what: &str, path: &str, result: Result<(), E>) -> String {
+    match result {
+        Ok(_) => format!("Saved {} to {}", what, path),
+        Err(e) => format!("Could not save {} to {}: {}", what, path, e),
+    }
+}
+
+//Hotkeys the window loop handles for every scenario. Returns one line per
+//key pressed saying where it was saved or why it was not
+pub fn handle_snapshot_keys(input: &InputFrame, buffer: &Vec2D<RGB>, camera: &Camera, scenario: &dyn Scenario) -> Vec<String> {
+    let mut reports: Vec<String> = Vec::new();
+    let now: u128 = timestamp();
+
+    if input.is_key_pressed(SCREENSHOT_KEY, KeyRepeat::No) {
+        let path: String = snapshot_path("screenshot", now, "png");
+        reports.push(describe("screenshot", &path, save_screenshot(buffer, &path)));
+    }
+
+    if input.is_key_pressed(SCENE_KEY, KeyRepeat::No) {
+        let path: String = snapshot_path("scene", now, "json");
+        reports.push(describe("scene", &path, save_json(camera.scene(), &path)));
+    }
+
+    if input.is_key_pressed(INPUT_KEY, KeyRepeat::No) {
+        match scenario.input() {
+            Some(scenario_input) => {
+                let path: String = snapshot_path("input", now, "json");
+                let file: ScenarioFile = ScenarioFile::new("unknown", scenario_input.into());
+                reports.push(describe("input", &path, scenario_serializer::save_scenario(&file, &path)));
+            }
+            None => reports.push("This scenario has no input to save".to_string()),
+        }
+    }
+
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        display::scenario::ScenarioInput,
+        entities::{line2d::Line2D, point2d::Point2d},
+    };
+    //the scene scenario is only built with the viewer
+    #[cfg(feature = "viewer")]
+    use crate::{
+        display::input::{InputEvent, InputState},
+        scenarios::scene_scenario::SceneScenario,
+    };
+
+    #[test]
+    fn test_snapshot_path() {
+        assert_eq!("scene_1234.json", snapshot_path("scene", 1234, "json"));
+    }
+
+    #[test]
+    fn test_saved_input_loads_as_scenario_file() {
+        let lines: Vec<Line2D> = vec![Line2D::new(Point2d::new(0f32, 0f32), Point2d::new(1f32, 2f32))];
+        let path: String = std::env::temp_dir()
+            .join(snapshot_path("input_test", timestamp(), "json"))
+            .to_string_lossy()
+            .to_string();
+
+        let file: ScenarioFile = ScenarioFile::new("unknown", ScenarioInput::Lines(lines.clone()).into());
+        scenario_serializer::save_scenario(&file, &path).unwrap();
+
+        //read back the way --input reads it
+        let loaded: Vec<Line2D> = scenario_serializer::load_lines(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(lines, loaded);
+    }
+
+    #[cfg(feature = "viewer")]
+    #[test]
+    fn test_scenario_without_input() {
+        let buffer: Vec2D<RGB> = Vec2D::new_from_flatpack(vec![RGB::black(); 4], 2, 2).unwrap();
+        let camera: Camera = Camera::new(10f32, 10f32);
+        let scenario: SceneScenario = SceneScenario::new(camera.scene().clone(), Vec::new());
+        let mut state: InputState = InputState::new();
+
+        let idle: InputFrame = state.next_frame(Vec::new());
+        assert!(handle_snapshot_keys(&idle, &buffer, &camera, &scenario).is_empty());
+
+        let pressed: InputFrame = state.next_frame(vec![InputEvent::KeyPressed { key: INPUT_KEY, repeat: false }]);
+        assert_eq!(vec!["This scenario has no input to save".to_string()], handle_snapshot_keys(&pressed, &buffer, &camera, &scenario));
+    }
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use crate::display::{rgb::RGB, rgba::RGBA, scanline::FillRule};
+
+pub const DEFAULT_POINT_COLOR: RGB = RGB(0xffffff);
+pub const DEFAULT_LINE_COLOR: RGB = RGB(0xff0000);
+
+//Points are drawn as size x size squares centered on the point
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PointStyle {
+    pub color: RGB,
+    pub size: u8,
+}
+
+impl PointStyle {
+    pub fn new(color: RGB, size: u8) -> Self {
+        PointStyle { color, size }
+    }
+}
+
+impl Default for PointStyle {
+    fn default() -> Self {
+        PointStyle::new(DEFAULT_POINT_COLOR, 1)
+    }
+}
+
+impl From<RGB> for PointStyle {
+    fn from(color: RGB) -> Self {
+        PointStyle::new(color, 1)
+    }
+}
+
+//Interior colour and rule of a polygon, the outline is only drawn when stroke is set
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FillStyle {
+    pub fill: RGBA,
+    pub stroke: Option<RGB>,
+    #[serde(default = "default_fill_rule")]
+    pub rule: FillRule,
+}
+
+fn default_fill_rule() -> FillRule {
+    FillRule::EvenOdd
+}
+
+impl FillStyle {
+    pub fn new(fill: RGBA, stroke: Option<RGB>) -> Self {
+        FillStyle {
+            fill,
+            stroke,
+            rule: default_fill_rule(),
+        }
+    }
+
+    pub fn with_rule(mut self, rule: FillRule) -> Self {
+        self.rule = rule;
+        self
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    display::{
+        camera::Camera,
+        input::{InputFrame, Key, KeyRepeat, MouseButton},
+        scenario::KeyBinding,
+    },
+    entities::{point2d::Point2d, rectangle2d::Rectangle2D},
+};
+
+const ZOOM_STEP: f32 = 0.85f32;
+const FIT_MARGIN: f32 = 0.05f32;
+
+const LAYER_KEYS: [Key; 9] = [
+    Key::Key1,
+    Key::Key2,
+    Key::Key3,
+    Key::Key4,
+    Key::Key5,
+    Key::Key6,
+    Key::Key7,
+    Key::Key8,
+    Key::Key9,
+];
+
+pub const VIEW_KEY_BINDINGS: [KeyBinding; 9] = [
+    KeyBinding::new("Wheel", "zoom about the cursor"),
+    KeyBinding::new("Middle drag", "pan, or Space + left drag"),
+    KeyBinding::new("F", "fit the view to the scene"),
+    KeyBinding::new("Home", "reset the view"),
+    KeyBinding::new("G / A / T", "grid, axes, tick labels"),
+    KeyBinding::new("B / I", "scale bar, integer lattice"),
+    KeyBinding::new("1 - 9", "show / hide a layer"),
+    KeyBinding::new("H", "show / hide this help"),
+    KeyBinding::new("Esc", "exit"),
+];
+
+//Mouse wheel zooms about the cursor, middle drag (or space + left drag) pans,
+//F fits the view to the scene and Home resets it. G, A, T, B and I toggle the
+//grid, axes, tick labels, scale bar and integer lattice overlays. Number keys
+//toggle the visibility of the scene layers, 1 being the bottom one, and H the
+//key binding help
+pub struct ViewController {
+    home: Rectangle2D,
+    width: usize,
+    height: usize,
+    drag_from: Option<Point2d>,
+    cursor_world: Option<Point2d>,
+}
+
+impl ViewController {
+    pub fn new(home: Rectangle2D, width: usize, height: usize) -> Self {
+        ViewController {
+            home,
+            width,
+            height,
+            drag_from: None,
+            cursor_world: None,
+        }
+    }
+
+    pub fn cursor_world(&self) -> Option<Point2d> {
+        self.cursor_world
+    }
+
+    fn to_world(&self, camera: &Camera, screen: Point2d) -> Option<Point2d> {
+        camera.screen_to_world(screen, self.width, self.height)
+    }
+
+    fn fit_to_scene(&self, camera: &mut Camera) {
+        let bounds: Rectangle2D = match camera.bounds() {
+            Some(b) => b,
+            None => {
+                return;
+            }
+        };
+
+        let margin: f32 = f32::max(bounds.width(), bounds.height()) * FIT_MARGIN;
+        let fitted: Rectangle2D = bounds.expand(f32::max(margin, 1f32));
+
+        camera.view_port = fitted;
+    }
+
+    fn is_dragging(input: &InputFrame) -> bool {
+        input.is_mouse_down(MouseButton::Middle)
+            || (input.is_key_down(Key::Space) && input.is_mouse_down(MouseButton::Left))
+    }
+
+    fn handle_overlay_toggles(input: &InputFrame, camera: &mut Camera) -> bool {
+        let toggles: [(Key, &mut bool); 6] = [
+            (Key::G, &mut camera.overlays.grid),
+            (Key::A, &mut camera.overlays.axes),
+            (Key::T, &mut camera.overlays.tick_labels),
+            (Key::B, &mut camera.overlays.scale_bar),
+            (Key::I, &mut camera.overlays.integer_lattice),
+            (Key::H, &mut camera.overlays.help),
+        ];
+
+        let mut changed: bool = false;
+        for (key, flag) in toggles {
+            if input.is_key_pressed(key, KeyRepeat::No) {
+                *flag = !*flag;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    fn handle_layer_toggles(input: &InputFrame, camera: &mut Camera) -> bool {
+        let mut changed: bool = false;
+
+        for (index, key) in LAYER_KEYS.iter().enumerate() {
+            if input.is_key_pressed(*key, KeyRepeat::No) {
+                changed = camera.toggle_layer(index) || changed;
+            }
+        }
+
+        changed
+    }
+
+    //Returns true when the view port changed and the frame needs to be drawn again
+    pub fn handle_input(&mut self, input: &InputFrame, camera: &mut Camera) -> bool {
+        let mut changed: bool = false;
+        let mouse: Option<Point2d> = input.mouse_pos();
+
+        if input.is_key_pressed(Key::Home, KeyRepeat::No) {
+            camera.view_port = self.home;
+            changed = true;
+        }
+
+        if input.is_key_pressed(Key::F, KeyRepeat::No) {
+            self.fit_to_scene(camera);
+            changed = true;
+        }
+
+        changed = Self::handle_overlay_toggles(input, camera) || changed;
+        changed = Self::handle_layer_toggles(input, camera) || changed;
+
+        if let Some(screen) = mouse {
+            let scroll: f32 = input.scroll();
+
+            if scroll != 0f32 {
+                if let Some(anchor) = self.to_world(camera, screen) {
+                    let factor: f32 = match scroll > 0f32 {
+                        true => ZOOM_STEP,
+                        false => 1f32 / ZOOM_STEP,
+                    };
+
+                    camera.zoom_about(anchor, factor);
+                    changed = true;
+                }
+            }
+        }
+
+        match (mouse, Self::is_dragging(input)) {
+            (Some(screen), true) => {
+                if let Some(from) = self.drag_from {
+                    let start: Option<Point2d> = self.to_world(camera, from);
+                    let end: Option<Point2d> = self.to_world(camera, screen);
+
+                    if let (Some(start), Some(end)) = (start, end) {
+                        //moving the content with the cursor moves the view the other way
+                        camera.pan(start - end);
+                        changed = changed || from != screen;
+                    }
+                }
+
+                self.drag_from = Some(screen);
+            }
+            _ => {
+                self.drag_from = None;
+            }
+        }
+
+        self.cursor_world = mouse.and_then(|screen| self.to_world(camera, screen));
+
+        changed
+    }
+
+    pub fn title(&self, base: &str) -> String {
+        match self.cursor_world {
+            Some(p) => format!("{} - ({:.2}, {:.2})", base, p.x, p.y),
+            None => base.to_string(),
+        }
+    }
+}
+
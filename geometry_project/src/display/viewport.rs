@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{
+    affine_matrix2d::Matrix2D, point2d::Point2d, rectangle2d::Rectangle2D, vect2d::Vector2D,
+};
+
+//Direction world +y points on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum YAxis {
+    Up,
+    Down,
+}
+
+//Maps a world rectangle onto a width x height pixel canvas. When the aspect
+//ratio is preserved the world is scaled uniformly and centered, leaving
+//letterbox bands along the longer screen axis
+#[derive(Debug, Clone, Copy)]
+pub struct ViewTransform {
+    pub world: Rectangle2D,
+    pub width: usize,
+    pub height: usize,
+    pub y_axis: YAxis,
+    pub preserve_aspect: bool,
+}
+
+impl ViewTransform {
+    pub fn new(world: Rectangle2D, width: usize, height: usize, y_axis: YAxis, preserve_aspect: bool) -> Self {
+        ViewTransform {
+            world,
+            width,
+            height,
+            y_axis,
+            preserve_aspect,
+        }
+    }
+
+    //Pixels per world unit along x and y
+    pub fn scale(&self) -> Vector2D {
+        let sx: f32 = self.width as f32 / self.world.width();
+        let sy: f32 = self.height as f32 / self.world.height();
+
+        match self.preserve_aspect {
+            true => Vector2D::new(sx.min(sy), sx.min(sy)),
+            false => Vector2D::new(sx, sy),
+        }
+    }
+
+    //Top left corner of the area the world rectangle lands in
+    pub fn offset(&self) -> Vector2D {
+        let scale: Vector2D = self.scale();
+
+        Vector2D::new(
+            (self.width as f32 - self.world.width() * scale.x) / 2f32,
+            (self.height as f32 - self.world.height() * scale.y) / 2f32,
+        )
+    }
+
+    //Screen rectangle covered by the world, everything outside is letterbox
+    pub fn screen_area(&self) -> Rectangle2D {
+        let scale: Vector2D = self.scale();
+        let offset: Vector2D = self.offset();
+
+        Rectangle2D::new(
+            Point2d::new(offset.x, offset.y),
+            Point2d::new(
+                offset.x + self.world.width() * scale.x,
+                offset.y + self.world.height() * scale.y,
+            ),
+        )
+    }
+
+    pub fn matrix(&self) -> Matrix2D {
+        let scale: Vector2D = self.scale();
+        let offset: Vector2D = self.offset();
+
+        match self.y_axis {
+            YAxis::Down => Matrix2D::skew(
+                Vector2D::new(offset.x - self.world.min.x * scale.x, offset.y - self.world.min.y * scale.y),
+                scale,
+            ),
+            YAxis::Up => Matrix2D::skew(
+                Vector2D::new(offset.x - self.world.min.x * scale.x, offset.y + self.world.max.y * scale.y),
+                Vector2D::new(scale.x, -scale.y),
+            ),
+        }
+    }
+
+    pub fn world_to_screen(&self, point: Point2d) -> Point2d {
+        point * self.matrix()
+    }
+
+    pub fn screen_to_world(&self, point: Point2d) -> Option<Point2d> {
+        self.matrix().inverse().map(|inverse| point * inverse)
+    }
+
+    //World rectangle visible on the whole canvas, letterbox bands included
+    pub fn visible_world(&self) -> Option<Rectangle2D> {
+        let a: Point2d = self.screen_to_world(Point2d::origin())?;
+        let b: Point2d = self.screen_to_world(Point2d::new(self.width as f32, self.height as f32))?;
+
+        Rectangle2D::from_points([a, b])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 0.0001;
+
+    fn assert_round_trip(transform: &ViewTransform, point: Point2d) {
+        let screen: Point2d = transform.world_to_screen(point);
+        let world: Point2d = transform.screen_to_world(screen).unwrap();
+
+        assert!(
+            world.approx_equals(&point, EPSILON),
+            "Expected {} after round trip but got {} (screen {})",
+            point,
+            world,
+            screen
+        );
+    }
+
+    #[test]
+    fn test_negative_world_y_down() {
+        let world: Rectangle2D = Rectangle2D::new(Point2d::new(-50f32, -20f32), Point2d::new(50f32, 80f32));
+        let transform: ViewTransform = ViewTransform::new(world, 200, 200, YAxis::Down, false);
+
+        assert!(transform.world_to_screen(world.min).approx_equals(&Point2d::origin(), EPSILON));
+        assert!(transform
+            .world_to_screen(world.max)
+            .approx_equals(&Point2d::new(200f32, 200f32), EPSILON));
+        assert!(transform
+            .world_to_screen(Point2d::origin())
+            .approx_equals(&Point2d::new(100f32, 40f32), EPSILON));
+    }
+
+    #[test]
+    fn test_y_up_flips_rows() {
+        let world: Rectangle2D = Rectangle2D::new(Point2d::new(-10f32, -10f32), Point2d::new(10f32, 10f32));
+        let transform: ViewTransform = ViewTransform::new(world, 100, 100, YAxis::Up, false);
+
+        let top_left: Point2d = transform.world_to_screen(Point2d::new(-10f32, 10f32));
+        let bottom_right: Point2d = transform.world_to_screen(Point2d::new(10f32, -10f32));
+
+        assert!(top_left.approx_equals(&Point2d::origin(), EPSILON), "top left was {}", top_left);
+        assert!(
+            bottom_right.approx_equals(&Point2d::new(100f32, 100f32), EPSILON),
+            "bottom right was {}",
+            bottom_right
+        );
+    }
+
+    #[test]
+    fn test_letterbox_centers_world() {
+        let world: Rectangle2D = Rectangle2D::new(Point2d::new(0f32, 0f32), Point2d::new(200f32, 100f32));
+        let transform: ViewTransform = ViewTransform::new(world, 100, 100, YAxis::Down, true);
+
+        let area: Rectangle2D = transform.screen_area();
+
+        assert!(area.min.approx_equals(&Point2d::new(0f32, 25f32), EPSILON), "area was {}", area);
+        assert!(area.max.approx_equals(&Point2d::new(100f32, 75f32), EPSILON), "area was {}", area);
+
+        let visible: Rectangle2D = transform.visible_world().unwrap();
+        assert!(visible.min.approx_equals(&Point2d::new(0f32, -50f32), EPSILON), "visible was {}", visible);
+        assert!(visible.max.approx_equals(&Point2d::new(200f32, 150f32), EPSILON), "visible was {}", visible);
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let worlds: [Rectangle2D; 3] = [
+            Rectangle2D::new(Point2d::new(25f32, 25f32), Point2d::new(75f32, 75f32)),
+            Rectangle2D::new(Point2d::new(-300f32, -7f32), Point2d::new(-100f32, 93f32)),
+            Rectangle2D::new(Point2d::new(-1f32, -2f32), Point2d::new(3f32, 0.5f32)),
+        ];
+
+        let points: [Point2d; 4] = [
+            Point2d::new(1.5f32, 1f32),
+            Point2d::new(-150f32, 12.5f32),
+            Point2d::new(60f32, -3f32),
+            Point2d::new(2.25f32, 0.125f32),
+        ];
+
+        for world in worlds {
+            for y_axis in [YAxis::Up, YAxis::Down] {
+                for preserve_aspect in [true, false] {
+                    let transform: ViewTransform = ViewTransform::new(world, 512, 300, y_axis, preserve_aspect);
+
+                    for point in points {
+                        assert_round_trip(&transform, point);
+                    }
+                }
+            }
+        }
+    }
+}
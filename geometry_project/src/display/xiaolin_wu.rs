@@ -0,0 +1,151 @@
+use crate::entities::line2d::Line2D;
+
+//Pixel touched by an anti-aliased line, coverage in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WuPixel {
+    pub row: i64,
+    pub col: i64,
+    pub coverage: f32,
+}
+
+fn fpart(x: f32) -> f32 {
+    x - x.floor()
+}
+
+fn rfpart(x: f32) -> f32 {
+    1f32 - fpart(x)
+}
+
+struct WuPlotter {
+    steep: bool,
+    pixels: Vec<WuPixel>,
+}
+
+impl WuPlotter {
+    fn plot(&mut self, x: f32, y: f32, coverage: f32) {
+        if coverage <= 0f32 {
+            return;
+        }
+
+        let (row, col) = match self.steep {
+            true => (x as i64, y as i64),
+            false => (y as i64, x as i64),
+        };
+
+        self.pixels.push(WuPixel {
+            row,
+            col,
+            coverage: coverage.min(1f32),
+        });
+    }
+}
+
+//Xiaolin Wu's line algorithm, line is in screen space where pixel (row, col)
+//covers [col, col + 1) x [row, row + 1)
+pub fn xiaolin_wu(line: Line2D) -> Vec<WuPixel> {
+    if !line.is_finite() {
+        return Vec::new();
+    }
+
+    //shift so pixel centers sit on integer coordinates
+    let mut x0: f32 = line.start.x - 0.5f32;
+    let mut y0: f32 = line.start.y - 0.5f32;
+    let mut x1: f32 = line.end.x - 0.5f32;
+    let mut y1: f32 = line.end.y - 0.5f32;
+
+    let steep: bool = (y1 - y0).abs() > (x1 - x0).abs();
+
+    if steep {
+        std::mem::swap(&mut x0, &mut y0);
+        std::mem::swap(&mut x1, &mut y1);
+    }
+
+    if x0 > x1 {
+        std::mem::swap(&mut x0, &mut x1);
+        std::mem::swap(&mut y0, &mut y1);
+    }
+
+    let dx: f32 = x1 - x0;
+    let dy: f32 = y1 - y0;
+    let gradient: f32 = match dx == 0f32 {
+        true => 1f32,
+        false => dy / dx,
+    };
+
+    let mut plotter: WuPlotter = WuPlotter {
+        steep,
+        pixels: Vec::new(),
+    };
+
+    let x_end: f32 = x0.round();
+    let y_end: f32 = y0 + gradient * (x_end - x0);
+    let x_gap: f32 = rfpart(x0 + 0.5f32);
+    let x_pixel_1: f32 = x_end;
+    plotter.plot(x_pixel_1, y_end.floor(), rfpart(y_end) * x_gap);
+    plotter.plot(x_pixel_1, y_end.floor() + 1f32, fpart(y_end) * x_gap);
+
+    let mut inter_y: f32 = y_end + gradient;
+
+    let x_end: f32 = x1.round();
+    let y_end: f32 = y1 + gradient * (x_end - x1);
+    let x_gap: f32 = fpart(x1 + 0.5f32);
+    let x_pixel_2: f32 = x_end;
+
+    if x_pixel_2 != x_pixel_1 {
+        plotter.plot(x_pixel_2, y_end.floor(), rfpart(y_end) * x_gap);
+        plotter.plot(x_pixel_2, y_end.floor() + 1f32, fpart(y_end) * x_gap);
+    }
+
+    let mut x: f32 = x_pixel_1 + 1f32;
+    while x < x_pixel_2 {
+        plotter.plot(x, inter_y.floor(), rfpart(inter_y));
+        plotter.plot(x, inter_y.floor() + 1f32, fpart(inter_y));
+        inter_y += gradient;
+        x += 1f32;
+    }
+
+    plotter.pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_horizontal_full_coverage() {
+        let line: Line2D = Line2D::new_flat(0.5f32, 2.5f32, 9.5f32, 2.5f32);
+        let pixels: Vec<WuPixel> = xiaolin_wu(line);
+
+        for col in 1..9 {
+            assert!(
+                pixels
+                    .iter()
+                    .any(|p| p.row == 2 && p.col == col && p.coverage == 1f32),
+                "Expected full coverage at column {}",
+                col
+            );
+        }
+
+        assert!(pixels.iter().all(|p| p.row == 2));
+    }
+
+    #[test]
+    fn test_diagonal_splits_coverage() {
+        let line: Line2D = Line2D::new_flat(0f32, 0f32, 10f32, 5f32);
+        let pixels: Vec<WuPixel> = xiaolin_wu(line);
+
+        let total: f32 = pixels.iter().map(|p| p.coverage).sum();
+
+        assert!(pixels.iter().any(|p| p.coverage < 1f32));
+        assert!((total - 10f32).abs() < 1.5f32, "Coverage should roughly match the run length, was {}", total);
+    }
+
+    #[test]
+    fn test_steep_line_swaps_axes() {
+        let line: Line2D = Line2D::new_flat(3.5f32, 0.5f32, 3.5f32, 9.5f32);
+        let pixels: Vec<WuPixel> = xiaolin_wu(line);
+
+        assert!(pixels.iter().all(|p| p.col == 3));
+        assert!(pixels.iter().any(|p| p.row == 5));
+    }
+}
@@ -44,6 +44,10 @@ impl Mul<Row> for Column {
 }
 
 impl Matrix2D {
+    pub fn identity() -> Self {
+        Matrix2D::skew(Vector2D::new(0f32, 0f32), Vector2D::new(1f32, 1f32))
+    }
+
     pub fn skew(translate: Vector2D, scale: Vector2D) -> Self {
         Matrix2D {
             r1: Row {
@@ -83,4 +87,97 @@ impl Matrix2D {
             },
         }
     }
+
+    //Inverse of the affine part, the last row is assumed to be (0, 0, 1)
+    pub fn inverse(&self) -> Option<Matrix2D> {
+        let a: f32 = self.r1.c1;
+        let b: f32 = self.r1.c2;
+        let tx: f32 = self.r1.c3;
+        let c: f32 = self.r2.c1;
+        let d: f32 = self.r2.c2;
+        let ty: f32 = self.r2.c3;
+
+        let det: f32 = a * d - b * c;
+
+        //singular when the determinant is lost in the rounding of its products,
+        //so a matrix scaling by 0.0001 still inverts
+        if det.abs() <= f32::EPSILON * (a * d).abs().max((b * c).abs()) || !det.is_finite() {
+            return None;
+        }
+
+        let ia: f32 = d / det;
+        let ib: f32 = -b / det;
+        let ic: f32 = -c / det;
+        let id: f32 = a / det;
+
+        Some(Matrix2D {
+            r1: Row {
+                c1: ia,
+                c2: ib,
+                c3: -(ia * tx + ib * ty),
+            },
+            r2: Row {
+                c1: ic,
+                c2: id,
+                c3: -(ic * tx + id * ty),
+            },
+            r3: Row {
+                c1: 0f32,
+                c2: 0f32,
+                c3: 1f32,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::point2d::Point2d;
+
+    #[test]
+    fn test_inverse_round_trip() {
+        let matrix: Matrix2D = Matrix2D::skew(Vector2D::new(-25f32, 40f32), Vector2D::new(5.12f32, -2f32));
+        let inverse: Matrix2D = matrix.inverse().unwrap();
+
+        let point: Point2d = Point2d::new(31.5f32, -7.25f32);
+        let round_trip: Point2d = (point * matrix) * inverse;
+
+        assert!(round_trip.approx_equals(&point, 0.0001), "Round trip was {}", round_trip);
+    }
+
+    #[test]
+    fn test_singular_has_no_inverse() {
+        let matrix: Matrix2D = Matrix2D::skew(Vector2D::new(1f32, 1f32), Vector2D::new(0f32, 3f32));
+
+        assert!(matrix.inverse().is_none());
+    }
+
+    #[test]
+    fn test_singular_relative_to_scale() {
+        let small: Matrix2D = Matrix2D::skew(Vector2D::new(0.5f32, 0f32), Vector2D::new(0.0001f32, 0.0001f32));
+        let point: Point2d = Point2d::new(3f32, -4f32);
+        let round_trip: Point2d = (point * small) * small.inverse().unwrap();
+        assert!(round_trip.approx_equals(&point, 0.0001), "Round trip was {}", round_trip);
+
+        //rows a rounding step apart, the determinant is above f32::EPSILON
+        let large: Matrix2D = Matrix2D {
+            r1: Row {
+                c1: 10000f32,
+                c2: 10000f32,
+                c3: 0f32,
+            },
+            r2: Row {
+                c1: 10000f32,
+                c2: 10000.001f32,
+                c3: 0f32,
+            },
+            r3: Row {
+                c1: 0f32,
+                c2: 0f32,
+                c3: 1f32,
+            },
+        };
+        assert!(large.inverse().is_none());
+    }
 }
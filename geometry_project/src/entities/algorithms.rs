@@ -1,8 +1,10 @@
 use core::f32;
 
+use crate::algorithms::tracer::{NoTracer, Tracer};
+use crate::display::{rgb::RGB, scene::Scene};
 use crate::numerics::floating_comparisons::{approx_equal, approx_greater, approx_less};
 
-use super::{line2d::Line2D, rectangle2d::Rectangle2D};
+use super::{line2d::Line2D, point2d::Point2d, polygon2d::Polygon2D, rectangle2d::Rectangle2D};
 
 fn max_array(arr: Vec<f32>, n: usize) -> f32 {
     let mut max: f32 = f32::MIN;
@@ -24,7 +26,38 @@ fn min_array(arr: Vec<f32>, n: usize) -> f32 {
     min
 }
 
+//Clip window, the input line and the part of it kept so far
+fn clip_scene(rect: Rectangle2D, line: Line2D, kept: Option<Line2D>) -> Scene {
+    let mut scene: Scene = Scene::new();
+
+    let window: Polygon2D = Polygon2D::new(vec![
+        rect.min,
+        Point2d::new(rect.max.x, rect.min.y),
+        rect.max,
+        Point2d::new(rect.min.x, rect.max.y),
+    ]);
+
+    scene.layer("window").push_polygon(&window, RGB::blue());
+    scene.layer("line").push_line_colored(line, RGB::red());
+
+    scene.push_note(format!("line {} - {}", line.start, line.end));
+
+    if let Some(kept) = kept {
+        scene.layer("clipped").push_line_colored(kept, RGB::green());
+        scene.push_note(format!("kept {} - {}", kept.start, kept.end));
+    }
+
+    scene
+}
+
 pub fn liang_barsky_clip(rect: Rectangle2D, line: Line2D) -> Option<Line2D> {
+    liang_barsky_clip_traced(rect, line, &mut NoTracer)
+}
+
+//Steps are "parallel" or "outside" when the line is rejected. Otherwise "enter"
+//with the line cut where it enters the window, "leave" with it also cut where
+//it leaves and "clip" for the result
+pub fn liang_barsky_clip_traced<T: Tracer>(rect: Rectangle2D, line: Line2D, tracer: &mut T) -> Option<Line2D> {
     let p1: f32 = -(line.end.x - line.start.x);
     let p2: f32 = -p1;
     let p3: f32 = -(line.end.y - line.start.y);
@@ -49,7 +82,7 @@ pub fn liang_barsky_clip(rect: Rectangle2D, line: Line2D) -> Option<Line2D> {
         || (approx_equal(p3, 0f32, f32::EPSILON) && approx_less(q3, 0f32, f32::EPSILON))
         || (approx_equal(p4, 0f32, f32::EPSILON) & approx_less(q4, 0f32, f32::EPSILON))
     {
-        println!("Parallel to clipping window");
+        tracer.trace("parallel", || clip_scene(rect, line, None));
         return None; //parallel to clipping window
     }
 
@@ -88,22 +121,34 @@ pub fn liang_barsky_clip(rect: Rectangle2D, line: Line2D) -> Option<Line2D> {
     let rn1: f32 = max_array(negarr, negind as usize);
     let rn2: f32 = min_array(posarr, posind as usize);
 
+    let at = |t: f32| -> Point2d {
+        Point2d::new(line.start.x + p2 * t, line.start.y + p4 * t)
+    };
+
     if approx_greater(rn1, rn2, f32::EPSILON) {
+        tracer.trace("outside", || clip_scene(rect, line, None));
         return None; //outisde clipping window
     }
 
+    tracer.trace("enter", || clip_scene(rect, line, Some(Line2D::new(at(rn1), line.end))));
+    tracer.trace("leave", || clip_scene(rect, line, Some(Line2D::new(at(rn1), at(rn2)))));
+
     let xn1: f32 = line.start.x + p2 * rn1;
     let yn1: f32 = line.start.y + p4 * rn1;
 
     let xn2: f32 = line.start.x + p2 * rn2;
     let yn2: f32 = line.start.y + p4 * rn2;
 
-    Some(Line2D::new_flat(xn1, yn1, xn2, yn2))
+    let clipped: Line2D = Line2D::new_flat(xn1, yn1, xn2, yn2);
+    tracer.trace("clip", || clip_scene(rect, line, Some(clipped)));
+
+    Some(clipped)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::algorithms::tracer::SceneRecorder;
 
     #[test]
     fn test_outside() {
@@ -126,4 +171,29 @@ mod tests {
         println!("Line after clipping: {}", clipped.unwrap());
         assert!(clipped.unwrap().approx_equals(&line, f32::EPSILON));
     }
+
+    #[test]
+    fn test_traced_clip() {
+        let rect: Rectangle2D = Rectangle2D::new_width_height(10f32, 10f32);
+        let line: Line2D = Line2D::new_flat(-5f32, 5f32, 15f32, 5f32);
+
+        let mut recorder: SceneRecorder = SceneRecorder::new();
+        let clipped = liang_barsky_clip_traced(rect, line, &mut recorder);
+
+        assert!(clipped.is_some());
+        assert_eq!(vec!["enter", "leave", "clip"], recorder.labels());
+    }
+
+    #[test]
+    fn test_traced_miss() {
+        //crosses the lines of both boundaries but never the window
+        let rect: Rectangle2D = Rectangle2D::new_width_height(10f32, 10f32);
+        let line: Line2D = Line2D::new_flat(12f32, -5f32, 25f32, 8f32);
+
+        let mut recorder: SceneRecorder = SceneRecorder::new();
+        let clipped = liang_barsky_clip_traced(rect, line, &mut recorder);
+
+        assert!(clipped.is_none());
+        assert_eq!(vec!["outside"], recorder.labels());
+    }
 }
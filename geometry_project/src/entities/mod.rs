@@ -3,6 +3,7 @@ pub mod algorithms;
 pub mod line2d;
 pub mod point2d;
 pub mod polygon2d;
+pub mod polygon_with_holes;
 pub mod rectangle2d;
 pub mod vect2d;
 pub mod lexicographic2d;
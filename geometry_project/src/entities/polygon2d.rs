@@ -1,15 +1,42 @@
-use super::{line2d::Line2D, point2d::Point2d};
+use std::ops::Mul;
 
+use serde::{Deserialize, Serialize};
+
+use super::{affine_matrix2d::Matrix2D, line2d::Line2D, point2d::Point2d};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Polygon2D {
     pub points: Vec<Point2d>,
 }
 
+impl Mul<Matrix2D> for &Polygon2D {
+    type Output = Polygon2D;
+
+    fn mul(self, rhs: Matrix2D) -> Self::Output {
+        Polygon2D {
+            points: self.points.iter().map(|p| *p * rhs).collect(),
+        }
+    }
+}
+
 impl Polygon2D {
+    pub fn new(points: Vec<Point2d>) -> Self {
+        Polygon2D { points }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.len() < 3
+    }
+
     pub fn lines(&self) -> Vec<Line2D> {
         let mut lines: Vec<Line2D> = Vec::new();
 
         let max_pts: usize = self.points.len();
 
+        if max_pts == 0 {
+            return lines;
+        }
+
         for i in 1..max_pts {
             lines.push(Line2D {
                 start: self.points[i - 1],
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use super::{line2d::Line2D, polygon2d::Polygon2D};
+
+//An outer ring and the rings cut out of it, all implicitly closed like Polygon2D
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolygonWithHoles {
+    pub exterior: Polygon2D,
+    pub holes: Vec<Polygon2D>,
+}
+
+impl From<Polygon2D> for PolygonWithHoles {
+    fn from(exterior: Polygon2D) -> Self {
+        PolygonWithHoles {
+            exterior,
+            holes: Vec::new(),
+        }
+    }
+}
+
+impl PolygonWithHoles {
+    pub fn new(exterior: Polygon2D, holes: Vec<Polygon2D>) -> Self {
+        PolygonWithHoles { exterior, holes }
+    }
+
+    //Edges of every ring, the exterior first
+    pub fn lines(&self) -> Vec<Line2D> {
+        let mut lines: Vec<Line2D> = self.exterior.lines();
+
+        for hole in &self.holes {
+            lines.extend(hole.lines());
+        }
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::point2d::Point2d;
+
+    fn square(min: f32, max: f32) -> Polygon2D {
+        Polygon2D::new(vec![
+            Point2d::new(min, min),
+            Point2d::new(max, min),
+            Point2d::new(max, max),
+            Point2d::new(min, max),
+        ])
+    }
+
+    #[test]
+    fn test_lines_include_holes() {
+        let polygon: PolygonWithHoles = PolygonWithHoles::new(square(0f32, 10f32), vec![square(4f32, 6f32)]);
+
+        assert_eq!(8, polygon.lines().len());
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     entities::point2d::Point2d,
     numerics::floating_comparisons::{
@@ -7,7 +9,7 @@ use crate::{
     },
 };
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Rectangle2D {
     pub min: Point2d,
     pub max: Point2d,
@@ -20,6 +22,41 @@ impl fmt::Display for Rectangle2D {
 }
 
 impl Rectangle2D {
+    pub fn new(min: Point2d, max: Point2d) -> Self {
+        Rectangle2D { min, max }
+    }
+
+    //Smallest rectangle holding every finite point, None when there are none
+    pub fn from_points<T>(points: T) -> Option<Self>
+    where
+        T: IntoIterator<Item = Point2d>,
+    {
+        let mut bounds: Option<Rectangle2D> = None;
+
+        for point in points.into_iter().filter(|p| p.is_finite()) {
+            bounds = Some(match bounds {
+                None => Rectangle2D::new(point, point),
+                Some(b) => Rectangle2D::new(
+                    Point2d::new(b.min.x.min(point.x), b.min.y.min(point.y)),
+                    Point2d::new(b.max.x.max(point.x), b.max.y.max(point.y)),
+                ),
+            });
+        }
+
+        bounds
+    }
+
+    pub fn center(&self) -> Point2d {
+        Point2d::new((self.min.x + self.max.x) / 2f32, (self.min.y + self.max.y) / 2f32)
+    }
+
+    pub fn expand(&self, margin: f32) -> Self {
+        Rectangle2D::new(
+            Point2d::new(self.min.x - margin, self.min.y - margin),
+            Point2d::new(self.max.x + margin, self.max.y + margin),
+        )
+    }
+
     pub fn new_width_height(width: f32, height: f32) -> Self {
         Rectangle2D {
             min: Point2d::origin(),
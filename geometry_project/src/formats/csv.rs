@@ -0,0 +1,125 @@
+use crate::{
+    entities::{point2d::Point2d, polygon2d::Polygon2D},
+    formats::{format_error::FormatError, geometry::Geometry},
+};
+
+//A row is x,y pairs: one pair is a point, two a segment and more a polygon.
+//Blank lines and lines starting with # are skipped, so is a first row that is
+//not numbers, the header spreadsheets write
+pub fn parse_csv(text: &str) -> Result<Vec<Geometry>, FormatError> {
+    let mut geometries: Vec<Geometry> = Vec::new();
+
+    for (index, row) in text.lines().enumerate() {
+        let line: usize = index + 1;
+        let trimmed: &str = row.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let mut values: Vec<f32> = Vec::new();
+        let mut column: usize = 1;
+
+        for field in row.split(',') {
+            match field.trim().parse::<f32>() {
+                Ok(value) => values.push(value),
+                Err(_) if values.is_empty() && line == first_row(text) => break,
+                Err(_) => {
+                    let offset: usize = field.chars().count() - field.trim_start().chars().count();
+                    return Err(FormatError::new(line, column + offset, format!("'{}' is not a number", field.trim())));
+                }
+            }
+
+            column += field.chars().count() + 1;
+        }
+
+        //the header
+        if values.is_empty() {
+            continue;
+        }
+
+        if !values.len().is_multiple_of(2) {
+            return Err(FormatError::new(line, 1, format!("expected x,y pairs, found {} values", values.len())));
+        }
+
+        let points: Vec<Point2d> = values.chunks(2).map(|c| Point2d::new(c[0], c[1])).collect();
+
+        geometries.push(match points.len() {
+            1 => Geometry::Point(points[0]),
+            2 => Geometry::LineString(points),
+            _ => Geometry::from(Polygon2D::new(points)),
+        });
+    }
+
+    Ok(geometries)
+}
+
+//The line of the first row that is not blank or a comment
+fn first_row(text: &str) -> usize {
+    text.lines()
+        .position(|l| !l.trim().is_empty() && !l.trim().starts_with('#'))
+        .map(|i| i + 1)
+        .unwrap_or(0)
+}
+
+fn row(points: &[Point2d]) -> String {
+    let values: Vec<String> = points.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+    values.join(",") + "\n"
+}
+
+//What parse_csv reads back. Line strings are written as their segments and
+//polygons lose their holes, there is no row for either
+pub fn write_csv(geometries: &[Geometry]) -> String {
+    let mut out: String = String::new();
+
+    for geometry in geometries {
+        out.extend(geometry.points().iter().map(|p| row(&[*p])));
+        out.extend(geometry.lines().iter().map(|l| row(&[l.start, l.end])));
+        out.extend(geometry.polygons().iter().map(|p| row(&p.exterior.points)));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::line2d::Line2D;
+
+    #[test]
+    fn test_rows() {
+        let geometries: Vec<Geometry> = parse_csv("x,y\n1,2\n\n# a segment\n0, 0, 3, 4\n0,0,1,0,0,1\n").unwrap();
+
+        assert_eq!(3, geometries.len());
+        assert_eq!(vec![Point2d::new(1f32, 2f32)], geometries[0].points());
+        assert_eq!(vec![Line2D::new(Point2d::new(0f32, 0f32), Point2d::new(3f32, 4f32))], geometries[1].lines());
+        assert_eq!(3, geometries[2].polygons()[0].exterior.points.len());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let geometries: Vec<Geometry> = vec![
+            Geometry::Point(Point2d::new(1.5f32, 2f32)),
+            Geometry::LineString(vec![Point2d::new(0f32, 0f32), Point2d::new(1f32, 1f32)]),
+            Geometry::from(Polygon2D::new(vec![Point2d::new(0f32, 0f32), Point2d::new(4f32, 0f32), Point2d::new(0f32, 4f32)])),
+        ];
+
+        assert_eq!(geometries, parse_csv(&write_csv(&geometries)).unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        let bad: FormatError = parse_csv("1,2\n3, four\n").unwrap_err();
+        assert_eq!((2, 4), (bad.line, bad.column));
+
+        //columns count characters, an ideographic space is one
+        let wide: FormatError = parse_csv("1,2\n3,\u{3000}x\n").unwrap_err();
+        assert_eq!((2, 4), (wide.line, wide.column));
+
+        let odd: FormatError = parse_csv("1,2,3\n").unwrap_err();
+        assert_eq!(1, odd.line);
+
+        //only the first row can be a header
+        assert!(parse_csv("1,2\nx,y\n").is_err());
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+use crate::entities::{line2d::Line2D, point2d::Point2d, polygon2d::Polygon2D};
+
+//Something an importer found but could not use, with the line it starts on
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unsupported {
+    pub line: usize,
+    pub what: String,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.what)
+    }
+}
+
+//Segments and closed outlines read from a drawing. Open polylines are split
+//into their segments, closed ones become polygons
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Drawing {
+    pub lines: Vec<Line2D>,
+    pub polygons: Vec<Polygon2D>,
+    pub unsupported: Vec<Unsupported>,
+}
+
+impl Drawing {
+    pub fn new() -> Self {
+        Drawing::default()
+    }
+
+    pub fn push_polyline(&mut self, vertices: &[Point2d], closed: bool) {
+        match closed && vertices.len() >= 3 {
+            true => self.polygons.push(Polygon2D::new(vertices.to_vec())),
+            false => self
+                .lines
+                .extend(vertices.windows(2).map(|w| Line2D::new(w[0], w[1]))),
+        }
+    }
+
+    pub fn report(&mut self, line: usize, what: impl Into<String>) {
+        self.unsupported.push(Unsupported {
+            line,
+            what: what.into(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_polyline() {
+        let vertices: Vec<Point2d> = vec![Point2d::new(0f32, 0f32), Point2d::new(1f32, 0f32), Point2d::new(1f32, 1f32)];
+        let mut drawing: Drawing = Drawing::new();
+
+        drawing.push_polyline(&vertices, false);
+        drawing.push_polyline(&vertices, true);
+        //two points can not close into a polygon
+        drawing.push_polyline(&vertices[..2], true);
+
+        assert_eq!(3, drawing.lines.len());
+        assert_eq!(1, drawing.polygons.len());
+    }
+}
@@ -0,0 +1,307 @@
+use crate::{
+    entities::{line2d::Line2D, point2d::Point2d},
+    formats::{drawing::Drawing, format_error::FormatError},
+};
+
+//POLYLINE flags, 3D meshes and polyface meshes are not outlines
+const CLOSED_FLAG: i32 = 1;
+const MESH_FLAGS: i32 = 16 | 64;
+
+//One group code and its value, line is where the code is
+#[derive(Debug, Clone)]
+struct Pair {
+    code: i32,
+    value: String,
+    line: usize,
+}
+
+//An entity is everything from one 0 group to the next
+#[derive(Debug, Clone)]
+struct Entity {
+    name: String,
+    line: usize,
+    pairs: Vec<Pair>,
+}
+
+impl Entity {
+    fn values(&self, code: i32) -> impl Iterator<Item = &Pair> + '_ {
+        self.pairs.iter().filter(move |p| p.code == code)
+    }
+
+    fn number(&self, code: i32) -> Result<Option<f32>, FormatError> {
+        self.values(code).next().map(parse_number).transpose()
+    }
+
+    fn flags(&self) -> Result<i32, FormatError> {
+        Ok(self.number(70)?.unwrap_or(0f32) as i32)
+    }
+
+    fn point(&self, x_code: i32, y_code: i32) -> Result<Point2d, FormatError> {
+        match (self.number(x_code)?, self.number(y_code)?) {
+            (Some(x), Some(y)) => Ok(Point2d::new(x, y)),
+            _ => Err(FormatError::new(
+                self.line,
+                1,
+                format!("{} is missing group {} or {}", self.name, x_code, y_code),
+            )),
+        }
+    }
+
+    fn has_bulge(&self) -> Result<bool, FormatError> {
+        for pair in self.values(42) {
+            if parse_number(pair)? != 0f32 {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+fn parse_number(pair: &Pair) -> Result<f32, FormatError> {
+    pair.value
+        .parse()
+        .map_err(|_| FormatError::new(pair.line + 1, 1, format!("invalid number '{}'", pair.value)))
+}
+
+fn pairs(text: &str) -> Result<Vec<Pair>, FormatError> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut pairs: Vec<Pair> = Vec::with_capacity(lines.len() / 2);
+
+    for (i, chunk) in lines.chunks(2).enumerate() {
+        let line: usize = 2 * i + 1;
+
+        let code: i32 = chunk[0]
+            .trim()
+            .parse()
+            .map_err(|_| FormatError::new(line, 1, format!("expected a group code, found '{}'", chunk[0].trim())))?;
+
+        let value: &str = match chunk.get(1) {
+            Some(v) => v.trim(),
+            None => {
+                return Err(FormatError::new(line, 1, "group code without a value"));
+            }
+        };
+
+        pairs.push(Pair { code, value: value.to_string(), line });
+    }
+
+    Ok(pairs)
+}
+
+//The entities between "2 ENTITIES" and the end of that section
+fn entities(pairs: &[Pair]) -> Result<Vec<Entity>, FormatError> {
+    let start: usize = pairs
+        .windows(2)
+        .position(|w| w[0].code == 0 && w[0].value == "SECTION" && w[1].code == 2 && w[1].value == "ENTITIES")
+        .ok_or_else(|| FormatError::new(1, 1, "the drawing has no ENTITIES section"))?;
+
+    let mut entities: Vec<Entity> = Vec::new();
+
+    for pair in &pairs[start + 2..] {
+        if pair.code == 0 {
+            if pair.value == "ENDSEC" {
+                return Ok(entities);
+            }
+
+            entities.push(Entity { name: pair.value.clone(), line: pair.line, pairs: Vec::new() });
+            continue;
+        }
+
+        match entities.last_mut() {
+            Some(entity) => entity.pairs.push(pair.clone()),
+            None => {
+                return Err(FormatError::new(pair.line, 1, "expected an entity"));
+            }
+        }
+    }
+
+    Err(FormatError::new(pairs.last().map(|p| p.line).unwrap_or(1), 1, "the ENTITIES section has no ENDSEC"))
+}
+
+//A polyline needs two vertices for a segment, fewer is reported and skipped
+fn too_short(drawing: &mut Drawing, entity: &Entity, vertices: &[Point2d]) -> bool {
+    if vertices.len() >= 2 {
+        return false;
+    }
+
+    drawing.report(entity.line, format!("{} with {} vertices is skipped", entity.name, vertices.len()));
+    true
+}
+
+//LWPOLYLINE vertices are repeated 10 / 20 groups
+fn lightweight_vertices(entity: &Entity) -> Result<Vec<Point2d>, FormatError> {
+    let mut vertices: Vec<Point2d> = Vec::new();
+    let mut x: Option<f32> = None;
+
+    for pair in &entity.pairs {
+        match (pair.code, x) {
+            (10, _) => x = Some(parse_number(pair)?),
+            (20, Some(vx)) => {
+                vertices.push(Point2d::new(vx, parse_number(pair)?));
+                x = None;
+            }
+            (20, None) => {
+                return Err(FormatError::new(pair.line, 1, "vertex y without an x"));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(vertices)
+}
+
+//Reads LINE, LWPOLYLINE and POLYLINE from an ASCII DXF. Anything else in the
+//ENTITIES section is reported. Arcs in polylines (bulges) are replaced by
+//their chords and reported too
+pub fn parse_dxf(text: &str) -> Result<Drawing, FormatError> {
+    let entities: Vec<Entity> = entities(&pairs(text)?)?;
+    let mut drawing: Drawing = Drawing::new();
+    let mut rest = entities.iter();
+
+    while let Some(entity) = rest.next() {
+        match entity.name.as_str() {
+            "LINE" => drawing.lines.push(Line2D::new(entity.point(10, 20)?, entity.point(11, 21)?)),
+            "LWPOLYLINE" => {
+                let vertices: Vec<Point2d> = lightweight_vertices(entity)?;
+
+                if too_short(&mut drawing, entity, &vertices) {
+                    continue;
+                }
+
+                if entity.has_bulge()? {
+                    drawing.report(entity.line, "LWPOLYLINE arcs are replaced by straight segments");
+                }
+
+                drawing.push_polyline(&vertices, entity.flags()? & CLOSED_FLAG != 0);
+            }
+            "POLYLINE" => {
+                let flags: i32 = entity.flags()?;
+                let mut vertices: Vec<Point2d> = Vec::new();
+                let mut bulge: bool = false;
+
+                for vertex in rest.by_ref() {
+                    match vertex.name.as_str() {
+                        "VERTEX" => {
+                            vertices.push(vertex.point(10, 20)?);
+                            bulge = bulge || vertex.has_bulge()?;
+                        }
+                        "SEQEND" => break,
+                        other => {
+                            return Err(FormatError::new(vertex.line, 1, format!("expected VERTEX or SEQEND, found {}", other)));
+                        }
+                    }
+                }
+
+                if flags & MESH_FLAGS != 0 {
+                    drawing.report(entity.line, "POLYLINE meshes are not supported");
+                    continue;
+                }
+
+                if too_short(&mut drawing, entity, &vertices) {
+                    continue;
+                }
+
+                if bulge {
+                    drawing.report(entity.line, "POLYLINE arcs are replaced by straight segments");
+                }
+
+                drawing.push_polyline(&vertices, flags & CLOSED_FLAG != 0);
+            }
+            other => drawing.report(entity.line, format!("{} entities are not supported", other)),
+        }
+    }
+
+    Ok(drawing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dxf(entities: &[&str]) -> String {
+        let mut lines: Vec<&str> = vec!["0", "SECTION", "2", "HEADER", "0", "ENDSEC", "0", "SECTION", "2", "ENTITIES"];
+        lines.extend(entities);
+        lines.extend(["0", "ENDSEC", "0", "EOF"]);
+
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_line() {
+        let drawing: Drawing = parse_dxf(&dxf(&["0", "LINE", "8", "0", "10", "1.5", "20", "2", "30", "0", "11", "4", "21", "6", "31", "0"])).unwrap();
+
+        assert_eq!(vec![Line2D::new(Point2d::new(1.5f32, 2f32), Point2d::new(4f32, 6f32))], drawing.lines);
+        assert!(drawing.unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_lightweight_polylines() {
+        let drawing: Drawing = parse_dxf(&dxf(&[
+            "0", "LWPOLYLINE", "90", "3", "70", "1", "10", "0", "20", "0", "10", "4", "20", "0", "10", "0", "20", "3",
+            "0", "LWPOLYLINE", "90", "3", "70", "0", "10", "0", "20", "0", "10", "4", "20", "0", "42", "0.5", "10", "0", "20", "3",
+        ]))
+        .unwrap();
+
+        assert_eq!(1, drawing.polygons.len());
+        assert_eq!(3, drawing.polygons[0].points.len());
+        assert_eq!(2, drawing.lines.len());
+        assert_eq!(1, drawing.unsupported.len());
+    }
+
+    #[test]
+    fn test_polyline_with_vertices() {
+        let drawing: Drawing = parse_dxf(&dxf(&[
+            "0", "POLYLINE", "66", "1", "70", "1",
+            "0", "VERTEX", "10", "0", "20", "0",
+            "0", "VERTEX", "10", "5", "20", "0",
+            "0", "VERTEX", "10", "5", "20", "5",
+            "0", "VERTEX", "10", "0", "20", "5",
+            "0", "SEQEND",
+            "0", "LINE", "10", "0", "20", "0", "11", "1", "21", "1",
+        ]))
+        .unwrap();
+
+        assert_eq!(4, drawing.polygons[0].points.len());
+        assert_eq!(1, drawing.lines.len());
+    }
+
+    #[test]
+    fn test_degenerate_polylines_are_reported() {
+        let drawing: Drawing = parse_dxf(&dxf(&[
+            "0", "LWPOLYLINE", "90", "1", "70", "0", "10", "0", "20", "0",
+            "0", "POLYLINE", "66", "1", "70", "0",
+            "0", "SEQEND",
+        ]))
+        .unwrap();
+
+        assert!(drawing.lines.is_empty());
+        assert_eq!(2, drawing.unsupported.len());
+        assert_eq!(11, drawing.unsupported[0].line);
+        assert_eq!("LWPOLYLINE with 1 vertices is skipped", drawing.unsupported[0].what);
+        assert_eq!("POLYLINE with 0 vertices is skipped", drawing.unsupported[1].what);
+    }
+
+    #[test]
+    fn test_unsupported_are_reported() {
+        let drawing: Drawing = parse_dxf(&dxf(&["0", "CIRCLE", "10", "0", "20", "0", "40", "1", "0", "TEXT", "1", "hello"])).unwrap();
+
+        assert!(drawing.lines.is_empty());
+        assert_eq!(2, drawing.unsupported.len());
+        assert_eq!(11, drawing.unsupported[0].line);
+        assert!(drawing.unsupported[1].what.contains("TEXT"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let bad_number: FormatError = parse_dxf(&dxf(&["0", "LINE", "10", "x", "20", "0", "11", "1", "21", "1"])).unwrap_err();
+        assert_eq!(14, bad_number.line);
+
+        let missing: FormatError = parse_dxf(&dxf(&["0", "LINE", "10", "0", "20", "0"])).unwrap_err();
+        assert_eq!(11, missing.line);
+
+        assert!(parse_dxf("0\nSECTION\n2\nENTITIES\n0\nLINE").is_err());
+        assert!(parse_dxf("0\nSECTION\n2\nHEADER\n0\nENDSEC").is_err());
+        assert_eq!(3, parse_dxf("0\nSECTION\nnot a code\nENTITIES").unwrap_err().line);
+    }
+}
@@ -0,0 +1,37 @@
+use std::fmt;
+
+//Where reading an exchange format went wrong, lines and columns start at 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl FormatError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        FormatError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<serde_json::Error> for FormatError {
+    fn from(error: serde_json::Error) -> Self {
+        //serde_json appends " at line x column y" to its own messages
+        let message: String = error.to_string();
+        let message: &str = message.split(" at line ").next().unwrap_or(&message);
+
+        FormatError::new(error.line(), error.column(), message)
+    }
+}
@@ -0,0 +1,384 @@
+use std::fmt;
+
+use serde::{
+    de::{self, DeserializeOwned, IgnoredAny, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::Value;
+
+use crate::{
+    entities::{point2d::Point2d, polygon2d::Polygon2D, polygon_with_holes::PolygonWithHoles},
+    formats::{format_error::FormatError, geometry::Geometry},
+};
+
+//[x, y] with an optional altitude that is dropped
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "Vec<f32>", into = "[f32; 2]")]
+struct Position(Point2d);
+
+impl TryFrom<Vec<f32>> for Position {
+    type Error = String;
+
+    fn try_from(value: Vec<f32>) -> Result<Self, Self::Error> {
+        match value.as_slice() {
+            [x, y, ..] => Ok(Position(Point2d::new(*x, *y))),
+            _ => Err(format!("a position needs at least 2 numbers, got {}", value.len())),
+        }
+    }
+}
+
+impl From<Position> for [f32; 2] {
+    fn from(position: Position) -> Self {
+        [position.0.x, position.0.y]
+    }
+}
+
+//Written closed, Polygon2D closes itself so the last position is dropped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Position>", into = "Vec<Position>")]
+struct Ring(Polygon2D);
+
+impl TryFrom<Vec<Position>> for Ring {
+    type Error = String;
+
+    fn try_from(value: Vec<Position>) -> Result<Self, Self::Error> {
+        let mut points: Vec<Point2d> = value.into_iter().map(|p| p.0).collect();
+
+        if points.len() < 4 || points.first() != points.last() {
+            return Err("a ring needs at least 4 positions and has to end where it starts".to_string());
+        }
+
+        points.pop();
+        Ok(Ring(Polygon2D::new(points)))
+    }
+}
+
+impl From<Ring> for Vec<Position> {
+    fn from(ring: Ring) -> Self {
+        let mut positions: Vec<Position> = ring.0.points.iter().map(|p| Position(*p)).collect();
+        positions.extend(ring.0.points.first().map(|p| Position(*p)));
+
+        positions
+    }
+}
+
+//Read by GeoJsonVisitor, serde's own tagged enums buffer the object and the
+//errors lose their line and column
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+enum GeoJson {
+    Point {
+        coordinates: Position,
+    },
+    MultiPoint {
+        coordinates: Vec<Position>,
+    },
+    LineString {
+        coordinates: Vec<Position>,
+    },
+    MultiLineString {
+        coordinates: Vec<Vec<Position>>,
+    },
+    Polygon {
+        coordinates: Vec<Ring>,
+    },
+    MultiPolygon {
+        coordinates: Vec<Vec<Ring>>,
+    },
+    GeometryCollection {
+        geometries: Vec<GeoJson>,
+    },
+    //Properties are kept as they are, they mean nothing to the scenarios
+    Feature {
+        geometry: Option<Box<GeoJson>>,
+        #[serde(default)]
+        properties: Value,
+    },
+    FeatureCollection {
+        features: Vec<GeoJson>,
+    },
+}
+
+const TYPES: [&str; 9] = [
+    "Point",
+    "MultiPoint",
+    "LineString",
+    "MultiLineString",
+    "Polygon",
+    "MultiPolygon",
+    "GeometryCollection",
+    "Feature",
+    "FeatureCollection",
+];
+
+//Reads the member holding the geometry and a feature's properties straight
+//from the input, so their errors are reported where they are. Members before
+//the type were kept as values, other members are skipped
+fn members<'de, A, T>(mut map: A, early: Vec<(String, Value)>, name: &'static str) -> Result<(Option<T>, Value), A::Error>
+where
+    A: MapAccess<'de>,
+    T: DeserializeOwned,
+{
+    let mut found: Option<T> = None;
+    let mut properties: Value = Value::Null;
+
+    for (key, value) in early {
+        if key == name {
+            found = Some(T::deserialize(value).map_err(de::Error::custom)?);
+        } else if key == "properties" {
+            properties = value;
+        }
+    }
+
+    while let Some(key) = map.next_key::<String>()? {
+        if key == name {
+            found = Some(map.next_value()?);
+        } else if key == "properties" {
+            properties = map.next_value()?;
+        } else {
+            map.next_value::<IgnoredAny>()?;
+        }
+    }
+
+    Ok((found, properties))
+}
+
+fn required<'de, A, T>(map: A, early: Vec<(String, Value)>, name: &'static str) -> Result<T, A::Error>
+where
+    A: MapAccess<'de>,
+    T: DeserializeOwned,
+{
+    members(map, early, name)?.0.ok_or_else(|| de::Error::missing_field(name))
+}
+
+struct GeoJsonVisitor;
+
+impl<'de> Visitor<'de> for GeoJsonVisitor {
+    type Value = GeoJson;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a GeoJSON object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<GeoJson, A::Error> {
+        let mut early: Vec<(String, Value)> = Vec::new();
+
+        let kind: String = loop {
+            match map.next_key::<String>()? {
+                Some(key) if key == "type" => break map.next_value()?,
+                Some(key) => early.push((key, map.next_value()?)),
+                None => return Err(de::Error::missing_field("type")),
+            }
+        };
+
+        Ok(match kind.as_str() {
+            "Point" => GeoJson::Point { coordinates: required(map, early, "coordinates")? },
+            "MultiPoint" => GeoJson::MultiPoint { coordinates: required(map, early, "coordinates")? },
+            "LineString" => GeoJson::LineString { coordinates: required(map, early, "coordinates")? },
+            "MultiLineString" => GeoJson::MultiLineString { coordinates: required(map, early, "coordinates")? },
+            "Polygon" => GeoJson::Polygon { coordinates: required(map, early, "coordinates")? },
+            "MultiPolygon" => GeoJson::MultiPolygon { coordinates: required(map, early, "coordinates")? },
+            "GeometryCollection" => GeoJson::GeometryCollection { geometries: required(map, early, "geometries")? },
+            "Feature" => {
+                let (geometry, properties) = members::<A, Option<Box<GeoJson>>>(map, early, "geometry")?;
+                GeoJson::Feature { geometry: geometry.flatten(), properties }
+            }
+            "FeatureCollection" => GeoJson::FeatureCollection { features: required(map, early, "features")? },
+            _ => return Err(de::Error::unknown_variant(&kind, &TYPES)),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for GeoJson {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(GeoJsonVisitor)
+    }
+}
+
+fn points(positions: Vec<Position>) -> Vec<Point2d> {
+    positions.into_iter().map(|p| p.0).collect()
+}
+
+fn positions(points: &[Point2d]) -> Vec<Position> {
+    points.iter().map(|p| Position(*p)).collect()
+}
+
+//Polygons without rings are skipped like any other empty geometry
+fn polygon(rings: Vec<Ring>) -> Option<PolygonWithHoles> {
+    let mut rings: Vec<Polygon2D> = rings.into_iter().map(|r| r.0).collect();
+
+    match rings.is_empty() {
+        true => None,
+        false => {
+            let exterior: Polygon2D = rings.remove(0);
+            Some(PolygonWithHoles::new(exterior, rings))
+        }
+    }
+}
+
+fn rings(polygon: &PolygonWithHoles) -> Vec<Ring> {
+    std::iter::once(&polygon.exterior)
+        .chain(polygon.holes.iter())
+        .map(|r| Ring(r.clone()))
+        .collect()
+}
+
+//Features and collections are unwrapped into their geometries
+fn geometries(object: GeoJson, out: &mut Vec<Geometry>) {
+    match object {
+        GeoJson::Point { coordinates } => out.push(Geometry::Point(coordinates.0)),
+        GeoJson::MultiPoint { coordinates } => out.push(Geometry::MultiPoint(points(coordinates))),
+        GeoJson::LineString { coordinates } => out.push(Geometry::LineString(points(coordinates))),
+        GeoJson::MultiLineString { coordinates } => {
+            out.push(Geometry::MultiLineString(coordinates.into_iter().map(points).collect()))
+        }
+        GeoJson::Polygon { coordinates } => out.extend(polygon(coordinates).map(Geometry::Polygon)),
+        GeoJson::MultiPolygon { coordinates } => {
+            out.push(Geometry::MultiPolygon(coordinates.into_iter().filter_map(polygon).collect()))
+        }
+        GeoJson::GeometryCollection { geometries: parts } => {
+            let mut collection: Vec<Geometry> = Vec::new();
+            parts.into_iter().for_each(|p| geometries(p, &mut collection));
+            out.push(Geometry::GeometryCollection(collection));
+        }
+        GeoJson::Feature { geometry, .. } => {
+            if let Some(g) = geometry {
+                geometries(*g, out);
+            }
+        }
+        GeoJson::FeatureCollection { features } => features.into_iter().for_each(|f| geometries(f, out)),
+    }
+}
+
+fn to_object(geometry: &Geometry) -> GeoJson {
+    match geometry {
+        Geometry::Point(p) => GeoJson::Point { coordinates: Position(*p) },
+        Geometry::LineString(vertices) => GeoJson::LineString { coordinates: positions(vertices) },
+        Geometry::Polygon(p) => GeoJson::Polygon { coordinates: rings(p) },
+        Geometry::MultiPoint(vertices) => GeoJson::MultiPoint { coordinates: positions(vertices) },
+        Geometry::MultiLineString(strings) => GeoJson::MultiLineString {
+            coordinates: strings.iter().map(|s| positions(s)).collect(),
+        },
+        Geometry::MultiPolygon(polygons) => GeoJson::MultiPolygon {
+            coordinates: polygons.iter().map(rings).collect(),
+        },
+        Geometry::GeometryCollection(parts) => GeoJson::GeometryCollection {
+            geometries: parts.iter().map(to_object).collect(),
+        },
+    }
+}
+
+//Any GeoJSON object, features and feature collections give their geometries
+pub fn parse_geojson(text: &str) -> Result<Vec<Geometry>, FormatError> {
+    let object: GeoJson = serde_json::from_str(text)?;
+    let mut out: Vec<Geometry> = Vec::new();
+
+    geometries(object, &mut out);
+    Ok(out)
+}
+
+pub fn to_geojson(geometry: &Geometry) -> String {
+    serde_json::to_string(&to_object(geometry)).expect("geometry is always serializable")
+}
+
+//A feature collection with one feature per geometry, what GIS tools expect
+pub fn write_geojson(geometries: &[Geometry]) -> String {
+    let features: Vec<GeoJson> = geometries
+        .iter()
+        .map(|g| GeoJson::Feature {
+            geometry: Some(Box::new(to_object(g))),
+            properties: Value::Object(Default::default()),
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&GeoJson::FeatureCollection { features }).expect("geometry is always serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geometries() {
+        let point: Vec<Geometry> = parse_geojson(r#"{"type": "Point", "coordinates": [1, 2, 30]}"#).unwrap();
+        assert_eq!(vec![Geometry::Point(Point2d::new(1f32, 2f32))], point);
+
+        let line: Vec<Geometry> = parse_geojson(r#"{"type": "LineString", "coordinates": [[0, 0], [3, 4], [5, 5]]}"#).unwrap();
+        assert_eq!(2, line[0].lines().len());
+
+        let polygon: Vec<Geometry> = parse_geojson(
+            r#"{"type": "Polygon", "coordinates": [
+                [[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]],
+                [[4, 4], [6, 4], [6, 6], [4, 4]]
+            ]}"#,
+        )
+        .unwrap();
+        let polygon: &PolygonWithHoles = &polygon[0].polygons()[0];
+        assert_eq!(4, polygon.exterior.points.len());
+        assert_eq!(1, polygon.holes.len());
+    }
+
+    #[test]
+    fn test_feature_collection() {
+        let text: &str = r#"{
+            "type": "FeatureCollection",
+            "features": [
+                {"type": "Feature", "properties": {"name": "a"}, "geometry": {"type": "MultiPoint", "coordinates": [[1, 1], [2, 2]]}},
+                {"type": "Feature", "properties": null, "geometry": null},
+                {"type": "Feature", "geometry": {"type": "GeometryCollection", "geometries": [
+                    {"type": "MultiLineString", "coordinates": [[[0, 0], [1, 1]], [[2, 2], [3, 3]]]}
+                ]}}
+            ]
+        }"#;
+
+        let geometries: Vec<Geometry> = parse_geojson(text).unwrap();
+
+        assert_eq!(2, geometries.len());
+        assert_eq!(2, geometries[0].points().len());
+        assert_eq!(2, geometries[1].lines().len());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let geometries: Vec<Geometry> = vec![
+            Geometry::Point(Point2d::new(1f32, 2f32)),
+            Geometry::LineString(vec![Point2d::new(0f32, 0f32), Point2d::new(1.5f32, 2f32)]),
+            Geometry::MultiPolygon(vec![PolygonWithHoles::new(
+                Polygon2D::new(vec![Point2d::new(0f32, 0f32), Point2d::new(4f32, 0f32), Point2d::new(0f32, 4f32)]),
+                vec![Polygon2D::new(vec![Point2d::new(1f32, 1f32), Point2d::new(2f32, 1f32), Point2d::new(1f32, 2f32)])],
+            )]),
+        ];
+
+        assert_eq!(geometries, parse_geojson(&write_geojson(&geometries)).unwrap());
+
+        let single: String = to_geojson(&geometries[0]);
+        assert_eq!(r#"{"type":"Point","coordinates":[1.0,2.0]}"#, single);
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let syntax: FormatError = parse_geojson("{\"type\": \"Point\",\n \"coordinates\": [1, 2,]}").unwrap_err();
+        assert_eq!(2, syntax.line);
+        assert!(!syntax.message.contains("at line"));
+
+        let short: FormatError = parse_geojson("{\"type\": \"Point\",\n \"coordinates\": [1]\n}").unwrap_err();
+        assert_eq!(3, short.line);
+        assert!(short.message.contains("at least 2 numbers"));
+
+        let unknown: FormatError = parse_geojson(r#"{"type": "Circle", "radius": 1}"#).unwrap_err();
+        assert_eq!(1, unknown.line);
+        assert!(unknown.message.contains("Circle"));
+
+        let open_ring: FormatError =
+            parse_geojson("{\"type\": \"Polygon\",\n \"coordinates\": [[[0, 0], [1, 0], [1, 1], [0, 1]]]}").unwrap_err();
+        assert_eq!(2, open_ring.line);
+        assert!(open_ring.message.contains("ring"));
+
+        //members before the type are read once it is known
+        let late: Vec<Geometry> = parse_geojson(r#"{"coordinates": [1, 2], "type": "Point"}"#).unwrap();
+        assert_eq!(vec![Geometry::Point(Point2d::new(1f32, 2f32))], late);
+
+        let late_short: FormatError = parse_geojson("{\"coordinates\": [1],\n \"type\": \"Point\"}").unwrap_err();
+        assert_eq!(2, late_short.line);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::entities::{line2d::Line2D, point2d::Point2d, polygon2d::Polygon2D, polygon_with_holes::PolygonWithHoles};
+
+//The geometries WKT and GeoJSON have in common. Line strings keep all their
+//vertices, a two point line string is a Line2D
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    Point(Point2d),
+    LineString(Vec<Point2d>),
+    Polygon(PolygonWithHoles),
+    MultiPoint(Vec<Point2d>),
+    MultiLineString(Vec<Vec<Point2d>>),
+    MultiPolygon(Vec<PolygonWithHoles>),
+    GeometryCollection(Vec<Geometry>),
+}
+
+impl From<Point2d> for Geometry {
+    fn from(point: Point2d) -> Self {
+        Geometry::Point(point)
+    }
+}
+
+impl From<Line2D> for Geometry {
+    fn from(line: Line2D) -> Self {
+        Geometry::LineString(vec![line.start, line.end])
+    }
+}
+
+impl From<Polygon2D> for Geometry {
+    fn from(polygon: Polygon2D) -> Self {
+        Geometry::Polygon(polygon.into())
+    }
+}
+
+impl From<PolygonWithHoles> for Geometry {
+    fn from(polygon: PolygonWithHoles) -> Self {
+        Geometry::Polygon(polygon)
+    }
+}
+
+//Consecutive vertices as segments
+fn segments(vertices: &[Point2d]) -> impl Iterator<Item = Line2D> + '_ {
+    vertices.windows(2).map(|w| Line2D::new(w[0], w[1]))
+}
+
+impl Geometry {
+    //Single geometries first, then the parts of multi geometries and collections
+    fn parts(&self) -> Vec<&Geometry> {
+        match self {
+            Geometry::GeometryCollection(geometries) => geometries.iter().flat_map(|g| g.parts()).collect(),
+            other => vec![other],
+        }
+    }
+
+    pub fn points(&self) -> Vec<Point2d> {
+        self.parts()
+            .into_iter()
+            .flat_map(|g| match g {
+                Geometry::Point(p) => vec![*p],
+                Geometry::MultiPoint(points) => points.clone(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    //Line strings split into their segments
+    pub fn lines(&self) -> Vec<Line2D> {
+        self.parts()
+            .into_iter()
+            .flat_map(|g| match g {
+                Geometry::LineString(vertices) => segments(vertices).collect(),
+                Geometry::MultiLineString(strings) => strings.iter().flat_map(|s| segments(s)).collect(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    pub fn polygons(&self) -> Vec<PolygonWithHoles> {
+        self.parts()
+            .into_iter()
+            .flat_map(|g| match g {
+                Geometry::Polygon(polygon) => vec![polygon.clone()],
+                Geometry::MultiPolygon(polygons) => polygons.clone(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collections_are_flattened() {
+        let geometry: Geometry = Geometry::GeometryCollection(vec![
+            Geometry::Point(Point2d::new(1f32, 1f32)),
+            Geometry::MultiPoint(vec![Point2d::new(2f32, 2f32)]),
+            Geometry::GeometryCollection(vec![Geometry::LineString(vec![
+                Point2d::new(0f32, 0f32),
+                Point2d::new(1f32, 0f32),
+                Point2d::new(1f32, 1f32),
+            ])]),
+        ]);
+
+        assert_eq!(2, geometry.points().len());
+        assert_eq!(2, geometry.lines().len());
+        assert!(geometry.polygons().is_empty());
+    }
+}
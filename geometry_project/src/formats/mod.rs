@@ -0,0 +1,9 @@
+pub mod csv;
+pub mod drawing;
+pub mod dxf;
+pub mod format_error;
+pub mod geojson;
+pub mod geometry;
+pub mod scenario_serializer;
+pub mod svg;
+pub mod wkt;
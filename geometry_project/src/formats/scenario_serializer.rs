@@ -0,0 +1,499 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+};
+
+use crate::{
+    display::scenario::ScenarioInput,
+    entities::{line2d::Line2D, point2d::Point2d, polygon2d::Polygon2D, rectangle2d::Rectangle2D},
+    formats::{
+        csv::{parse_csv, write_csv},
+        drawing::{Drawing, Unsupported},
+        dxf::parse_dxf,
+        format_error::FormatError,
+        geojson::{parse_geojson, write_geojson},
+        geometry::Geometry,
+        svg::{parse_svg, DEFAULT_FLATTEN_TOLERANCE},
+        wkt::{parse_wkt, write_wkt},
+    },
+};
+
+//Files without a version are the bare JSON arrays older builds wrote
+pub const SCENARIO_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum ScenarioFileError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    //WKT, GeoJSON, CSV or a drawing that did not parse
+    Format(FormatError),
+    //Written by a newer build
+    UnsupportedVersion(u32),
+    //Neither a scenario object nor an array of entities
+    UnknownLayout,
+    //The file holds no entities of the kind asked for
+    Missing(&'static str),
+}
+
+impl fmt::Display for ScenarioFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioFileError::Io(e) => write!(f, "{}", e),
+            ScenarioFileError::Json(e) => write!(f, "invalid JSON, {}", e),
+            ScenarioFileError::Format(e) => write!(f, "{}", e),
+            ScenarioFileError::UnsupportedVersion(v) => write!(
+                f,
+                "version {} is newer than the supported version {}",
+                v, SCENARIO_FORMAT_VERSION
+            ),
+            ScenarioFileError::UnknownLayout => write!(f, "not a scenario file"),
+            ScenarioFileError::Missing(kind) => write!(f, "the file has no {}", kind),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioFileError {}
+
+impl From<io::Error> for ScenarioFileError {
+    fn from(error: io::Error) -> Self {
+        ScenarioFileError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for ScenarioFileError {
+    fn from(error: serde_json::Error) -> Self {
+        ScenarioFileError::Json(error)
+    }
+}
+
+impl From<FormatError> for ScenarioFileError {
+    fn from(error: FormatError) -> Self {
+        ScenarioFileError::Format(error)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScenarioMetadata {
+    //Registry name of the scenario that wrote the file
+    pub scenario: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bounds: Option<Rectangle2D>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epsilon: Option<f32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioEntities {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub points: Vec<Point2d>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lines: Vec<Line2D>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub polygons: Vec<Polygon2D>,
+}
+
+impl From<ScenarioInput> for ScenarioEntities {
+    fn from(input: ScenarioInput) -> Self {
+        match input {
+            ScenarioInput::Points(points) => ScenarioEntities {
+                points,
+                ..Default::default()
+            },
+            ScenarioInput::Lines(lines) => ScenarioEntities {
+                lines,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl ScenarioEntities {
+    //Line strings become their segments. Scenarios only work on simple
+    //polygons, so holes are dropped and only the outer rings are kept
+    pub fn from_geometries(geometries: &[Geometry]) -> Self {
+        ScenarioEntities {
+            points: geometries.iter().flat_map(|g| g.points()).collect(),
+            lines: geometries.iter().flat_map(|g| g.lines()).collect(),
+            polygons: geometries
+                .iter()
+                .flat_map(|g| g.polygons())
+                .map(|p| p.exterior)
+                .collect(),
+        }
+    }
+
+    pub fn to_geometries(&self) -> Vec<Geometry> {
+        let points = self.points.iter().map(|p| Geometry::from(*p));
+        let lines = self.lines.iter().map(|l| Geometry::from(*l));
+        let polygons = self.polygons.iter().map(|p| Geometry::from(p.clone()));
+
+        points.chain(lines).chain(polygons).collect()
+    }
+}
+
+//Which layout a file is read and written in, the versioned JSON by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioFormat {
+    Json,
+    Wkt,
+    GeoJson,
+    Csv,
+    //Drawings, read only
+    Dxf,
+    Svg,
+}
+
+impl ScenarioFormat {
+    pub fn from_path(path: &str) -> ScenarioFormat {
+        path.rsplit_once('.')
+            .and_then(|(_, e)| ScenarioFormat::from_name(e))
+            .unwrap_or(ScenarioFormat::Json)
+    }
+
+    //An extension without the dot, in any case
+    pub fn from_name(name: &str) -> Option<ScenarioFormat> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(ScenarioFormat::Json),
+            "wkt" => Some(ScenarioFormat::Wkt),
+            "geojson" => Some(ScenarioFormat::GeoJson),
+            "csv" => Some(ScenarioFormat::Csv),
+            "dxf" => Some(ScenarioFormat::Dxf),
+            "svg" => Some(ScenarioFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioFile {
+    pub version: u32,
+    pub metadata: ScenarioMetadata,
+    pub entities: ScenarioEntities,
+    //What an imported drawing held that could not be used, never saved. The
+    //caller decides whether to show it
+    #[serde(skip)]
+    pub warnings: Vec<Unsupported>,
+}
+
+impl ScenarioFile {
+    pub fn new(scenario: &str, entities: ScenarioEntities) -> Self {
+        ScenarioFile {
+            version: SCENARIO_FORMAT_VERSION,
+            metadata: ScenarioMetadata {
+                scenario: scenario.to_string(),
+                ..Default::default()
+            },
+            entities,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.metadata.seed = seed;
+        self
+    }
+
+    pub fn with_bounds(mut self, bounds: Rectangle2D) -> Self {
+        self.metadata.bounds = Some(bounds);
+        self
+    }
+
+    pub fn with_epsilon(mut self, epsilon: f32) -> Self {
+        self.metadata.epsilon = Some(epsilon);
+        self
+    }
+
+    pub fn points(self) -> Result<Vec<Point2d>, ScenarioFileError> {
+        match self.entities.points.is_empty() {
+            true => Err(ScenarioFileError::Missing("points")),
+            false => Ok(self.entities.points),
+        }
+    }
+
+    pub fn lines(self) -> Result<Vec<Line2D>, ScenarioFileError> {
+        match self.entities.lines.is_empty() {
+            true => Err(ScenarioFileError::Missing("lines")),
+            false => Ok(self.entities.lines),
+        }
+    }
+}
+
+//Old files are one or more bare arrays, several when they were saved with
+//append. The arrays are joined and typed by what their elements parse as
+fn migrate(arrays: Vec<Value>) -> Result<ScenarioFile, ScenarioFileError> {
+    let mut elements: Vec<Value> = Vec::new();
+
+    for array in arrays {
+        match array {
+            Value::Array(mut values) => elements.append(&mut values),
+            _ => {
+                return Err(ScenarioFileError::UnknownLayout);
+            }
+        }
+    }
+
+    let mut entities: ScenarioEntities = ScenarioEntities::default();
+    let elements: Value = Value::Array(elements);
+
+    if let Ok(points) = serde_json::from_value::<Vec<Point2d>>(elements.clone()) {
+        entities.points = points;
+    } else if let Ok(lines) = serde_json::from_value::<Vec<Line2D>>(elements.clone()) {
+        entities.lines = lines;
+    } else {
+        entities.polygons = serde_json::from_value(elements)?;
+    }
+
+    let mut file: ScenarioFile = ScenarioFile::new("unknown", entities);
+    file.version = 0;
+
+    Ok(file)
+}
+
+//WKT and GeoJSON carry no metadata, the file gets the current version so a
+//save in the JSON layout keeps what was read
+fn from_geometries(geometries: Vec<Geometry>) -> ScenarioFile {
+    ScenarioFile::new("unknown", ScenarioEntities::from_geometries(&geometries))
+}
+
+//What the drawing held that could not be used becomes the warnings, the
+//scenario runs on the rest
+fn from_drawing(drawing: Drawing) -> ScenarioFile {
+    let mut file: ScenarioFile = ScenarioFile::new(
+        "unknown",
+        ScenarioEntities {
+            lines: drawing.lines,
+            polygons: drawing.polygons,
+            ..Default::default()
+        },
+    );
+    file.warnings = drawing.unsupported;
+
+    file
+}
+
+pub fn parse_scenario(text: &str) -> Result<ScenarioFile, ScenarioFileError> {
+    let values: Vec<Value> = serde_json::Deserializer::from_str(text)
+        .into_iter::<Value>()
+        .collect::<Result<Vec<Value>, serde_json::Error>>()?;
+
+    match values.as_slice() {
+        //GeoJSON objects are told apart by their type member
+        [Value::Object(object)] if !object.contains_key("version") && object.contains_key("type") => {
+            Ok(from_geometries(parse_geojson(text)?))
+        }
+        [Value::Object(object)] => {
+            let version: u64 = object
+                .get("version")
+                .and_then(|v| v.as_u64())
+                .ok_or(ScenarioFileError::UnknownLayout)?;
+
+            if version > SCENARIO_FORMAT_VERSION as u64 {
+                return Err(ScenarioFileError::UnsupportedVersion(version as u32));
+            }
+
+            Ok(serde_json::from_value(values[0].clone())?)
+        }
+        [] => Err(ScenarioFileError::UnknownLayout),
+        _ => migrate(values),
+    }
+}
+
+//Reads the versioned format or the old bare arrays from JSON, or WKT, GeoJSON,
+//CSV, DXF or SVG. What a drawing held that could not be used is in warnings
+pub fn read_scenario(text: &str, format: ScenarioFormat) -> Result<ScenarioFile, ScenarioFileError> {
+    match format {
+        ScenarioFormat::Wkt => Ok(from_geometries(parse_wkt(text)?)),
+        ScenarioFormat::GeoJson => Ok(from_geometries(parse_geojson(text)?)),
+        ScenarioFormat::Csv => Ok(from_geometries(parse_csv(text)?)),
+        ScenarioFormat::Dxf => Ok(from_drawing(parse_dxf(text)?)),
+        ScenarioFormat::Svg => Ok(from_drawing(parse_svg(text, DEFAULT_FLATTEN_TOLERANCE)?)),
+        ScenarioFormat::Json => parse_scenario(text),
+    }
+}
+
+//Only the JSON layout keeps the metadata
+pub fn write_scenario<W: Write>(file: &ScenarioFile, format: ScenarioFormat, mut out: W) -> Result<(), ScenarioFileError> {
+    match format {
+        ScenarioFormat::Wkt => out.write_all(write_wkt(&file.entities.to_geometries()).as_bytes())?,
+        ScenarioFormat::GeoJson => out.write_all(write_geojson(&file.entities.to_geometries()).as_bytes())?,
+        ScenarioFormat::Csv => out.write_all(write_csv(&file.entities.to_geometries()).as_bytes())?,
+        ScenarioFormat::Json => serde_json::to_writer_pretty(&mut out, file)?,
+        ScenarioFormat::Dxf | ScenarioFormat::Svg => {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "DXF and SVG are only imported").into());
+        }
+    }
+
+    out.flush()?;
+
+    Ok(())
+}
+
+//The format follows the extension
+pub fn load_scenario(path: &str) -> Result<ScenarioFile, ScenarioFileError> {
+    read_scenario(&fs::read_to_string(path)?, ScenarioFormat::from_path(path))
+}
+
+//Always replaces the whole file, the format follows the extension
+pub fn save_scenario(file: &ScenarioFile, path: &str) -> Result<(), ScenarioFileError> {
+    let format: ScenarioFormat = ScenarioFormat::from_path(path);
+
+    //checked before the file is truncated
+    if matches!(format, ScenarioFormat::Dxf | ScenarioFormat::Svg) {
+        return write_scenario(file, format, io::sink());
+    }
+
+    write_scenario(file, format, BufWriter::new(File::create(path)?))
+}
+
+pub fn load_points(path: &str) -> Result<Vec<Point2d>, ScenarioFileError> {
+    load_scenario(path)?.points()
+}
+
+pub fn load_lines(path: &str) -> Result<Vec<Line2D>, ScenarioFileError> {
+    load_scenario(path)?.lines()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("{}_{}.json", name, std::process::id()))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn points() -> Vec<Point2d> {
+        vec![Point2d::new(1f32, 2f32), Point2d::new(3f32, 4f32)]
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bounds: Rectangle2D = Rectangle2D::new(Point2d::new(0f32, 0f32), Point2d::new(10f32, 5f32));
+        let file: ScenarioFile = ScenarioFile::new("convex-hull", ScenarioEntities { points: points(), ..Default::default() })
+            .with_seed(Some(42))
+            .with_bounds(bounds)
+            .with_epsilon(0.5f32);
+
+        let parsed: ScenarioFile = parse_scenario(&serde_json::to_string(&file).unwrap()).unwrap();
+
+        assert_eq!(SCENARIO_FORMAT_VERSION, parsed.version);
+        assert_eq!("convex-hull", parsed.metadata.scenario);
+        assert_eq!(Some(42), parsed.metadata.seed);
+        assert_eq!(Some(0.5f32), parsed.metadata.epsilon);
+        assert_eq!(bounds.max, parsed.metadata.bounds.unwrap().max);
+        assert_eq!(points(), parsed.points().unwrap());
+    }
+
+    #[test]
+    fn test_migrate_bare_arrays() {
+        let points_text: String = serde_json::to_string(&points()).unwrap();
+        let migrated: ScenarioFile = parse_scenario(&points_text).unwrap();
+
+        assert_eq!(0, migrated.version);
+        assert_eq!(points(), migrated.points().unwrap());
+
+        let lines: Vec<Line2D> = vec![Line2D::new(Point2d::new(0f32, 0f32), Point2d::new(1f32, 1f32))];
+        let lines_text: String = serde_json::to_string(&lines).unwrap();
+
+        assert_eq!(lines, parse_scenario(&lines_text).unwrap().lines().unwrap());
+    }
+
+    #[test]
+    fn test_migrate_appended_arrays() {
+        let text: String = serde_json::to_string(&points()).unwrap().repeat(2);
+        let migrated: Vec<Point2d> = parse_scenario(&text).unwrap().points().unwrap();
+
+        assert_eq!(4, migrated.len());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(parse_scenario("[1, 2"), Err(ScenarioFileError::Json(_))));
+        assert!(matches!(parse_scenario("{\"name\": 1}"), Err(ScenarioFileError::UnknownLayout)));
+        assert!(matches!(parse_scenario("  "), Err(ScenarioFileError::UnknownLayout)));
+        assert!(matches!(
+            parse_scenario("{\"version\": 99, \"metadata\": {}, \"entities\": {}}"),
+            Err(ScenarioFileError::UnsupportedVersion(99))
+        ));
+
+        let file: ScenarioFile = ScenarioFile::new("convex-hull", ScenarioEntities { points: points(), ..Default::default() });
+        assert!(matches!(file.lines(), Err(ScenarioFileError::Missing("lines"))));
+
+        assert!(matches!(load_scenario("no/such/scenario.json"), Err(ScenarioFileError::Io(_))));
+    }
+
+    #[test]
+    fn test_gis_formats() {
+        let wkt: ScenarioFile = parse_wkt("MULTIPOINT (1 2, 3 4)\nLINESTRING (0 0, 1 1, 2 0)")
+            .map(from_geometries)
+            .unwrap();
+
+        assert_eq!(points(), wkt.entities.points);
+        assert_eq!(2, wkt.entities.lines.len());
+
+        let geojson: ScenarioFile = parse_scenario(r#"{"type": "MultiPoint", "coordinates": [[1, 2], [3, 4]]}"#).unwrap();
+        assert_eq!(points(), geojson.points().unwrap());
+
+        let error: ScenarioFileError = parse_scenario("{\"type\": \"Point\", \"coordinates\": []}").unwrap_err();
+        assert!(matches!(error, ScenarioFileError::Format(FormatError { line: 1, .. })));
+    }
+
+    #[test]
+    fn test_save_as_wkt_and_geojson() {
+        for extension in ["wkt", "geojson", "csv"] {
+            let path: String = temp_path(&format!("scenario_{}", extension)).replace(".json", &format!(".{}", extension));
+            let file: ScenarioFile = ScenarioFile::new("convex-hull", ScenarioEntities { points: points(), ..Default::default() });
+
+            save_scenario(&file, &path).unwrap();
+            let loaded: Result<Vec<Point2d>, ScenarioFileError> = load_points(&path);
+            fs::remove_file(&path).ok();
+
+            assert_eq!(points(), loaded.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_shorter_save_replaces_the_file() {
+        let path: String = temp_path("scenario_truncate");
+        let many: Vec<Point2d> = (0..50).map(|i| Point2d::new(i as f32, i as f32)).collect();
+
+        save_scenario(&ScenarioFile::new("convex-hull", ScenarioEntities { points: many, ..Default::default() }), &path).unwrap();
+        save_scenario(&ScenarioFile::new("convex-hull", ScenarioEntities { points: points(), ..Default::default() }), &path).unwrap();
+        let loaded: Result<Vec<Point2d>, ScenarioFileError> = load_points(&path);
+        fs::remove_file(&path).ok();
+
+        assert_eq!(points(), loaded.unwrap());
+    }
+
+    #[test]
+    fn test_drawings_are_imported_only() {
+        let path: String = temp_path("scenario_drawing").replace(".json", ".svg");
+        fs::write(&path, r#"<svg><line x1="0" y1="0" x2="4" y2="3"/><circle r="1"/></svg>"#).unwrap();
+
+        let loaded: Result<Vec<Line2D>, ScenarioFileError> = load_lines(&path);
+        let saved: Result<(), ScenarioFileError> = save_scenario(&ScenarioFile::new("unknown", Default::default()), &path);
+        fs::remove_file(&path).ok();
+
+        assert_eq!(vec![Line2D::new(Point2d::new(0f32, 0f32), Point2d::new(4f32, 3f32))], loaded.unwrap());
+        assert!(matches!(saved, Err(ScenarioFileError::Io(e)) if e.kind() == io::ErrorKind::Unsupported));
+        assert_eq!(ScenarioFormat::Dxf, ScenarioFormat::from_path("plan.DXF"));
+    }
+
+    #[test]
+    fn test_drawing_warnings_are_returned() {
+        let file: ScenarioFile =
+            read_scenario(r#"<svg><line x1="0" y1="0" x2="4" y2="3"/><circle r="1"/></svg>"#, ScenarioFormat::Svg).unwrap();
+
+        assert_eq!(1, file.warnings.len());
+        assert!(file.warnings[0].what.contains("circle"));
+        assert!(read_scenario("POINT (1 2)", ScenarioFormat::Wkt).unwrap().warnings.is_empty());
+    }
+}
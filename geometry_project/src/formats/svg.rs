@@ -0,0 +1,543 @@
+use crate::{
+    entities::{line2d::Line2D, point2d::Point2d},
+    formats::{drawing::Drawing, format_error::FormatError},
+};
+
+//Largest distance between a flattened curve and the real one, in SVG units
+pub const DEFAULT_FLATTEN_TOLERANCE: f32 = 0.1;
+//Bounds the subdivision of degenerate curves
+const MAX_FLATTEN_DEPTH: u32 = 16;
+
+//Shapes we know about but can not turn into lines and polygons
+const UNSUPPORTED_SHAPES: [&str; 7] = ["rect", "circle", "ellipse", "text", "image", "use", "foreignObject"];
+
+#[derive(Debug, Clone)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    line: usize,
+}
+
+impl Element {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    fn number(&self, name: &str) -> Result<f32, FormatError> {
+        match self.attribute(name) {
+            None => Ok(0f32),
+            Some(value) => value
+                .trim()
+                .parse()
+                .map_err(|_| FormatError::new(self.line, 1, format!("<{}> {} is not a number: '{}'", self.name, name, value))),
+        }
+    }
+}
+
+//Byte offsets where each line starts, to turn offsets into lines and columns
+struct Lines {
+    starts: Vec<usize>,
+}
+
+impl Lines {
+    fn new(text: &str) -> Self {
+        let mut starts: Vec<usize> = vec![0];
+        starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+
+        Lines { starts }
+    }
+
+    fn position(&self, text: &str, offset: usize) -> (usize, usize) {
+        let line: usize = self.starts.partition_point(|s| *s <= offset);
+        let start: usize = self.starts[line - 1];
+
+        (line, text[start..offset].chars().count() + 1)
+    }
+
+    fn error(&self, text: &str, offset: usize, message: impl Into<String>) -> FormatError {
+        let (line, column) = self.position(text, offset);
+        FormatError::new(line, column, message)
+    }
+}
+
+//Start tags with their attributes, comments, declarations and end tags are skipped
+fn elements(text: &str) -> Result<Vec<Element>, FormatError> {
+    let lines: Lines = Lines::new(text);
+    let bytes: &[u8] = text.as_bytes();
+    let mut elements: Vec<Element> = Vec::new();
+    let mut i: usize = 0;
+
+    let skip_past = |from: usize, end: &str| -> Result<usize, FormatError> {
+        text[from..]
+            .find(end)
+            .map(|found| from + found + end.len())
+            .ok_or_else(|| lines.error(text, from, format!("missing '{}'", end)))
+    };
+
+    while let Some(found) = text[i..].find('<') {
+        let start: usize = i + found;
+        let rest: &str = &text[start..];
+
+        if rest.starts_with("<!--") {
+            i = skip_past(start, "-->")?;
+            continue;
+        }
+
+        if rest.starts_with("<![CDATA[") {
+            i = skip_past(start, "]]>")?;
+            continue;
+        }
+
+        if rest.starts_with("<?") {
+            i = skip_past(start, "?>")?;
+            continue;
+        }
+
+        if rest.starts_with("<!") || rest.starts_with("</") {
+            i = skip_past(start, ">")?;
+            continue;
+        }
+
+        i = start + 1;
+        let name_end: usize = i + text[i..]
+            .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+            .ok_or_else(|| lines.error(text, start, "unfinished tag"))?;
+
+        if name_end == i {
+            return Err(lines.error(text, start, "expected an element name"));
+        }
+
+        let mut element: Element = Element {
+            name: text[i..name_end].to_string(),
+            attributes: Vec::new(),
+            line: lines.position(text, start).0,
+        };
+        i = name_end;
+
+        loop {
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+
+            match bytes.get(i) {
+                None => {
+                    return Err(lines.error(text, start, format!("<{}> is not closed", element.name)));
+                }
+                Some(b'>') => {
+                    i += 1;
+                    break;
+                }
+                Some(b'/') if bytes.get(i + 1) == Some(&b'>') => {
+                    i += 2;
+                    break;
+                }
+                _ => {}
+            }
+
+            let attribute_start: usize = i;
+            while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !matches!(bytes[i], b'=' | b'>' | b'/') {
+                i += 1;
+            }
+
+            let name: String = text[attribute_start..i].to_string();
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+
+            if name.is_empty() || bytes.get(i) != Some(&b'=') {
+                return Err(lines.error(text, attribute_start, format!("expected an attribute of <{}>", element.name)));
+            }
+            i += 1;
+
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+
+            let quote: u8 = match bytes.get(i) {
+                Some(q) if *q == b'"' || *q == b'\'' => *q,
+                _ => {
+                    return Err(lines.error(text, i, format!("expected a quoted value for {}", name)));
+                }
+            };
+
+            let value_end: usize = text[i + 1..]
+                .find(quote as char)
+                .map(|found| i + 1 + found)
+                .ok_or_else(|| lines.error(text, i, format!("the value of {} is not closed", name)))?;
+
+            element.attributes.push((name, text[i + 1..value_end].to_string()));
+            i = value_end + 1;
+        }
+
+        elements.push(element);
+    }
+
+    Ok(elements)
+}
+
+//Numbers in points and path data, separated by whitespace, commas or nothing
+//at all like "10-5.5.5"
+struct Numbers<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> Numbers<'a> {
+    fn new(text: &'a str) -> Self {
+        Numbers { text, offset: 0 }
+    }
+
+    fn skip_separators(&mut self) {
+        let rest: &str = &self.text[self.offset..];
+        self.offset += rest.len() - rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',').len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_separators();
+        self.text[self.offset..].chars().next()
+    }
+
+    fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+        let bytes: &[u8] = self.text.as_bytes();
+        let start: usize = self.offset;
+        let mut end: usize = start;
+        let mut dot: bool = false;
+
+        if matches!(bytes.get(end), Some(b'-') | Some(b'+')) {
+            end += 1;
+        }
+
+        while let Some(b) = bytes.get(end) {
+            match b {
+                b'0'..=b'9' => end += 1,
+                b'.' if !dot => {
+                    dot = true;
+                    end += 1;
+                }
+                b'e' | b'E' if end > start => {
+                    end += 1;
+                    if matches!(bytes.get(end), Some(b'-') | Some(b'+')) {
+                        end += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        let value: f32 = self.text[start..end].parse().ok()?;
+        self.offset = end;
+        Some(value)
+    }
+
+    fn point(&mut self) -> Option<Point2d> {
+        Some(Point2d::new(self.number()?, self.number()?))
+    }
+}
+
+fn points_attribute(element: &Element) -> Result<Vec<Point2d>, FormatError> {
+    let mut numbers: Numbers = Numbers::new(element.attribute("points").unwrap_or(""));
+    let mut points: Vec<Point2d> = Vec::new();
+
+    while numbers.peek().is_some() {
+        points.push(numbers.point().ok_or_else(|| {
+            FormatError::new(element.line, 1, format!("<{}> points are not pairs of numbers", element.name))
+        })?);
+    }
+
+    Ok(points)
+}
+
+fn mid(a: Point2d, b: Point2d) -> Point2d {
+    Point2d::new((a.x + b.x) / 2f32, (a.y + b.y) / 2f32)
+}
+
+fn distance_to_line(p: Point2d, a: Point2d, b: Point2d) -> f32 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len: f32 = (dx * dx + dy * dy).sqrt();
+
+    match len > 0f32 {
+        true => ((p.x - a.x) * dy - (p.y - a.y) * dx).abs() / len,
+        false => ((p.x - a.x).powi(2) + (p.y - a.y).powi(2)).sqrt(),
+    }
+}
+
+//Halves the curve until its control points are within tolerance of the
+//chord, the end points of the pieces are pushed, the start is not
+pub fn flatten_cubic(curve: [Point2d; 4], tolerance: f32, out: &mut Vec<Point2d>) {
+    flatten_cubic_depth(curve, tolerance, out, 0);
+}
+
+fn flatten_cubic_depth(curve: [Point2d; 4], tolerance: f32, out: &mut Vec<Point2d>, depth: u32) {
+    let [p0, p1, p2, p3] = curve;
+    let flatness: f32 = distance_to_line(p1, p0, p3).max(distance_to_line(p2, p0, p3));
+
+    if depth >= MAX_FLATTEN_DEPTH || flatness <= tolerance {
+        out.push(p3);
+        return;
+    }
+
+    let (p01, p12, p23) = (mid(p0, p1), mid(p1, p2), mid(p2, p3));
+    let (p012, p123) = (mid(p01, p12), mid(p12, p23));
+    let middle: Point2d = mid(p012, p123);
+
+    flatten_cubic_depth([p0, p01, p012, middle], tolerance, out, depth + 1);
+    flatten_cubic_depth([middle, p123, p23, p3], tolerance, out, depth + 1);
+}
+
+//A quadratic is the cubic with its control points 2/3 of the way to the control
+fn quadratic_to_cubic(p0: Point2d, control: Point2d, p2: Point2d) -> [Point2d; 4] {
+    let c1: Point2d = Point2d::new(p0.x + 2f32 / 3f32 * (control.x - p0.x), p0.y + 2f32 / 3f32 * (control.y - p0.y));
+    let c2: Point2d = Point2d::new(p2.x + 2f32 / 3f32 * (control.x - p2.x), p2.y + 2f32 / 3f32 * (control.y - p2.y));
+
+    [p0, c1, c2, p2]
+}
+
+fn reflect(control: Point2d, about: Point2d) -> Point2d {
+    Point2d::new(2f32 * about.x - control.x, 2f32 * about.y - control.y)
+}
+
+//The last control point, so S and T can mirror it
+#[derive(Debug, Clone, Copy)]
+enum Control {
+    None,
+    Cubic(Point2d),
+    Quadratic(Point2d),
+}
+
+fn parse_path(element: &Element, tolerance: f32, drawing: &mut Drawing) -> Result<(), FormatError> {
+    let data: &str = element.attribute("d").unwrap_or("");
+    let mut numbers: Numbers = Numbers::new(data);
+    let error = |offset: usize, message: &str| FormatError::new(element.line, 1, format!("<path> d at {}: {}", offset, message));
+
+    let mut subpath: Vec<Point2d> = Vec::new();
+    let mut current: Point2d = Point2d::new(0f32, 0f32);
+    let mut start: Point2d = current;
+    let mut control: Control = Control::None;
+    let mut command: Option<char> = None;
+    let mut arcs: bool = false;
+
+    while let Some(c) = numbers.peek() {
+        if c.is_ascii_alphabetic() {
+            command = Some(c);
+            numbers.offset += 1;
+        }
+
+        let cmd: char = command.ok_or_else(|| error(numbers.offset, "expected a command"))?;
+        let relative: bool = cmd.is_ascii_lowercase();
+        let origin: Point2d = match relative {
+            true => current,
+            false => Point2d::new(0f32, 0f32),
+        };
+
+        let offset: usize = numbers.offset;
+        let missing = || error(offset, &format!("{} is missing numbers", cmd));
+        let point = |numbers: &mut Numbers| -> Result<Point2d, FormatError> {
+            let p: Point2d = numbers.point().ok_or_else(missing)?;
+            Ok(Point2d::new(origin.x + p.x, origin.y + p.y))
+        };
+
+        let mut next_control: Control = Control::None;
+
+        match cmd.to_ascii_uppercase() {
+            'M' => {
+                drawing.push_polyline(&subpath, false);
+                current = point(&mut numbers)?;
+                start = current;
+                subpath = vec![current];
+
+                //more pairs after a move are lines
+                command = Some(if relative { 'l' } else { 'L' });
+            }
+            'L' => {
+                current = point(&mut numbers)?;
+                subpath.push(current);
+            }
+            'H' => {
+                let x: f32 = numbers.number().ok_or_else(missing)?;
+                current = Point2d::new(origin.x + x, current.y);
+                subpath.push(current);
+            }
+            'V' => {
+                let y: f32 = numbers.number().ok_or_else(missing)?;
+                current = Point2d::new(current.x, origin.y + y);
+                subpath.push(current);
+            }
+            'C' | 'S' => {
+                let c1: Point2d = match (cmd.to_ascii_uppercase(), control) {
+                    ('C', _) => point(&mut numbers)?,
+                    (_, Control::Cubic(previous)) => reflect(previous, current),
+                    _ => current,
+                };
+                let c2: Point2d = point(&mut numbers)?;
+                let end: Point2d = point(&mut numbers)?;
+
+                flatten_cubic([current, c1, c2, end], tolerance, &mut subpath);
+                current = end;
+                next_control = Control::Cubic(c2);
+            }
+            'Q' | 'T' => {
+                let c: Point2d = match (cmd.to_ascii_uppercase(), control) {
+                    ('Q', _) => point(&mut numbers)?,
+                    (_, Control::Quadratic(previous)) => reflect(previous, current),
+                    _ => current,
+                };
+                let end: Point2d = point(&mut numbers)?;
+
+                flatten_cubic(quadratic_to_cubic(current, c, end), tolerance, &mut subpath);
+                current = end;
+                next_control = Control::Quadratic(c);
+            }
+            'A' => {
+                //radii, rotation and the two flags
+                for _ in 0..5 {
+                    numbers.number().ok_or_else(missing)?;
+                }
+
+                current = point(&mut numbers)?;
+                subpath.push(current);
+                arcs = true;
+            }
+            'Z' => {
+                if subpath.len() > 1 && subpath.last() == Some(&start) {
+                    subpath.pop();
+                }
+
+                drawing.push_polyline(&subpath, true);
+                current = start;
+                subpath = vec![start];
+                command = None;
+            }
+            other => {
+                return Err(error(offset, &format!("unknown command {}", other)));
+            }
+        }
+
+        control = next_control;
+    }
+
+    drawing.push_polyline(&subpath, false);
+
+    if arcs {
+        drawing.report(element.line, "arcs in <path> are replaced by straight segments");
+    }
+
+    Ok(())
+}
+
+//Reads <line>, <polyline>, <polygon> and <path> into lines and polygons, curves
+//are flattened to within tolerance. Coordinates are kept as they are, so y
+//grows downwards like in the SVG. Transforms and other shapes are reported
+pub fn parse_svg(text: &str, tolerance: f32) -> Result<Drawing, FormatError> {
+    let mut drawing: Drawing = Drawing::new();
+
+    for element in elements(text)? {
+        if element.attribute("transform").is_some() {
+            drawing.report(element.line, format!("the transform of <{}> is ignored", element.name));
+        }
+
+        match element.name.as_str() {
+            "line" => drawing.lines.push(Line2D::new(
+                Point2d::new(element.number("x1")?, element.number("y1")?),
+                Point2d::new(element.number("x2")?, element.number("y2")?),
+            )),
+            "polyline" => drawing.push_polyline(&points_attribute(&element)?, false),
+            "polygon" => drawing.push_polyline(&points_attribute(&element)?, true),
+            "path" => parse_path(&element, tolerance, &mut drawing)?,
+            name if UNSUPPORTED_SHAPES.contains(&name) => {
+                drawing.report(element.line, format!("<{}> elements are not supported", name))
+            }
+            _ => {}
+        }
+    }
+
+    Ok(drawing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn svg(body: &str) -> String {
+        format!(
+            "<?xml version=\"1.0\"?>\n<!-- drawn by hand -->\n<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox='0 0 100 100'>\n{}\n</svg>",
+            body
+        )
+    }
+
+    #[test]
+    fn test_basic_shapes() {
+        let drawing: Drawing = parse_svg(
+            &svg(r#"<line x1="0" y1="1" x2="10" y2="11"/>
+<polyline points="0,0 10,0 10,10"/>
+<polygon points="0 0 5 0 5 5 0 5" fill="none"></polygon>"#),
+            DEFAULT_FLATTEN_TOLERANCE,
+        )
+        .unwrap();
+
+        assert_eq!(3, drawing.lines.len());
+        assert_eq!(Point2d::new(10f32, 11f32), drawing.lines[0].end);
+        assert_eq!(1, drawing.polygons.len());
+        assert_eq!(4, drawing.polygons[0].points.len());
+        assert!(drawing.unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_path_commands() {
+        let drawing: Drawing = parse_svg(&svg(r#"<path d="M10-5.5h5v5H10z m20,0 l5 0 l0 5"/>"#), 0.1f32).unwrap();
+
+        assert_eq!(1, drawing.polygons.len());
+        assert_eq!(
+            vec![Point2d::new(10f32, -5.5f32), Point2d::new(15f32, -5.5f32), Point2d::new(15f32, -0.5f32), Point2d::new(10f32, -0.5f32)],
+            drawing.polygons[0].points
+        );
+
+        //the second subpath starts relative to where the first one closed
+        assert_eq!(2, drawing.lines.len());
+        assert_eq!(Point2d::new(30f32, -5.5f32), drawing.lines[0].start);
+    }
+
+    #[test]
+    fn test_curves_are_flattened_to_tolerance() {
+        let coarse: Drawing = parse_svg(&svg(r#"<path d="M0 0 C 0 100 100 100 100 0"/>"#), 10f32).unwrap();
+        let fine: Drawing = parse_svg(&svg(r#"<path d="M0 0 C 0 100 100 100 100 0"/>"#), 0.1f32).unwrap();
+
+        assert!(coarse.lines.len() < fine.lines.len());
+        assert_eq!(Point2d::new(100f32, 0f32), fine.lines.last().unwrap().end);
+
+        //the curve peaks at 75 in the middle
+        let peak: f32 = fine.lines.iter().map(|l| l.end.y).fold(0f32, f32::max);
+        assert!((peak - 75f32).abs() < 0.1f32);
+
+        let quadratic: Drawing = parse_svg(&svg(r#"<path d="M0 0 Q 50 100 100 0 T 200 0"/>"#), 0.1f32).unwrap();
+        let lowest: f32 = quadratic.lines.iter().map(|l| l.end.y).fold(0f32, f32::min);
+        assert!((lowest + 50f32).abs() < 0.1f32);
+    }
+
+    #[test]
+    fn test_unsupported_are_reported() {
+        let drawing: Drawing = parse_svg(
+            &svg(r#"<g transform="translate(5 5)"><circle cx="1" cy="1" r="1"/></g>
+<path d="M0 0 A 5 5 0 0 1 10 0"/>"#),
+            DEFAULT_FLATTEN_TOLERANCE,
+        )
+        .unwrap();
+
+        assert_eq!(1, drawing.lines.len());
+        assert_eq!(3, drawing.unsupported.len());
+        assert!(drawing.unsupported.iter().all(|u| u.line == 4 || u.line == 5));
+    }
+
+    #[test]
+    fn test_errors() {
+        let unquoted: FormatError = parse_svg("<svg>\n  <line x1=0/>\n</svg>", 1f32).unwrap_err();
+        assert_eq!((2, 12), (unquoted.line, unquoted.column));
+
+        let bad_points: FormatError = parse_svg("<svg>\n\n<polyline points=\"1 2 3\"/></svg>", 1f32).unwrap_err();
+        assert_eq!(3, bad_points.line);
+
+        assert!(parse_svg("<svg><path d=\"10 10\"/></svg>", 1f32).is_err());
+        assert!(parse_svg("<svg><line x1=\"a\"/></svg>", 1f32).is_err());
+        assert!(parse_svg("<svg><!-- never closed", 1f32).is_err());
+    }
+}
@@ -0,0 +1,408 @@
+use crate::{
+    entities::{point2d::Point2d, polygon2d::Polygon2D, polygon_with_holes::PolygonWithHoles},
+    formats::{format_error::FormatError, geometry::Geometry},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(f32),
+    Open,
+    Close,
+    Comma,
+}
+
+#[derive(Debug, Clone)]
+struct Spanned {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+fn is_number_start(c: char) -> bool {
+    c.is_ascii_digit() || c == '-' || c == '+' || c == '.'
+}
+
+fn tokenize(text: &str) -> Result<Vec<Spanned>, FormatError> {
+    let mut tokens: Vec<Spanned> = Vec::new();
+    let mut chars = text.chars().peekable();
+    let (mut line, mut column) = (1usize, 1usize);
+
+    while let Some(&c) = chars.peek() {
+        let (start_line, start_column) = (line, column);
+
+        if c == '\n' {
+            chars.next();
+            line += 1;
+            column = 1;
+            continue;
+        }
+
+        if c.is_whitespace() {
+            chars.next();
+            column += 1;
+            continue;
+        }
+
+        let token: Token = match c {
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            c if c.is_ascii_alphabetic() => {
+                let mut word: String = String::new();
+
+                while let Some(&w) = chars.peek().filter(|w| w.is_ascii_alphanumeric()) {
+                    word.push(w.to_ascii_uppercase());
+                    chars.next();
+                    column += 1;
+                }
+
+                tokens.push(Spanned { token: Token::Word(word), line: start_line, column: start_column });
+                continue;
+            }
+            c if is_number_start(c) => {
+                let mut number: String = String::new();
+
+                while let Some(&n) = chars.peek().filter(|n| is_number_start(**n) || **n == 'e' || **n == 'E') {
+                    number.push(n);
+                    chars.next();
+                    column += 1;
+                }
+
+                //too large for an f32 parses as infinity, that is no coordinate either
+                let value: f32 = number
+                    .parse()
+                    .ok()
+                    .filter(|v: &f32| v.is_finite())
+                    .ok_or_else(|| FormatError::new(start_line, start_column, format!("invalid number '{}'", number)))?;
+
+                tokens.push(Spanned { token: Token::Number(value), line: start_line, column: start_column });
+                continue;
+            }
+            other => {
+                return Err(FormatError::new(line, column, format!("unexpected character '{}'", other)));
+            }
+        };
+
+        chars.next();
+        column += 1;
+        tokens.push(Spanned { token, line: start_line, column: start_column });
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    index: usize,
+    //Where the input ends, for errors about missing tokens
+    end: (usize, usize),
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|s| &s.token)
+    }
+
+    fn error(&self, message: impl Into<String>) -> FormatError {
+        match self.tokens.get(self.index) {
+            Some(s) => FormatError::new(s.line, s.column, message),
+            None => FormatError::new(self.end.0, self.end.1, message),
+        }
+    }
+
+    fn next(&mut self, expected: &str) -> Result<Token, FormatError> {
+        match self.tokens.get(self.index) {
+            Some(s) => {
+                self.index += 1;
+                Ok(s.token.clone())
+            }
+            None => Err(self.error(format!("expected {}, found the end of the input", expected))),
+        }
+    }
+
+    fn expect(&mut self, token: Token, expected: &str) -> Result<(), FormatError> {
+        match self.peek() {
+            Some(t) if *t == token => {
+                self.index += 1;
+                Ok(())
+            }
+            _ => Err(self.error(format!("expected {}", expected))),
+        }
+    }
+
+    fn number(&mut self) -> Result<f32, FormatError> {
+        match self.peek() {
+            Some(Token::Number(n)) => {
+                let n: f32 = *n;
+                self.index += 1;
+                Ok(n)
+            }
+            _ => Err(self.error("expected a number")),
+        }
+    }
+
+    //x y with an optional z and m that are dropped
+    fn coordinate(&mut self) -> Result<Point2d, FormatError> {
+        let point: Point2d = Point2d::new(self.number()?, self.number()?);
+
+        for _ in 0..2 {
+            if let Some(Token::Number(_)) = self.peek() {
+                self.index += 1;
+            }
+        }
+
+        Ok(point)
+    }
+
+    //( item, item, .. )
+    fn list<T>(&mut self, item: fn(&mut Parser) -> Result<T, FormatError>) -> Result<Vec<T>, FormatError> {
+        self.expect(Token::Open, "'('")?;
+        let mut items: Vec<T> = vec![item(self)?];
+
+        loop {
+            match self.next("',' or ')'")? {
+                Token::Comma => items.push(item(self)?),
+                Token::Close => return Ok(items),
+                _ => {
+                    self.index -= 1;
+                    return Err(self.error("expected ',' or ')'"));
+                }
+            }
+        }
+    }
+
+    fn coordinates(&mut self) -> Result<Vec<Point2d>, FormatError> {
+        self.list(Parser::coordinate)
+    }
+
+    //Rings are written closed, Polygon2D closes itself so the last point is dropped
+    fn ring(&mut self) -> Result<Polygon2D, FormatError> {
+        let start: usize = self.index;
+        let mut points: Vec<Point2d> = self.coordinates()?;
+
+        if points.len() < 4 || points.first() != points.last() {
+            self.index = start;
+            return Err(self.error("a ring needs at least 4 points and has to end where it starts"));
+        }
+
+        points.pop();
+        Ok(Polygon2D::new(points))
+    }
+
+    fn polygon(&mut self) -> Result<PolygonWithHoles, FormatError> {
+        let mut rings: Vec<Polygon2D> = self.list(Parser::ring)?;
+        let exterior: Polygon2D = rings.remove(0);
+
+        Ok(PolygonWithHoles::new(exterior, rings))
+    }
+
+    //MULTIPOINT takes both (1 2, 3 4) and ((1 2), (3 4))
+    fn multi_point_item(&mut self) -> Result<Point2d, FormatError> {
+        match self.peek() {
+            Some(Token::Open) => {
+                self.index += 1;
+                let point: Point2d = self.coordinate()?;
+                self.expect(Token::Close, "')'")?;
+                Ok(point)
+            }
+            _ => self.coordinate(),
+        }
+    }
+
+    fn geometry(&mut self) -> Result<Geometry, FormatError> {
+        let tag_position: usize = self.index;
+        let tag: String = match self.next("a geometry type")? {
+            Token::Word(word) => word,
+            _ => {
+                self.index -= 1;
+                return Err(self.error("expected a geometry type"));
+            }
+        };
+
+        if let Some(Token::Word(dimension)) = self.peek() {
+            if matches!(dimension.as_str(), "Z" | "M" | "ZM") {
+                self.index += 1;
+            }
+        }
+
+        //empty geometries contribute nothing, whatever their type
+        if let Some(Token::Word(word)) = self.peek() {
+            if word == "EMPTY" {
+                self.index += 1;
+                return Ok(Geometry::GeometryCollection(Vec::new()));
+            }
+        }
+
+        match tag.as_str() {
+            "POINT" => {
+                let points: Vec<Point2d> = self.coordinates()?;
+
+                match points.len() {
+                    1 => Ok(Geometry::Point(points[0])),
+                    _ => {
+                        self.index = tag_position;
+                        Err(self.error("POINT takes a single coordinate"))
+                    }
+                }
+            }
+            "LINESTRING" => Ok(Geometry::LineString(self.coordinates()?)),
+            "POLYGON" => Ok(Geometry::Polygon(self.polygon()?)),
+            "MULTIPOINT" => Ok(Geometry::MultiPoint(self.list(Parser::multi_point_item)?)),
+            "MULTILINESTRING" => Ok(Geometry::MultiLineString(self.list(Parser::coordinates)?)),
+            "MULTIPOLYGON" => Ok(Geometry::MultiPolygon(self.list(Parser::polygon)?)),
+            "GEOMETRYCOLLECTION" => Ok(Geometry::GeometryCollection(self.list(Parser::geometry)?)),
+            other => {
+                self.index = tag_position;
+                Err(self.error(format!("unknown geometry type {}", other)))
+            }
+        }
+    }
+}
+
+//One or more geometries separated by whitespace, usually one per line
+pub fn parse_wkt(text: &str) -> Result<Vec<Geometry>, FormatError> {
+    let last_line: &str = text.rsplit('\n').next().unwrap_or("");
+    let mut parser: Parser = Parser {
+        tokens: tokenize(text)?,
+        index: 0,
+        end: (text.matches('\n').count() + 1, last_line.chars().count() + 1),
+    };
+
+    let mut geometries: Vec<Geometry> = Vec::new();
+
+    while parser.peek().is_some() {
+        geometries.push(parser.geometry()?);
+    }
+
+    Ok(geometries)
+}
+
+fn write_coordinates(points: &[Point2d]) -> String {
+    let coordinates: Vec<String> = points.iter().map(|p| format!("{} {}", p.x, p.y)).collect();
+
+    format!("({})", coordinates.join(", "))
+}
+
+fn write_ring(ring: &Polygon2D) -> String {
+    let mut points: Vec<Point2d> = ring.points.clone();
+    points.extend(ring.points.first());
+
+    write_coordinates(&points)
+}
+
+fn write_polygon(polygon: &PolygonWithHoles) -> String {
+    let rings: Vec<String> = std::iter::once(&polygon.exterior)
+        .chain(polygon.holes.iter())
+        .map(write_ring)
+        .collect();
+
+    format!("({})", rings.join(", "))
+}
+
+fn write_list<T>(items: &[T], write: fn(&T) -> String) -> String {
+    match items.is_empty() {
+        true => "EMPTY".to_string(),
+        false => format!("({})", items.iter().map(write).collect::<Vec<String>>().join(", ")),
+    }
+}
+
+pub fn to_wkt(geometry: &Geometry) -> String {
+    match geometry {
+        Geometry::Point(p) => format!("POINT ({} {})", p.x, p.y),
+        Geometry::LineString(points) if points.is_empty() => "LINESTRING EMPTY".to_string(),
+        Geometry::LineString(points) => format!("LINESTRING {}", write_coordinates(points)),
+        Geometry::Polygon(polygon) => format!("POLYGON {}", write_polygon(polygon)),
+        Geometry::MultiPoint(points) => format!("MULTIPOINT {}", write_list(points, |p| format!("({} {})", p.x, p.y))),
+        Geometry::MultiLineString(strings) => format!("MULTILINESTRING {}", write_list(strings, |s| write_coordinates(s))),
+        Geometry::MultiPolygon(polygons) => format!("MULTIPOLYGON {}", write_list(polygons, write_polygon)),
+        Geometry::GeometryCollection(geometries) => format!("GEOMETRYCOLLECTION {}", write_list(geometries, to_wkt)),
+    }
+}
+
+//One geometry per line, what parse_wkt reads back
+pub fn write_wkt(geometries: &[Geometry]) -> String {
+    geometries.iter().map(|g| to_wkt(g) + "\n").collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(text: &str) -> Geometry {
+        let mut geometries: Vec<Geometry> = parse_wkt(text).unwrap();
+        assert_eq!(1, geometries.len());
+        geometries.remove(0)
+    }
+
+    #[test]
+    fn test_point_and_line_string() {
+        assert_eq!(Geometry::Point(Point2d::new(1f32, -2.5f32)), parse_one("POINT (1 -2.5)"));
+        assert_eq!(Geometry::Point(Point2d::new(1f32, 2f32)), parse_one("point z (1 2 3)"));
+
+        let line: Geometry = parse_one("LINESTRING (0 0, 10 0, 10 1e1)");
+        assert_eq!(2, line.lines().len());
+        assert_eq!(Point2d::new(10f32, 10f32), line.lines()[1].end);
+    }
+
+    #[test]
+    fn test_polygon_with_hole() {
+        let geometry: Geometry = parse_one("POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0), (4 4, 6 4, 6 6, 4 4))");
+        let polygon: &PolygonWithHoles = &geometry.polygons()[0];
+
+        assert_eq!(4, polygon.exterior.points.len());
+        assert_eq!(1, polygon.holes.len());
+        assert_eq!(3, polygon.holes[0].points.len());
+    }
+
+    #[test]
+    fn test_multi_geometries() {
+        assert_eq!(parse_one("MULTIPOINT (1 2, 3 4)"), parse_one("MULTIPOINT ((1 2), (3 4))"));
+        assert_eq!(3, parse_one("MULTILINESTRING ((0 0, 1 1), (2 2, 3 3, 4 4))").lines().len());
+        assert_eq!(2, parse_one("MULTIPOLYGON (((0 0, 1 0, 1 1, 0 0)), ((5 5, 6 5, 6 6, 5 5)))").polygons().len());
+
+        let collection: Geometry = parse_one("GEOMETRYCOLLECTION (POINT (1 1), LINESTRING (0 0, 1 1), POINT EMPTY)");
+        assert_eq!(1, collection.points().len());
+        assert_eq!(1, collection.lines().len());
+    }
+
+    #[test]
+    fn test_several_geometries() {
+        let geometries: Vec<Geometry> = parse_wkt("POINT (1 1)\nPOINT (2 2)\n\nLINESTRING (0 0, 1 1)\n").unwrap();
+
+        assert_eq!(3, geometries.len());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text: &str = "POINT (1 2)
+LINESTRING (0 0, 1.5 2)
+POLYGON ((0 0, 10 0, 10 10, 0 0), (1 1, 2 1, 2 2, 1 1))
+MULTIPOINT ((1 2), (3 4))
+GEOMETRYCOLLECTION (POINT (1 1), MULTILINESTRING ((0 0, 1 1)))
+";
+        let geometries: Vec<Geometry> = parse_wkt(text).unwrap();
+
+        assert_eq!(text, write_wkt(&geometries));
+    }
+
+    #[test]
+    fn test_error_positions() {
+        assert_eq!(FormatError::new(2, 8, "expected a number"), parse_wkt("POINT (1 1)\nPOINT (x 1)").unwrap_err());
+
+        let unknown: FormatError = parse_wkt("  CIRCLE (1 1)").unwrap_err();
+        assert_eq!((1, 3), (unknown.line, unknown.column));
+
+        let open_ring: FormatError = parse_wkt("POLYGON (\n  (0 0, 1 0, 1 1, 0 1))").unwrap_err();
+        assert_eq!((2, 3), (open_ring.line, open_ring.column));
+
+        let unfinished: FormatError = parse_wkt("LINESTRING (0 0, 1 1").unwrap_err();
+        assert_eq!((1, 21), (unfinished.line, unfinished.column));
+
+        let huge: FormatError = parse_wkt("POINT (1 2)\nPOINT (1e40 2)").unwrap_err();
+        assert_eq!(FormatError::new(2, 8, "invalid number '1e40'"), huge);
+
+        let character: FormatError = parse_wkt("POINT (1 1);").unwrap_err();
+        assert_eq!((1, 12), (character.line, character.column));
+    }
+}
@@ -0,0 +1,31 @@
+//Entities, numerics, algorithms and scenes for other crates. The window, the
+//scenarios, Ctrl^C handling and the local logging crates are behind the viewer
+//feature, depend on this with default-features = false to leave them out
+pub mod algorithms;
+pub mod display;
+pub mod entities;
+pub mod extensions;
+pub mod formats;
+pub mod numerics;
+//The interactive scenarios only run in the window
+#[cfg(feature = "viewer")]
+pub mod scenarios;
+pub mod scene_logger;
+pub mod spatial;
+#[cfg(test)]
+mod testing_tools;
+
+//Stands in for the <name>_log! macros def_log! defines when the logging crates
+//are left out. Statements only borrow their arguments, so nothing is formatted
+//and no variable goes unused
+#[cfg(not(feature = "log-statements"))]
+macro_rules! no_log {
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {{
+        $(let _ = &$arg;)*
+    }};
+}
+#[cfg(not(feature = "log-statements"))]
+pub(crate) use no_log;
+
+//Scenes are what the algorithms trace, geometry_project::scene without display
+pub use display::scene;
@@ -1,111 +1,84 @@
-pub mod algorithms;
-pub mod display;
-pub mod entities;
-pub mod extensions;
-pub mod numerics;
-mod scenarios;
-pub mod scene_logger;
-mod testing_tools;
-
-use ctrlc::set_handler;
-use data_structures::vec2d::Vec2D;
-use display::{camera::Camera, rgb::RGB, scenario::Scenario};
-use entities::{line2d::Line2D, point2d::Point2d, rectangle2d::Rectangle2D};
-use log::{log, Level, Log, trace};
-use log_statement::def_log;
-use logging::flush;
-use logging::logger::logger::LoggingManager;
-use minifb::{Key, Window, WindowOptions};
-use scenarios::{
-    convex_hull_scenario::ConvexHullScenario, line_intersection_scenario::LineIntersectionScenario,
-    right_turn_debug::RightTurnDebug,
+mod batch;
+mod cli;
+#[cfg(feature = "viewer")]
+mod viewer;
+
+use cli::{BatchJob, Command, Options};
+use geometry_project::{
+    display::animation::{self, AnimationFrame, AnimationOptions},
+    scene_logger::scene_log_reader,
 };
-use std::time::Duration;
-
-pub const WINDOW_WIDTH: usize = 512;
-pub const WINDOW_HEIGHT: usize = 512;
+use std::io::{self, BufWriter};
 
 fn main() {
-    LoggingManager::init("log_output.txt").expect("Failed to initialize Logger");
-
-    set_handler(|| handle_sigint()).expect("Error setting ctrlc hook");
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-    let window = Window::new(
-        "Geometry Renderer - Esc to exit",
-        WINDOW_WIDTH,
-        WINDOW_HEIGHT,
-        WindowOptions::default(),
-    )
-    .unwrap_or_else(|e| {
-        panic!("{}", e);
+    let options: Options = cli::parse_args(&args).unwrap_or_else(|e| {
+        eprintln!("{}\n\n{}", e, cli::USAGE);
+        std::process::exit(2);
     });
 
-    let buffer = blank_screen();
-
-    log!(Level::Info, "Lets start");
-
-    window_loop(window, buffer);
+    match &options.command {
+        Command::Help => println!("{}", cli::USAGE),
+        Command::List => list_scenarios(),
+        Command::Export(input, output) => export_animation(input, output, &options),
+        Command::Batch(job, input) => run_batch(job, input.as_deref(), &options),
+        Command::Run | Command::Replay(_) | Command::Diff(_, _) => open_viewer(&options),
+    }
 }
 
-fn handle_sigint() {
-    eprintln!("Caught Ctrl^C flushing log");
-    LoggingManager::static_flush();
+fn exit_with<T>(error: &str, context: &str) -> T {
+    eprintln!("{}: {}", error, context);
     std::process::exit(1);
 }
 
-fn blank_screen() -> Vec2D<RGB> {
-    Vec2D::new_from_flatpack(
-        vec![RGB::black(); WINDOW_WIDTH * WINDOW_HEIGHT],
-        WINDOW_WIDTH,
-        WINDOW_HEIGHT,
-    )
-    .expect("width height unexpected")
+#[cfg(feature = "viewer")]
+fn open_viewer(options: &Options) {
+    viewer::open(options);
 }
 
-fn window_loop(mut window: Window, mut buffer: Vec2D<RGB>) {
-    let mut camera: Camera = Camera::new(100f32, 100f32);
-
-    let min: Point2d = Point2d { x: 25f32, y: 25f32 };
-    let max: Point2d = Point2d { x: 75f32, y: 75f32 };
+#[cfg(not(feature = "viewer"))]
+fn open_viewer(_options: &Options) {
+    eprintln!("Built without a window, rebuild with --features viewer to run, replay or diff");
+    std::process::exit(2);
+}
 
-    let scenario: &mut dyn Scenario =
-         &mut LineIntersectionScenario::new_first_intersection(2, Rectangle2D { min, max })
-         .round_points();
+#[cfg(feature = "viewer")]
+fn list_scenarios() {
+    println!("{}", geometry_project::scenarios::registry::describe());
+}
 
-    //let scenario: &mut dyn Scenario = &mut ConvexHullScenario::new(10, Rectangle2D { min, max });
+#[cfg(not(feature = "viewer"))]
+fn list_scenarios() {
+    eprintln!("Built without a window, the scenarios need --features viewer");
+    std::process::exit(2);
+}
 
-    match scenario.initialize() {
-        Ok(_) => {}
-        Err(e_msg) => {
-            panic!("{}", e_msg);
-        }
+//Renders the logged or traced steps without opening a window
+fn export_animation(input: &str, output: &str, options: &Options) {
+    let frames: Vec<AnimationFrame> = scene_log_reader::read_scenes(input)
+        .unwrap_or_else(|e| exit_with(e, input))
+        .into_iter()
+        .map(AnimationFrame::from)
+        .collect();
+
+    let animation_options: AnimationOptions = AnimationOptions {
+        width: options.window.0,
+        height: options.window.1,
+        delay_ms: options.delay_ms,
+        captions: options.captions,
     };
 
-    println!("Ready to render");
-
-    while window.is_open() && !window.is_key_down(Key::Escape) {
-        std::thread::sleep(Duration::from_millis(5));
-
-        trace!("next frame");
-        scenario.handle_input(&window);
-
-        if scenario.redraw() {
-            trace!("Redraw scenario");
-            camera.clear();
-            buffer = blank_screen();
-            scenario.process(&mut camera);
-
-            camera.draw(&mut buffer);
-        }
-
-        buffer_to_window(&mut window, buffer.clone());
+    match animation::export(&frames, output, &animation_options) {
+        Ok(files) => println!("{} frames from {} written to {} file(s) at {}", frames.len(), input, files, output),
+        Err(e) => exit_with(&e.to_string(), output),
     }
-
-    flush!("end");
 }
 
-fn buffer_to_window(window: &mut Window, buffer2d: Vec2D<RGB>) {
-    let buffer: Vec<u32> = buffer2d.into_iter().map(|r| r.0).collect();
-
-    window.update_with_buffer(buffer.as_slice(), WINDOW_WIDTH, WINDOW_HEIGHT);
+//No logger and no window, only the result goes to stdout so it can be piped
+fn run_batch(job: &BatchJob, input: Option<&str>, options: &Options) {
+    if let Err(e) = batch::run(job, input, options, BufWriter::new(io::stdout().lock())) {
+        eprintln!("{}", e);
+        std::process::exit(e.exit_code());
+    }
 }
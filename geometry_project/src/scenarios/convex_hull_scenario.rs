@@ -2,8 +2,22 @@ use log::debug;
 
 use crate::{
     algorithms::{convex_hull, random_geometry::Random2D},
-    display::scenario::Scenario,
-    entities::{point2d::Point2d, rectangle2d::Rectangle2D},
+    display::{
+        editor::Editor,
+        input::{InputFrame, Key, KeyRepeat},
+        label::Label,
+        rgb::RGB,
+        rgba::RGBA,
+        scenario::{KeyBinding, Scenario, ScenarioInput},
+        scene_proxy::ISceneProxy,
+        style::PointStyle,
+    },
+    entities::{point2d::Point2d, polygon2d::Polygon2D, rectangle2d::Rectangle2D},
+    formats::scenario_serializer::{self, ScenarioEntities, ScenarioFile, ScenarioFileError},
+    scene_logger::{
+        scene_logger::SceneLogger,
+        trace_file::{TraceHeader, TraceWriter},
+    },
 };
 
 pub struct ConvexHullScenario {
@@ -11,8 +25,13 @@ pub struct ConvexHullScenario {
     pub rect: Rectangle2D,
     points: Vec<Point2d>,
     new_pts: bool,
+    trace_path: Option<String>,
+    seed: Option<u64>,
+    editor: Editor,
 }
 
+const SCENARIO_FILE: &str = "convex_hull_scenario.txt";
+
 impl ConvexHullScenario {
     pub fn new(count: usize, rect: Rectangle2D) -> Self {
         ConvexHullScenario {
@@ -20,10 +39,93 @@ impl ConvexHullScenario {
             rect,
             points: Vec::with_capacity(count),
             new_pts: true,
+            trace_path: None,
+            seed: None,
+            editor: Editor::new(),
+        }
+    }
+
+    pub fn new_specific(points: Vec<Point2d>, rect: Rectangle2D) -> Self {
+        ConvexHullScenario {
+            count: points.len(),
+            rect,
+            points,
+            new_pts: true,
+            trace_path: None,
+            seed: None,
+            editor: Editor::new(),
+        }
+    }
+
+    pub fn from_file(file_name: &str, rect: Rectangle2D) -> Result<Self, ScenarioFileError> {
+        Ok(ConvexHullScenario::new_specific(scenario_serializer::load_points(file_name)?, rect))
+    }
+
+    //Write the hull steps to a trace file instead of the log, the format
+    //follows the extension. The seed is only recorded in the header
+    pub fn with_trace(mut self, path: &str, seed: Option<u64>) -> Self {
+        self.trace_path = Some(path.to_string());
+        self.seed = seed;
+        self
+    }
+
+    fn save_scenario(&self) {
+        let entities: ScenarioEntities = ScenarioEntities {
+            points: self.points.clone(),
+            ..Default::default()
+        };
+        let file: ScenarioFile = ScenarioFile::new("convex-hull", entities)
+            .with_seed(self.seed)
+            .with_bounds(self.rect);
+
+        if let Err(e) = scenario_serializer::save_scenario(&file, SCENARIO_FILE) {
+            debug!("Could not save {}: {}", SCENARIO_FILE, e);
+        }
+    }
+
+    fn load_scenario(&mut self) {
+        match scenario_serializer::load_points(SCENARIO_FILE) {
+            Ok(points) => {
+                self.points = points;
+                self.editor.cancel();
+                self.new_pts = true;
+            }
+            Err(e) => debug!("Could not load {}: {}", SCENARIO_FILE, e),
+        }
+    }
+
+    //Sorts the given points, not self.points, so an index being dragged stays valid
+    fn hull(&self, points: &mut Vec<Point2d>) -> Option<Polygon2D> {
+        let path: &str = match &self.trace_path {
+            Some(p) => p,
+            None => {
+                //steps end up in the log where the replay viewer can step through them
+                let mut logger: SceneLogger = SceneLogger::new("Algorithms", cfg!(feature = "Algorithms"));
+                return convex_hull::convex_hull_traced(points, &mut logger);
+            }
+        };
+
+        let header: TraceHeader = TraceHeader::new("convex_hull", self.seed, points.get_scene());
+
+        let mut writer = match TraceWriter::create_for_path(path, &header) {
+            Ok(w) => w,
+            Err(e) => {
+                debug!("Could not create trace {}: {}", path, e);
+                return convex_hull::convex_hull(points);
+            }
+        };
+
+        let polygon: Option<Polygon2D> = convex_hull::convex_hull_traced(points, &mut writer);
+
+        if let Err(e) = writer.finish() {
+            debug!("Could not write trace {}: {}", path, e);
         }
+
+        polygon
     }
 
     fn random_points(&mut self) {
+        self.editor.cancel();
         self.points.clear();
         self.points
             .extend(Random2D::random_points(self.rect, self.count as i32));
@@ -37,30 +139,66 @@ impl ConvexHullScenario {
     }
 }
 
+const KEY_BINDINGS: [KeyBinding; 6] = [
+    KeyBinding::new("R", "new random points"),
+    KeyBinding::new("Left click", "add a point"),
+    KeyBinding::new("Left drag", "move a point"),
+    KeyBinding::new("Right click", "delete a point"),
+    KeyBinding::new("S", "save the points"),
+    KeyBinding::new("L", "load the saved points"),
+];
+
 impl Scenario for ConvexHullScenario {
     fn initialize(&mut self) -> Result<(), &'static str> {
         if self.count == 0 {
             return Err("Count must be greater than 0");
         }
 
-        self.random_points();
+        if self.points.is_empty() {
+            self.random_points();
+        }
         Ok(())
     }
 
-    fn handle_input(&mut self, window: &minifb::Window) {
-        if window.is_key_pressed(minifb::Key::R, minifb::KeyRepeat::No) {
+    fn handle_input(&mut self, input: &InputFrame) {
+        if input.is_key_pressed(Key::R, KeyRepeat::No) {
             self.random_points();
         }
+
+        if input.is_key_pressed(Key::S, KeyRepeat::No) {
+            self.save_scenario();
+        }
+
+        if input.is_key_pressed(Key::L, KeyRepeat::No) {
+            self.load_scenario();
+        }
+
+        if self.editor.edit_points(input, &mut self.points) {
+            self.new_pts = true;
+        }
     }
 
     fn process(&mut self, camera: &mut crate::display::camera::Camera) {
-        let polygon =
-            convex_hull::convex_hull(&mut self.points).unwrap_or_else(|| panic!("Uhm didnt work"));
+        let mut points: Vec<Point2d> = self.points.clone();
+
+        //edits can leave too few points for a hull
+        if let Some(polygon) = self.hull(&mut points) {
+            camera
+                .layer("hull")
+                .push_filled_polygon(polygon, RGBA::new(40, 60, 110, 160), Some(RGB::red()));
+        }
 
-        camera.push_points(self.points.clone());
-        camera.set_point_size(3);
+        camera
+            .layer("points")
+            .push_points_styled(points.clone(), PointStyle::new(RGB::white(), 3));
 
-        camera.push_polygon(polygon);
+        //points are sorted by the hull, so these match the indexes in the logged scenes
+        camera.layer("labels").push_labels(
+            points
+                .iter()
+                .enumerate()
+                .map(|(i, p)| Label::index(*p, i, RGB::white())),
+        );
     }
 
     fn redraw(&mut self) -> bool {
@@ -71,4 +209,12 @@ impl Scenario for ConvexHullScenario {
 
         return false;
     }
+
+    fn key_bindings(&self) -> Vec<KeyBinding> {
+        KEY_BINDINGS.to_vec()
+    }
+
+    fn input(&self) -> Option<ScenarioInput> {
+        Some(ScenarioInput::Points(self.points.clone()))
+    }
 }
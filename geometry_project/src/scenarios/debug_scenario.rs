@@ -1,6 +1,5 @@
-use crate::display::scenario::Scenario;
-use minifb::Window;
+use crate::display::{input::InputFrame, scenario::Scenario};
 
 pub trait DebugScenario: Scenario {
-    fn handle_debug_input(&mut self, window: &Window);
+    fn handle_debug_input(&mut self, input: &InputFrame);
 }
@@ -0,0 +1,222 @@
+use crate::{
+    algorithms::random_geometry::Random2D,
+    display::{
+        camera::Camera,
+        input::{InputFrame, Key, KeyRepeat},
+        line_style::disk_polygon,
+        rgb::RGB,
+        scenario::{KeyBinding, Scenario, ScenarioInput},
+        style::PointStyle,
+    },
+    entities::{line2d::Line2D, point2d::Point2d, polygon2d::Polygon2D, rectangle2d::Rectangle2D},
+    spatial::kd_tree::{Axis, KdTree},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum QueryMode {
+    Nearest,
+    Radius,
+    Range,
+}
+
+impl QueryMode {
+    fn next(&self) -> QueryMode {
+        match self {
+            QueryMode::Nearest => QueryMode::Radius,
+            QueryMode::Radius => QueryMode::Range,
+            QueryMode::Range => QueryMode::Nearest,
+        }
+    }
+}
+
+const MAX_K: usize = 20;
+const MAX_REACH_STEPS: usize = 20;
+//Radius and half the range size are this fraction of the smaller side of the bounds
+const REACH_STEP: f32 = 0.025;
+
+//The kd-tree of random points with its splitting lines, the query follows the mouse
+pub struct KdTreeScenario {
+    pub count: usize,
+    pub rect: Rectangle2D,
+    tree: KdTree,
+    mode: QueryMode,
+    k: usize,
+    reach_steps: usize,
+    query: Point2d,
+    changed: bool,
+}
+
+const KEY_BINDINGS: [KeyBinding; 4] = [
+    KeyBinding::new("Mouse", "move the query"),
+    KeyBinding::new("M", "k nearest, radius or range query"),
+    KeyBinding::new("Up/Down", "grow or shrink k, the radius or the range"),
+    KeyBinding::new("R", "new random points"),
+];
+
+impl KdTreeScenario {
+    pub fn new(count: usize, rect: Rectangle2D) -> Self {
+        KdTreeScenario {
+            count,
+            rect,
+            tree: KdTree::new(Vec::new()),
+            mode: QueryMode::Nearest,
+            k: 3,
+            reach_steps: 4,
+            query: rect.center(),
+            changed: true,
+        }
+    }
+
+    pub fn new_specific(points: Vec<Point2d>, rect: Rectangle2D) -> Self {
+        let mut scenario: KdTreeScenario = KdTreeScenario::new(points.len(), rect);
+        scenario.tree = KdTree::new(points);
+        scenario
+    }
+
+    fn random_points(&mut self) {
+        self.tree = KdTree::new(Random2D::random_points(self.rect, self.count as i32).into_iter().collect());
+        self.changed = true;
+    }
+
+    fn reach(&self) -> f32 {
+        f32::min(self.rect.width(), self.rect.height()) * REACH_STEP * self.reach_steps as f32
+    }
+
+    fn range(&self) -> Rectangle2D {
+        let reach: f32 = self.reach();
+        Rectangle2D::new(
+            Point2d::new(self.query.x - reach, self.query.y - reach),
+            Point2d::new(self.query.x + reach, self.query.y + reach),
+        )
+    }
+
+    fn grow(&mut self, by: isize) {
+        match self.mode {
+            QueryMode::Nearest => self.k = (self.k as isize + by).clamp(1, MAX_K as isize) as usize,
+            _ => self.reach_steps = (self.reach_steps as isize + by).clamp(1, MAX_REACH_STEPS as isize) as usize,
+        }
+
+        self.changed = true;
+    }
+
+    fn results(&self) -> Vec<Point2d> {
+        match self.mode {
+            QueryMode::Nearest => self.tree.k_nearest(self.query, self.k),
+            QueryMode::Radius => self.tree.within_radius(self.query, self.reach()),
+            QueryMode::Range => self.tree.range(self.range()),
+        }
+    }
+
+    fn outline(&self) -> Option<Polygon2D> {
+        match self.mode {
+            QueryMode::Nearest => None,
+            QueryMode::Radius => Some(disk_polygon(self.query, self.reach())),
+            QueryMode::Range => {
+                let range: Rectangle2D = self.range();
+                Some(Polygon2D::new(vec![
+                    range.min,
+                    Point2d::new(range.max.x, range.min.y),
+                    range.max,
+                    Point2d::new(range.min.x, range.max.y),
+                ]))
+            }
+        }
+    }
+
+    fn status(&self, found: usize) -> String {
+        match self.mode {
+            QueryMode::Nearest => format!("{} nearest of {} points", self.k, self.tree.len()),
+            QueryMode::Radius => format!("{} of {} points within {:.1}", found, self.tree.len(), self.reach()),
+            QueryMode::Range => format!("{} of {} points in the range", found, self.tree.len()),
+        }
+    }
+}
+
+impl Scenario for KdTreeScenario {
+    fn initialize(&mut self) -> Result<(), &'static str> {
+        if self.count == 0 {
+            return Err("Count must be greater than 0");
+        }
+
+        if self.tree.is_empty() {
+            self.random_points();
+        }
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &InputFrame) {
+        if input.is_key_pressed(Key::R, KeyRepeat::No) {
+            self.random_points();
+        }
+
+        if input.is_key_pressed(Key::M, KeyRepeat::No) {
+            self.mode = self.mode.next();
+            self.changed = true;
+        }
+
+        if input.is_key_pressed(Key::Up, KeyRepeat::Yes) {
+            self.grow(1);
+        }
+
+        if input.is_key_pressed(Key::Down, KeyRepeat::Yes) {
+            self.grow(-1);
+        }
+
+        if let Some(mouse) = input.mouse_world() {
+            if mouse != self.query {
+                self.query = mouse;
+                self.changed = true;
+            }
+        }
+    }
+
+    fn process(&mut self, camera: &mut Camera) {
+        //splits of the root span the bounds, points outside them still get a cell
+        let bounds: Rectangle2D = match self.tree.bounds() {
+            Some(b) => Rectangle2D::from_points([b.min, b.max, self.rect.min, self.rect.max]).unwrap_or(b),
+            None => self.rect,
+        };
+
+        camera.layer("splits").push_lines_color(self.tree.splits(bounds).into_iter().map(|(axis, line): (Axis, Line2D)| {
+            match axis {
+                Axis::X => (line, RGB::new(70, 90, 160)),
+                Axis::Y => (line, RGB::new(60, 140, 90)),
+            }
+        }));
+
+        camera
+            .layer("points")
+            .push_points_styled(self.tree.points().to_vec(), PointStyle::new(RGB::white(), 3));
+
+        if let Some(outline) = self.outline() {
+            camera.layer("query").push_polygon(&outline, RGB::new(200, 200, 60));
+        }
+
+        camera
+            .layer("query")
+            .push_points_styled([self.query], PointStyle::new(RGB::new(200, 200, 60), 4));
+
+        let results: Vec<Point2d> = self.results();
+        camera.push_status(self.status(results.len()));
+        camera
+            .layer("results")
+            .push_points_styled(results, PointStyle::new(RGB::red(), 5));
+    }
+
+    fn redraw(&mut self) -> bool {
+        if self.changed {
+            self.changed = false;
+            return true;
+        }
+
+        false
+    }
+
+    fn key_bindings(&self) -> Vec<KeyBinding> {
+        KEY_BINDINGS.to_vec()
+    }
+
+    fn input(&self) -> Option<ScenarioInput> {
+        Some(ScenarioInput::Points(self.tree.points().to_vec()))
+    }
+}
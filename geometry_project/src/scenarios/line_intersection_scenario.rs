@@ -2,12 +2,15 @@ use std::f32;
 
 use crate::{
     algorithms::{self, random_geometry::Random2D},
-    display::{camera::Camera, hsv::HSV, rgb::RGB, scenario::Scenario, scene::Scene},
+    display::{editor::Editor, hsv::HSV, input::{InputFrame, Key, KeyRepeat}, label::Label, rgb::RGB, scenario::{KeyBinding, Scenario, ScenarioInput}, scene::Scene, style::PointStyle},
     entities::{line2d::{Line2D, ParametricLine2D}, point2d::Point2d, rectangle2d::Rectangle2D},
 };
 
-use crate::scenarios::scenario_serializer;
+use crate::formats::scenario_serializer::{self, ScenarioEntities, ScenarioFile, ScenarioFileError};
+use crate::scene_logger::trace_file::{TraceHeader, TraceWriter};
 
+use log::debug;
+#[cfg(feature = "log-statements")]
 use log_statement::def_log;
 use serde::{Deserialize, Serialize};
 
@@ -17,10 +20,17 @@ pub struct LineIntersectionScenario {
     lines: Vec<Line2D>,
     round_points: bool,
     new_pts: bool,
+    trace_path: Option<String>,
+    seed: Option<u64>,
+    editor: Editor,
 }
 
+#[cfg(feature = "log-statements")]
 const LOGGING_ENABLED: bool = true;
+#[cfg(feature = "log-statements")]
 def_log!(LineIntersection, LOGGING_ENABLED);
+#[cfg(not(feature = "log-statements"))]
+use crate::no_log as lineintersection_log;
 
 const SCENARIO_FILE: &'static str = "line_intersection_scenario.txt";
 
@@ -32,6 +42,9 @@ impl LineIntersectionScenario {
             lines: Vec::with_capacity(count),
             round_points: false,
             new_pts: true,
+            trace_path: None,
+            seed: None,
+            editor: Editor::new(),
         }
     }
 
@@ -56,26 +69,71 @@ impl LineIntersectionScenario {
         inst
     }
 
+    //Random and edited end points are on the integer grid
     pub fn round_points(mut self) -> Self {
         self.round_points = true;
+        self.editor = Editor::new().with_snap(true);
         return self;
     }
 
     pub fn new_specific(lines: Vec<Line2D>, rect: Rectangle2D) -> Self {
-        LineIntersectionScenario { count: lines.len(), rect, lines, round_points: false, new_pts: true }
+        LineIntersectionScenario { count: lines.len(), rect, lines, round_points: false, new_pts: true, trace_path: None, seed: None, editor: Editor::new() }
     }
 
-    fn save_scenario(&mut self) {
-        scenario_serializer::save_entities_to_file(&self.lines, &SCENARIO_FILE, false);
+    //Every change of the lines writes the steps of a line sweep over them to
+    //a trace file, the format follows the extension. The seed is only
+    //recorded in the header
+    pub fn with_trace(mut self, path: &str, seed: Option<u64>) -> Self {
+        self.trace_path = Some(path.to_string());
+        self.seed = seed;
+        self
     }
 
-    fn load_scenario(&mut self) {
-        self.lines = match scenario_serializer::deserialize_from_file(&SCENARIO_FILE) {
-            Ok(l) => l,
-            Err(s) => panic!("{}", s),
+    fn trace_sweep(&self, path: &str) {
+        let header: TraceHeader = TraceHeader::new("line_sweep", self.seed, Scene::from(&self.lines));
+
+        let mut writer = match TraceWriter::create_for_path(path, &header) {
+            Ok(w) => w,
+            Err(e) => {
+                debug!("Could not create trace {}: {}", path, e);
+                return;
+            }
         };
 
-        self.new_pts = true;
+        algorithms::line_intersection::line_sweep_intersection_traced(&self.lines, f32::EPSILON, &mut writer);
+
+        if let Err(e) = writer.finish() {
+            debug!("Could not write trace {}: {}", path, e);
+        }
+    }
+
+    fn save_scenario(&mut self) {
+        let entities: ScenarioEntities = ScenarioEntities {
+            lines: self.lines.clone(),
+            ..Default::default()
+        };
+        let file: ScenarioFile = ScenarioFile::new("line-intersection", entities)
+            .with_bounds(self.rect)
+            .with_epsilon(f32::EPSILON);
+
+        if let Err(e) = scenario_serializer::save_scenario(&file, SCENARIO_FILE) {
+            debug!("Could not save {}: {}", SCENARIO_FILE, e);
+        }
+    }
+
+    pub fn from_file(file_name: &str, rect: Rectangle2D) -> Result<Self, ScenarioFileError> {
+        Ok(LineIntersectionScenario::new_specific(scenario_serializer::load_lines(file_name)?, rect))
+    }
+
+    fn load_scenario(&mut self) {
+        match scenario_serializer::load_lines(SCENARIO_FILE) {
+            Ok(lines) => {
+                self.lines = lines;
+                self.editor.cancel();
+                self.new_pts = true;
+            }
+            Err(e) => debug!("Could not load {}: {}", SCENARIO_FILE, e),
+        }
     }
 
     pub fn intersections(&self) -> Vec<(Point2d, Line2D, Line2D)>
@@ -84,6 +142,7 @@ impl LineIntersectionScenario {
     }
 
     fn random_lines(&mut self) {
+        self.editor.cancel();
         self.lines.clear();
 
         if self.round_points
@@ -100,34 +159,52 @@ impl LineIntersectionScenario {
     }
 }
 
+const KEY_BINDINGS: [KeyBinding; 6] = [
+    KeyBinding::new("R", "new random lines"),
+    KeyBinding::new("Left drag", "draw a segment"),
+    KeyBinding::new("Drag an end", "move it"),
+    KeyBinding::new("Right click", "delete a segment"),
+    KeyBinding::new("S", "save the lines"),
+    KeyBinding::new("L", "load the saved lines"),
+];
+
 impl Scenario for LineIntersectionScenario {
     fn initialize(&mut self) -> Result<(), &'static str> {
         if self.count == 0 {
             return Err("Count must be greater than 0");
         }
 
-        self.random_lines();
+        //lines given up front or picked to intersect are kept
+        if self.lines.is_empty() {
+            self.random_lines();
+        }
         Ok(())
     }
 
-    fn handle_input(&mut self, window: &minifb::Window) {
-        if window.is_key_pressed(minifb::Key::R, minifb::KeyRepeat::No) {
+    fn handle_input(&mut self, input: &InputFrame) {
+        if input.is_key_pressed(Key::R, KeyRepeat::No) {
             self.random_lines();
         }
 
-        if window.is_key_pressed(minifb::Key::S, minifb::KeyRepeat::No) {
+        if input.is_key_pressed(Key::S, KeyRepeat::No) {
             self.save_scenario();
         }
 
-        if window.is_key_pressed(minifb::Key::L, minifb::KeyRepeat::No) {
+        if input.is_key_pressed(Key::L, KeyRepeat::No) {
             self.load_scenario();
         }
+
+        if self.editor.edit_segments(input, &mut self.lines) {
+            self.new_pts = true;
+        }
     }
 
     fn process(&mut self, camera: &mut crate::display::camera::Camera) {
         let intersections = self.intersections();
-        
-        camera.set_point_size(4);
+
+        if let Some(path) = &self.trace_path {
+            self.trace_sweep(path);
+        }
 
         //println!("Lines: {:?}", &self.lines);
         println!("Points: {:?}", &intersections.iter().map(|t| -> String 
@@ -137,16 +214,11 @@ impl Scenario for LineIntersectionScenario {
             }).collect::<Vec<String>>());
 
         let colors: Vec<RGB> = HSV::random_colors(intersections.len(), 0.8f32, 0.9f32).iter().map(|h| h.to_rgb()).collect();
-        camera.set_point_colors(colors.clone());
-        let mut index: usize = 0;
-        for (point, seg_a, seg_b) in intersections
+        for ((point, seg_a, seg_b), color) in intersections.into_iter().zip(colors)
         {
-            camera.push_point(point);
-            camera.push_line(seg_a);
-            camera.push_line_color(colors[index]);
-            camera.push_line(seg_b);
-            camera.push_line_color(colors[index]);
-            index += 1;
+            camera.layer("segments").push_lines_color([(seg_a, color), (seg_b, color)]);
+            camera.layer("intersections").push_point_styled(point, PointStyle::new(color, 4));
+            camera.layer("labels").push_label(Label::coordinates(point, color));
         }
 
         //camera.push_points(intersections.iter().map(|f| -> Point2d { return f.0; }));
@@ -163,4 +235,12 @@ impl Scenario for LineIntersectionScenario {
 
         return false;
     }
+
+    fn key_bindings(&self) -> Vec<KeyBinding> {
+        KEY_BINDINGS.to_vec()
+    }
+
+    fn input(&self) -> Option<ScenarioInput> {
+        Some(ScenarioInput::Lines(self.lines.clone()))
+    }
 }
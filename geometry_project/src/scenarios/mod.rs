@@ -1,6 +1,11 @@
 pub mod convex_hull_scenario;
+pub mod kd_tree_scenario;
 pub mod line_intersection_scenario;
+pub mod replay_scenario;
+pub mod registry;
 pub mod right_turn_debug;
+pub mod scenario_menu;
+pub mod scene_scenario;
+pub mod step_debugger;
 
 mod debug_scenario;
-mod scenario_serializer;
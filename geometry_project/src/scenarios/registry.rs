@@ -0,0 +1,246 @@
+use crate::{
+    algorithms::random_geometry::Random2D,
+    display::scenario::{Scenario, ScenarioConfig},
+    entities::{line2d::Line2D, point2d::Point2d, rectangle2d::Rectangle2D},
+    formats::scenario_serializer::{self, ScenarioFileError},
+    scenarios::{
+        convex_hull_scenario::ConvexHullScenario, kd_tree_scenario::KdTreeScenario,
+        line_intersection_scenario::LineIntersectionScenario, right_turn_debug::RightTurnDebug, step_debugger::StepDebugger,
+    },
+};
+
+//Builds a scenario from the config and the number of entities to make
+type BuildScenario = fn(&ScenarioConfig, usize) -> Result<Box<dyn Scenario>, String>;
+
+pub struct ScenarioEntry {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default_count: usize,
+    build: BuildScenario,
+}
+
+impl ScenarioEntry {
+    pub fn build(&self, config: &ScenarioConfig) -> Result<Box<dyn Scenario>, String> {
+        (self.build)(config, config.count.unwrap_or(self.default_count))
+    }
+}
+
+//Names the file, parse errors keep their line and column
+fn input_error(file: &str) -> impl Fn(ScenarioFileError) -> String + '_ {
+    move |e| format!("{}: {}", file, e)
+}
+
+fn line_intersection(config: &ScenarioConfig, count: usize) -> Result<Box<dyn Scenario>, String> {
+    let scenario: LineIntersectionScenario = match &config.input {
+        Some(file) => LineIntersectionScenario::from_file(file, config.bounds).map_err(input_error(file))?,
+        None => {
+            if count < 2 {
+                return Err("line-intersection needs at least 2 lines".to_string());
+            }
+
+            LineIntersectionScenario::new_first_intersection(count, config.bounds).round_points()
+        }
+    };
+
+    match &config.trace {
+        Some(path) => Ok(Box::new(scenario.with_trace(path, config.seed))),
+        None => Ok(Box::new(scenario)),
+    }
+}
+
+fn convex_hull(config: &ScenarioConfig, count: usize) -> Result<Box<dyn Scenario>, String> {
+    let mut scenario: ConvexHullScenario = match &config.input {
+        Some(file) => ConvexHullScenario::from_file(file, config.bounds).map_err(input_error(file))?,
+        None => ConvexHullScenario::new(count, config.bounds),
+    };
+
+    if let Some(path) = &config.trace {
+        scenario = scenario.with_trace(path, config.seed);
+    }
+
+    Ok(Box::new(scenario))
+}
+
+//Nothing is traced, the triples are stepped through by hand
+fn right_turn(config: &ScenarioConfig, count: usize) -> Result<Box<dyn Scenario>, String> {
+    if config.trace.is_some() {
+        return Err("right-turn does not support --trace".to_string());
+    }
+
+    let points: Vec<Point2d> = match &config.input {
+        Some(file) => scenario_serializer::load_points(file).map_err(input_error(file))?,
+        None => Random2D::random_points(config.bounds, count as i32).into_iter().collect(),
+    };
+
+    //fewer points have no triple to step to
+    if points.len() < 3 {
+        return Err("right-turn needs at least 3 points".to_string());
+    }
+
+    Ok(Box::new(RightTurnDebug::new_specific(points)))
+}
+
+fn traced(debugger: StepDebugger, config: &ScenarioConfig) -> StepDebugger {
+    match &config.trace {
+        Some(path) => debugger.with_trace(path, config.seed),
+        None => debugger,
+    }
+}
+
+fn hull_debugger(config: &ScenarioConfig, count: usize) -> Result<Box<dyn Scenario>, String> {
+    let points: Vec<Point2d> = match &config.input {
+        Some(file) => scenario_serializer::load_points(file).map_err(input_error(file))?,
+        None => Random2D::random_points(config.bounds, count as i32).into_iter().collect(),
+    };
+
+    Ok(Box::new(traced(StepDebugger::convex_hull(points).with_breakpoints(&["upper", "lower"]), config)))
+}
+
+fn sweep_debugger(config: &ScenarioConfig, count: usize) -> Result<Box<dyn Scenario>, String> {
+    let lines: Vec<Line2D> = match &config.input {
+        Some(file) => scenario_serializer::load_lines(file).map_err(input_error(file))?,
+        None => Random2D::random_lines_int(config.bounds, count as i32).into_iter().collect(),
+    };
+
+    Ok(Box::new(traced(StepDebugger::line_sweep(lines).with_breakpoints(&["intersection"]), config)))
+}
+
+//Segments clipped against the middle of the bounds
+fn clip_debugger(config: &ScenarioConfig, count: usize) -> Result<Box<dyn Scenario>, String> {
+    let bounds: Rectangle2D = config.bounds;
+    let window: Rectangle2D = bounds.expand(-f32::min(bounds.width(), bounds.height()) / 4f32);
+    let lines: Vec<Line2D> = match &config.input {
+        Some(file) => scenario_serializer::load_lines(file).map_err(input_error(file))?,
+        None => Random2D::random_lines(bounds, count as i32).into_iter().collect(),
+    };
+
+    Ok(Box::new(traced(StepDebugger::clip(window, lines).with_breakpoints(&["clip"]), config)))
+}
+
+fn kd_tree(config: &ScenarioConfig, count: usize) -> Result<Box<dyn Scenario>, String> {
+    if config.trace.is_some() {
+        return Err("kd-tree does not support --trace".to_string());
+    }
+
+    let scenario: KdTreeScenario = match &config.input {
+        Some(file) => {
+            let points: Vec<Point2d> = scenario_serializer::load_points(file).map_err(input_error(file))?;
+            KdTreeScenario::new_specific(points, config.bounds)
+        }
+        None => KdTreeScenario::new(count, config.bounds),
+    };
+
+    Ok(Box::new(scenario))
+}
+
+pub static SCENARIOS: [ScenarioEntry; 7] = [
+    ScenarioEntry {
+        name: "line-intersection",
+        description: "random segments with at least one intersection, S/L save and load",
+        default_count: 2,
+        build: line_intersection,
+    },
+    ScenarioEntry {
+        name: "convex-hull",
+        description: "convex hull of random points, R for new points",
+        default_count: 10,
+        build: convex_hull,
+    },
+    ScenarioEntry {
+        name: "right-turn",
+        description: "steps through point triples checking right turns",
+        default_count: 6,
+        build: right_turn,
+    },
+    ScenarioEntry {
+        name: "hull-debugger",
+        description: "steps through the convex hull, P plays, K sets breakpoints",
+        default_count: 10,
+        build: hull_debugger,
+    },
+    ScenarioEntry {
+        name: "sweep-debugger",
+        description: "steps through the line sweep with its queue and status",
+        default_count: 5,
+        build: sweep_debugger,
+    },
+    ScenarioEntry {
+        name: "clip-debugger",
+        description: "steps through Liang-Barsky clipping of random segments",
+        default_count: 3,
+        build: clip_debugger,
+    },
+    ScenarioEntry {
+        name: "kd-tree",
+        description: "kd-tree splits with k nearest, radius and range queries at the mouse, M switches",
+        default_count: 40,
+        build: kd_tree,
+    },
+];
+
+pub fn index_of(name: &str) -> Option<usize> {
+    SCENARIOS.iter().position(|s| s.name == name)
+}
+
+//One line per scenario for --list
+pub fn describe() -> String {
+    SCENARIOS
+        .iter()
+        .map(|s| format!("{:<20} {} (default count {})", s.name, s.description, s.default_count))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::scenario::DEFAULT_SCENARIO;
+
+    #[test]
+    fn test_names_are_unique() {
+        for (i, a) in SCENARIOS.iter().enumerate() {
+            assert!(SCENARIOS.iter().skip(i + 1).all(|b| b.name != a.name), "{} is listed twice", a.name);
+        }
+    }
+
+    #[test]
+    fn test_index_of() {
+        assert!(index_of(DEFAULT_SCENARIO).is_some());
+        assert_eq!(Some(1), index_of("convex-hull"));
+        assert!(index_of("nope").is_none());
+    }
+
+    #[test]
+    fn test_unsupported_trace_is_an_error() {
+        let config: ScenarioConfig = ScenarioConfig {
+            count: None,
+            bounds: Rectangle2D::new_width_height(10f32, 10f32),
+            seed: None,
+            input: None,
+            trace: Some("steps.ndjson".to_string()),
+        };
+
+        let built = SCENARIOS[index_of("right-turn").unwrap()].build(&config);
+        assert_eq!(Some("right-turn does not support --trace".to_string()), built.err());
+    }
+
+    #[test]
+    fn test_bad_input_keeps_line_and_column() {
+        let path: String = std::env::temp_dir().join("registry_bad_input.wkt").to_string_lossy().to_string();
+        std::fs::write(&path, "POINT (1 2)\nPOINT (3 x)\n").unwrap();
+
+        let config: ScenarioConfig = ScenarioConfig {
+            count: None,
+            bounds: Rectangle2D::new_width_height(10f32, 10f32),
+            seed: None,
+            input: Some(path.clone()),
+            trace: None,
+        };
+
+        let error: String = SCENARIOS[index_of("convex-hull").unwrap()].build(&config).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(error.starts_with(&path));
+        assert!(error.contains("line 2, column"), "{}", error);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    display::{
+        camera::Camera,
+        input::{InputFrame, Key, KeyRepeat},
+        scenario::{KeyBinding, Scenario},
+    },
+    scene_logger::scene_log_reader::{group_by_target, read_scenes, read_trace, LoggedScene, SceneGroup},
+};
+
+//Steps through the scenes SceneLogger wrote to a log file, or the steps of a
+//trace file. Left and right move one scene back and forward, up and down
+//switch between logging targets
+pub struct ReplayScenario {
+    groups: Vec<SceneGroup>,
+    group: usize,
+    step: usize,
+    redraw: bool,
+}
+
+impl ReplayScenario {
+    pub fn new(scenes: Vec<LoggedScene>) -> Self {
+        ReplayScenario {
+            groups: group_by_target(scenes),
+            group: 0,
+            step: 0,
+            redraw: true,
+        }
+    }
+
+    //The log has to be read before the logger is initialized, it truncates the file.
+    //Trace files are recognised by their extension
+    pub fn from_file(path: &str) -> Result<Self, &'static str> {
+        Ok(ReplayScenario::new(read_scenes(path)?))
+    }
+
+    //The traced input is shown as the first step
+    pub fn from_trace(path: &str) -> Result<Self, &'static str> {
+        Ok(ReplayScenario::new(read_trace(path)?))
+    }
+
+    fn current(&self) -> Option<&LoggedScene> {
+        self.groups.get(self.group)?.scenes.get(self.step)
+    }
+
+    fn step_by(&mut self, forward: bool) {
+        let len: usize = match self.groups.get(self.group) {
+            Some(g) => g.scenes.len(),
+            None => {
+                return;
+            }
+        };
+
+        let next: usize = match forward {
+            true => usize::min(self.step + 1, len - 1),
+            false => self.step.saturating_sub(1),
+        };
+
+        self.redraw = self.redraw || next != self.step;
+        self.step = next;
+    }
+
+    fn switch_group(&mut self, forward: bool) {
+        if self.groups.len() < 2 {
+            return;
+        }
+
+        self.group = match forward {
+            true => (self.group + 1) % self.groups.len(),
+            false => (self.group + self.groups.len() - 1) % self.groups.len(),
+        };
+        self.step = 0;
+        self.redraw = true;
+    }
+}
+
+const KEY_BINDINGS: [KeyBinding; 2] = [
+    KeyBinding::new("Left / Right", "previous / next step"),
+    KeyBinding::new("Up / Down", "previous / next target"),
+];
+
+impl Scenario for ReplayScenario {
+    fn initialize(&mut self) -> Result<(), &'static str> {
+        if self.groups.is_empty() {
+            return Err("No scenes found in the log");
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &InputFrame) {
+        if input.is_key_pressed(Key::Right, KeyRepeat::Yes) {
+            self.step_by(true);
+        } else if input.is_key_pressed(Key::Left, KeyRepeat::Yes) {
+            self.step_by(false);
+        }
+
+        if input.is_key_pressed(Key::Up, KeyRepeat::No) {
+            self.switch_group(true);
+        } else if input.is_key_pressed(Key::Down, KeyRepeat::No) {
+            self.switch_group(false);
+        }
+    }
+
+    fn process(&mut self, camera: &mut Camera) {
+        self.redraw = false;
+
+        let group: &SceneGroup = &self.groups[self.group];
+        let logged: &LoggedScene = match self.current() {
+            Some(s) => s,
+            None => {
+                return;
+            }
+        };
+
+        camera.push_scene(logged.scene.clone());
+        camera.push_status(format!(
+            "{} ({}/{}) step {}/{}",
+            group.target,
+            self.group + 1,
+            self.groups.len(),
+            self.step + 1,
+            group.scenes.len()
+        ));
+        camera.push_status(logged.location());
+    }
+
+    fn redraw(&mut self) -> bool {
+        self.redraw
+    }
+
+    fn key_bindings(&self) -> Vec<KeyBinding> {
+        KEY_BINDINGS.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::scene::Scene;
+
+    fn logged(target: &str, line: u32) -> LoggedScene {
+        LoggedScene {
+            target: target.to_string(),
+            file: "test.rs".to_string(),
+            line,
+            step: None,
+            scene: Scene::new(),
+        }
+    }
+
+    #[test]
+    fn test_steps_are_clamped() {
+        let mut replay: ReplayScenario = ReplayScenario::new(vec![logged("a", 1), logged("a", 2)]);
+
+        replay.step_by(false);
+        assert_eq!(1, replay.current().unwrap().line);
+
+        replay.step_by(true);
+        replay.step_by(true);
+        assert_eq!(2, replay.current().unwrap().line);
+    }
+
+    #[test]
+    fn test_switching_target_restarts() {
+        let mut replay: ReplayScenario =
+            ReplayScenario::new(vec![logged("a", 1), logged("a", 2), logged("b", 3)]);
+
+        replay.step_by(true);
+        replay.switch_group(true);
+        assert_eq!(3, replay.current().unwrap().line);
+
+        replay.switch_group(true);
+        assert_eq!(1, replay.current().unwrap().line);
+    }
+}
@@ -1,8 +1,16 @@
 use crate::{
     algorithms::{self, mixed_increment::MixedIncremenet, random_geometry::Random2D},
-    display::{rgb::RGB, scenario::Scenario},
+    display::{
+        input::{InputFrame, Key, KeyRepeat},
+        label::{Label, LegendEntry},
+        line_style::LineStyle,
+        rgb::RGB,
+        scenario::{KeyBinding, Scenario, ScenarioInput},
+        scene::Layer,
+    },
     entities::{line2d::Line2D, point2d::Point2d, rectangle2d::Rectangle2D},
 };
+#[cfg(feature = "log-statements")]
 use log_statement::def_log;
 
 pub struct RightTurnDebug {
@@ -13,8 +21,12 @@ pub struct RightTurnDebug {
     redraw: bool,
 }
 
+#[cfg(feature = "log-statements")]
 const LOGGING_ENABLED: bool = false;
+#[cfg(feature = "log-statements")]
 def_log!(RightTurnDebug, LOGGING_ENABLED);
+#[cfg(not(feature = "log-statements"))]
+use crate::no_log as rightturndebug_log;
 
 impl RightTurnDebug {
     pub fn new(count: usize, rect: Rectangle2D) -> Self {
@@ -22,6 +34,12 @@ impl RightTurnDebug {
             .into_iter()
             .collect();
 
+        RightTurnDebug::new_specific(points)
+    }
+
+    pub fn new_specific(points: Vec<Point2d>) -> Self {
+        let count: usize = points.len();
+
         let mut debug = RightTurnDebug {
             count,
             points,
@@ -70,19 +88,24 @@ impl RightTurnDebug {
     }
 }
 
+const KEY_BINDINGS: [KeyBinding; 2] = [
+    KeyBinding::new("Left / Right", "previous / next point triple"),
+    KeyBinding::new("Enter", "log the current triple"),
+];
+
 impl Scenario for RightTurnDebug {
     fn initialize(&mut self) -> Result<(), &'static str> {
         Ok(())
     }
 
-    fn handle_input(&mut self, window: &minifb::Window) {
-        if window.is_key_pressed(minifb::Key::Right, minifb::KeyRepeat::No) {
+    fn handle_input(&mut self, input: &InputFrame) {
+        if input.is_key_pressed(Key::Right, KeyRepeat::No) {
             self.increment();
             self.redraw = true;
-        } else if window.is_key_pressed(minifb::Key::Left, minifb::KeyRepeat::No) {
+        } else if input.is_key_pressed(Key::Left, KeyRepeat::No) {
             self.decrement();
             self.redraw = true;
-        } else if window.is_key_pressed(minifb::Key::Enter, minifb::KeyRepeat::No) {
+        } else if input.is_key_pressed(Key::Enter, KeyRepeat::No) {
             rightturndebug_log!(
                 "a: {}, b: {}, c: {}",
                 self.points[self.indexes[0]],
@@ -93,15 +116,12 @@ impl Scenario for RightTurnDebug {
     }
 
     fn process(&mut self, camera: &mut crate::display::camera::Camera) {
-        camera.push_points(self.points.clone());
+        camera.layer("points").push_points(self.points.clone());
 
         let a: Point2d = self.points[self.indexes[0]];
         let b: Point2d = self.points[self.indexes[1]];
         let c: Point2d = self.points[self.indexes[2]];
 
-        camera.push_line(Line2D::new(a, b));
-        camera.push_line(Line2D::new(b, c));
-
         let right_turn: bool = algorithms::convex_hull::right_turn(a, b, c);
 
         rightturndebug_log!(
@@ -117,12 +137,29 @@ impl Scenario for RightTurnDebug {
             false => RGB::red(),
         };
 
-        camera.push_line_color(color);
-        camera.push_line_color(color);
+        //arrows show the order the turn is evaluated in
+        let style: LineStyle = LineStyle::solid().with_width(2f32).anti_aliased().with_arrow_head();
+        camera.layer("turn").push_line_styled(Line2D::new(a, b), color, style);
+        camera.layer("turn").push_line_styled(Line2D::new(b, c), color, style);
+
+        let labels: &mut Layer = camera.layer("labels");
+        labels.push_label(Label::new(a, format!("a {}", self.indexes[0]), RGB::white()));
+        labels.push_label(Label::new(b, format!("b {}", self.indexes[1]), RGB::white()));
+        labels.push_label(Label::new(c, format!("c {}", self.indexes[2]), RGB::white()));
+        camera.push_legend_entry(LegendEntry::new(RGB::green(), "right turn"));
+        camera.push_legend_entry(LegendEntry::new(RGB::red(), "left turn"));
         self.redraw = false;
     }
 
     fn redraw(&mut self) -> bool {
         self.redraw
     }
+
+    fn key_bindings(&self) -> Vec<KeyBinding> {
+        KEY_BINDINGS.to_vec()
+    }
+
+    fn input(&self) -> Option<ScenarioInput> {
+        Some(ScenarioInput::Points(self.points.clone()))
+    }
 }
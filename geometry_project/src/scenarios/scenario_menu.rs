@@ -0,0 +1,247 @@
+use crate::{
+    display::{
+        camera::Camera,
+        input::{InputFrame, Key, KeyRepeat},
+        scenario::{KeyBinding, Scenario, ScenarioConfig, ScenarioInput},
+    },
+    scenarios::registry::ScenarioEntry,
+};
+
+const SWITCH_KEYS: [Key; 9] = [
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+];
+
+const KEY_BINDINGS: [KeyBinding; 2] = [
+    KeyBinding::new("Tab", "next scenario"),
+    KeyBinding::new("F1 - F9", "switch to a scenario"),
+];
+
+//Holds every registered scenario and forwards to the active one. Scenarios
+//are built the first time they are shown and kept, so switching back returns
+//to where it was left. Number keys already toggle layers, so F keys pick one
+pub struct ScenarioMenu {
+    entries: &'static [ScenarioEntry],
+    scenarios: Vec<Option<Box<dyn Scenario>>>,
+    config: ScenarioConfig,
+    start: usize,
+    active: usize,
+    switched: bool,
+    error: Option<String>,
+}
+
+impl ScenarioMenu {
+    //config is for the starting scenario, the others get their defaults
+    pub fn new(entries: &'static [ScenarioEntry], active: usize, config: ScenarioConfig) -> Self {
+        ScenarioMenu {
+            entries,
+            scenarios: entries.iter().map(|_| None).collect(),
+            config,
+            start: active,
+            active,
+            switched: true,
+            error: None,
+        }
+    }
+
+    pub fn active_name(&self) -> &'static str {
+        self.entries[self.active].name
+    }
+
+    fn config_for(&self, index: usize) -> ScenarioConfig {
+        match index == self.start {
+            true => self.config.clone(),
+            false => ScenarioConfig {
+                count: None,
+                input: None,
+                trace: None,
+                ..self.config.clone()
+            },
+        }
+    }
+
+    //Builds the starting scenario. Unlike initialize the error keeps what went
+    //wrong, such as the line and column of a bad --input file
+    pub fn start(&mut self) -> Result<(), String> {
+        if self.active >= self.entries.len() {
+            return Err("No scenario at that index".to_string());
+        }
+
+        self.ensure_built(self.active)
+    }
+
+    fn ensure_built(&mut self, index: usize) -> Result<(), String> {
+        if self.scenarios[index].is_some() {
+            return Ok(());
+        }
+
+        let mut scenario: Box<dyn Scenario> = self.entries[index].build(&self.config_for(index))?;
+        scenario.initialize()?;
+        self.scenarios[index] = Some(scenario);
+
+        Ok(())
+    }
+
+    //Returns true when the active scenario changed, a scenario that fails to
+    //build leaves the current one active
+    pub fn select(&mut self, index: usize) -> bool {
+        if index >= self.entries.len() || index == self.active {
+            return false;
+        }
+
+        match self.ensure_built(index) {
+            Ok(_) => {
+                self.active = index;
+                self.error = None;
+            }
+            Err(e) => {
+                self.error = Some(e);
+            }
+        }
+
+        self.switched = true;
+        self.error.is_none()
+    }
+
+    pub fn select_next(&mut self) -> bool {
+        self.select((self.active + 1) % self.entries.len())
+    }
+
+    fn active_scenario(&mut self) -> &mut Box<dyn Scenario> {
+        self.scenarios[self.active]
+            .as_mut()
+            .expect("active scenario is built by initialize and select")
+    }
+}
+
+impl Scenario for ScenarioMenu {
+    fn initialize(&mut self) -> Result<(), &'static str> {
+        self.start().map_err(|_| "The starting scenario failed to build")
+    }
+
+    fn handle_input(&mut self, input: &InputFrame) {
+        if input.is_key_pressed(Key::Tab, KeyRepeat::No) {
+            self.select_next();
+        }
+
+        for (index, key) in SWITCH_KEYS.iter().enumerate() {
+            if input.is_key_pressed(*key, KeyRepeat::No) {
+                self.select(index);
+            }
+        }
+
+        self.active_scenario().handle_input(input);
+    }
+
+    fn process(&mut self, camera: &mut Camera) {
+        self.active_scenario().process(camera);
+
+        camera.push_status(format!(
+            "F{} {} of {}, Tab for the next",
+            self.active + 1,
+            self.active_name(),
+            self.entries.len()
+        ));
+
+        if let Some(e) = &self.error {
+            camera.push_status(e.clone());
+        }
+    }
+
+    //The active scenario is always asked so it can reset its own flag
+    fn redraw(&mut self) -> bool {
+        let scenario_redraw: bool = self.active_scenario().redraw();
+        let switched: bool = std::mem::take(&mut self.switched);
+
+        scenario_redraw || switched
+    }
+
+    fn key_bindings(&self) -> Vec<KeyBinding> {
+        let mut bindings: Vec<KeyBinding> = KEY_BINDINGS.to_vec();
+
+        if let Some(scenario) = &self.scenarios[self.active] {
+            bindings.extend(scenario.key_bindings());
+        }
+
+        bindings
+    }
+
+    fn input(&self) -> Option<ScenarioInput> {
+        self.scenarios[self.active].as_ref()?.input()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::{point2d::Point2d, rectangle2d::Rectangle2D},
+        scenarios::registry::SCENARIOS,
+    };
+
+    fn menu() -> ScenarioMenu {
+        let config: ScenarioConfig = ScenarioConfig {
+            count: Some(4),
+            bounds: Rectangle2D::new(Point2d::new(0f32, 0f32), Point2d::new(10f32, 10f32)),
+            seed: None,
+            input: None,
+            trace: None,
+        };
+
+        let mut menu: ScenarioMenu = ScenarioMenu::new(&SCENARIOS, 0, config);
+        menu.initialize().unwrap();
+        menu
+    }
+
+    #[test]
+    fn test_scenarios_are_built_when_shown() {
+        let mut menu: ScenarioMenu = menu();
+
+        assert!(menu.scenarios[0].is_some());
+        assert!(menu.scenarios[1].is_none());
+
+        assert!(menu.select(1));
+        assert_eq!(SCENARIOS[1].name, menu.active_name());
+        assert!(menu.scenarios[1].is_some());
+    }
+
+    #[test]
+    fn test_switching_back_keeps_state() {
+        let mut menu: ScenarioMenu = menu();
+        let first: *const dyn Scenario = menu.scenarios[0].as_deref().unwrap();
+
+        menu.select_next();
+        menu.select(0);
+
+        assert!(std::ptr::addr_eq(first, menu.scenarios[0].as_deref().unwrap() as *const dyn Scenario));
+    }
+
+    #[test]
+    fn test_switch_requests_redraw() {
+        let mut menu: ScenarioMenu = menu();
+
+        while menu.redraw() {}
+
+        assert!(!menu.select(0));
+        assert!(menu.select(2));
+        assert!(menu.redraw());
+    }
+
+    #[test]
+    fn test_key_bindings_include_active_scenario() {
+        let mut menu: ScenarioMenu = menu();
+        menu.select(1);
+
+        let keys: Vec<&str> = menu.key_bindings().iter().map(|b| b.key).collect();
+
+        assert!(keys.contains(&"Tab"));
+        assert!(keys.contains(&"R"));
+    }
+}
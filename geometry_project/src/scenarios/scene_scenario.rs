@@ -0,0 +1,40 @@
+use crate::display::{camera::Camera, input::InputFrame, scenario::Scenario, scene::Scene};
+
+//Shows a fixed scene, status lines are drawn under the HUD
+pub struct SceneScenario {
+    scene: Scene,
+    status: Vec<String>,
+    redraw: bool,
+}
+
+impl SceneScenario {
+    pub fn new(scene: Scene, status: Vec<String>) -> Self {
+        SceneScenario {
+            scene,
+            status,
+            redraw: true,
+        }
+    }
+}
+
+impl Scenario for SceneScenario {
+    fn initialize(&mut self) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn handle_input(&mut self, _input: &InputFrame) {}
+
+    fn process(&mut self, camera: &mut Camera) {
+        camera.push_scene(self.scene.clone());
+
+        for line in &self.status {
+            camera.push_status(line.clone());
+        }
+
+        self.redraw = false;
+    }
+
+    fn redraw(&mut self) -> bool {
+        self.redraw
+    }
+}
@@ -0,0 +1,453 @@
+use crate::{
+    algorithms::{
+        convex_hull, line_intersection,
+        tracer::{SceneRecorder, TraceStep},
+    },
+    display::{
+        camera::Camera,
+        input::{InputFrame, Key, KeyRepeat},
+        scenario::{KeyBinding, Scenario, ScenarioInput},
+        scene::Scene,
+    },
+    entities::{algorithms::liang_barsky_clip_traced, line2d::Line2D, point2d::Point2d, rectangle2d::Rectangle2D},
+    scenarios::debug_scenario::DebugScenario,
+    scene_logger::trace_file::{TraceHeader, TraceWriter},
+};
+use log::debug;
+
+//Frames between steps while playing, the window loop runs a frame about every 5ms
+const PLAY_INTERVAL: u32 = 20;
+
+const KEY_BINDINGS: [KeyBinding; 7] = [
+    KeyBinding::new("P", "play / pause"),
+    KeyBinding::new("Right", "single step"),
+    KeyBinding::new("Left", "step back"),
+    KeyBinding::new("Page Down", "run to the next breakpoint"),
+    KeyBinding::new("Page Up", "back to the previous breakpoint"),
+    KeyBinding::new("K", "toggle a breakpoint on this step's label"),
+    KeyBinding::new("R", "run the algorithm again"),
+];
+
+//Runs a traced algorithm into a recorder and then lets you move through the
+//recorded steps. Everything is recorded up front, so stepping back is as cheap
+//as stepping forward. Breakpoints are step labels, so "pop" stops at every pop
+pub struct StepDebugger {
+    name: String,
+    run: Box<dyn FnMut(&mut SceneRecorder)>,
+    input: Option<ScenarioInput>,
+    steps: Vec<TraceStep>,
+    position: usize,
+    breakpoints: Vec<String>,
+    playing: bool,
+    frames: u32,
+    redraw: bool,
+    trace_path: Option<String>,
+    seed: Option<u64>,
+}
+
+impl StepDebugger {
+    pub fn new<F>(name: &str, run: F) -> Self
+    where
+        F: FnMut(&mut SceneRecorder) + 'static,
+    {
+        StepDebugger {
+            name: name.to_string(),
+            run: Box::new(run),
+            input: None,
+            steps: Vec::new(),
+            position: 0,
+            breakpoints: Vec::new(),
+            playing: false,
+            frames: 0,
+            redraw: true,
+            trace_path: None,
+            seed: None,
+        }
+    }
+
+    pub fn convex_hull(points: Vec<Point2d>) -> Self {
+        let input: ScenarioInput = ScenarioInput::Points(points.clone());
+
+        StepDebugger::new("convex hull", move |recorder| {
+            let mut points: Vec<Point2d> = points.clone();
+            convex_hull::convex_hull_traced(&mut points, recorder);
+        })
+        .with_input(input)
+    }
+
+    pub fn line_sweep(lines: Vec<Line2D>) -> Self {
+        let input: ScenarioInput = ScenarioInput::Lines(lines.clone());
+
+        StepDebugger::new("line sweep", move |recorder| {
+            line_intersection::line_sweep_intersection_traced(&lines, f32::EPSILON, recorder);
+        })
+        .with_input(input)
+    }
+
+    //Only the segments are saved as input, the clip window follows from the bounds
+    pub fn clip(rect: Rectangle2D, lines: Vec<Line2D>) -> Self {
+        let input: ScenarioInput = ScenarioInput::Lines(lines.clone());
+
+        StepDebugger::new("liang barsky clip", move |recorder| {
+            for line in &lines {
+                liang_barsky_clip_traced(rect, *line, recorder);
+            }
+        })
+        .with_input(input)
+    }
+
+    //What the recorded algorithm runs on, for saving it from the viewer
+    pub fn with_input(mut self, input: ScenarioInput) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    pub fn with_breakpoints(mut self, labels: &[&str]) -> Self {
+        self.breakpoints = labels.iter().map(|l| l.to_string()).collect();
+        self
+    }
+
+    //Every recording is also written to a trace file, the format follows the
+    //extension. The seed is only recorded in the header
+    pub fn with_trace(mut self, path: &str, seed: Option<u64>) -> Self {
+        self.trace_path = Some(path.to_string());
+        self.seed = seed;
+        self
+    }
+
+    fn record(&mut self) {
+        let mut recorder: SceneRecorder = SceneRecorder::new();
+        (self.run)(&mut recorder);
+
+        self.steps = recorder.steps;
+        self.position = 0;
+        self.playing = false;
+        self.redraw = true;
+
+        if let Some(path) = &self.trace_path {
+            self.write_trace(path);
+        }
+    }
+
+    fn write_trace(&self, path: &str) {
+        let mut input: Scene = Scene::new();
+        match &self.input {
+            Some(ScenarioInput::Points(points)) => input.push_points(points.iter().copied()),
+            Some(ScenarioInput::Lines(lines)) => input.push_lines(lines.iter().copied()),
+            None => {}
+        }
+
+        let header: TraceHeader = TraceHeader::new(&self.name.replace(' ', "_"), self.seed, input);
+
+        let result = TraceWriter::create_for_path(path, &header).and_then(|mut writer| {
+            for step in &self.steps {
+                writer.write_step(step)?;
+            }
+
+            writer.finish()
+        });
+
+        if let Err(e) = result {
+            debug!("Could not write trace {}: {}", path, e);
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn current(&self) -> Option<&TraceStep> {
+        self.steps.get(self.position)
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    fn is_breakpoint(&self, index: usize) -> bool {
+        self.breakpoints.iter().any(|b| *b == self.steps[index].label)
+    }
+
+    fn move_to(&mut self, index: usize) {
+        self.redraw = self.redraw || index != self.position;
+        self.position = index;
+    }
+
+    pub fn step_forward(&mut self) {
+        if self.position + 1 < self.steps.len() {
+            self.move_to(self.position + 1);
+        }
+    }
+
+    pub fn step_back(&mut self) {
+        self.move_to(self.position.saturating_sub(1));
+    }
+
+    //Stays put when there is no breakpoint ahead
+    pub fn run_to_breakpoint(&mut self) {
+        let next: Option<usize> = (self.position + 1..self.steps.len()).find(|i| self.is_breakpoint(*i));
+
+        if let Some(i) = next {
+            self.move_to(i);
+        }
+    }
+
+    //Rewinds to the start when there is no breakpoint behind
+    pub fn rewind_to_breakpoint(&mut self) {
+        let previous: usize = (0..self.position).rev().find(|i| self.is_breakpoint(*i)).unwrap_or(0);
+
+        self.move_to(previous);
+    }
+
+    pub fn toggle_breakpoint(&mut self) {
+        let label: String = match self.current() {
+            Some(step) => step.label.clone(),
+            None => {
+                return;
+            }
+        };
+
+        match self.breakpoints.iter().position(|b| *b == label) {
+            Some(i) => {
+                self.breakpoints.remove(i);
+            }
+            None => self.breakpoints.push(label),
+        }
+
+        self.redraw = true;
+    }
+
+    pub fn toggle_play(&mut self) {
+        //playing from the last step starts over
+        if !self.playing && self.position + 1 >= self.steps.len() {
+            self.move_to(0);
+        }
+
+        self.playing = !self.playing;
+        self.frames = 0;
+        self.redraw = true;
+    }
+
+    //One frame of playback, pauses at the end and on breakpoints
+    fn tick(&mut self) {
+        if !self.playing {
+            return;
+        }
+
+        self.frames += 1;
+        if self.frames < PLAY_INTERVAL {
+            return;
+        }
+
+        self.frames = 0;
+        self.step_forward();
+
+        if self.position + 1 >= self.steps.len() || self.is_breakpoint(self.position) {
+            self.playing = false;
+            self.redraw = true;
+        }
+    }
+}
+
+impl DebugScenario for StepDebugger {
+    fn handle_debug_input(&mut self, input: &InputFrame) {
+        if input.is_key_pressed(Key::P, KeyRepeat::No) {
+            self.toggle_play();
+        }
+
+        if input.is_key_pressed(Key::Right, KeyRepeat::Yes) {
+            self.playing = false;
+            self.step_forward();
+        } else if input.is_key_pressed(Key::Left, KeyRepeat::Yes) {
+            self.playing = false;
+            self.step_back();
+        }
+
+        if input.is_key_pressed(Key::PageDown, KeyRepeat::No) {
+            self.playing = false;
+            self.run_to_breakpoint();
+        } else if input.is_key_pressed(Key::PageUp, KeyRepeat::No) {
+            self.playing = false;
+            self.rewind_to_breakpoint();
+        }
+
+        if input.is_key_pressed(Key::K, KeyRepeat::No) {
+            self.toggle_breakpoint();
+        }
+
+        if input.is_key_pressed(Key::R, KeyRepeat::No) {
+            self.record();
+        }
+    }
+}
+
+impl Scenario for StepDebugger {
+    fn initialize(&mut self) -> Result<(), &'static str> {
+        self.record();
+
+        if self.steps.is_empty() {
+            return Err("The algorithm recorded no steps");
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &InputFrame) {
+        self.handle_debug_input(input);
+        self.tick();
+    }
+
+    fn process(&mut self, camera: &mut Camera) {
+        self.redraw = false;
+
+        let step: &TraceStep = match self.current() {
+            Some(s) => s,
+            None => {
+                camera.push_status(format!("{}: no steps", self.name));
+                return;
+            }
+        };
+
+        let scene: Scene = step.scene.clone();
+        let marker: &str = match self.is_breakpoint(self.position) {
+            true => " *",
+            false => "",
+        };
+
+        camera.push_status(format!(
+            "{} step {}/{} {}{}",
+            self.name,
+            self.position + 1,
+            self.steps.len(),
+            step.label,
+            marker
+        ));
+        camera.push_status(format!("{}:{}", step.file, step.line));
+        camera.push_status(format!(
+            "{}, breakpoints: {}",
+            match self.playing {
+                true => "playing",
+                false => "paused",
+            },
+            match self.breakpoints.is_empty() {
+                true => "none".to_string(),
+                false => self.breakpoints.join(", "),
+            }
+        ));
+
+        //the step's notes show up in the side panel
+        camera.push_scene(scene);
+    }
+
+    fn redraw(&mut self) -> bool {
+        self.redraw
+    }
+
+    fn key_bindings(&self) -> Vec<KeyBinding> {
+        KEY_BINDINGS.to_vec()
+    }
+
+    fn input(&self) -> Option<ScenarioInput> {
+        self.input.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::tracer::Tracer;
+
+    fn labelled(labels: &[&'static str]) -> StepDebugger {
+        let labels: Vec<&'static str> = labels.to_vec();
+
+        let mut debugger: StepDebugger = StepDebugger::new("test", move |recorder| {
+            for label in &labels {
+                recorder.trace(label, Scene::new);
+            }
+        });
+
+        debugger.initialize().unwrap();
+        debugger
+    }
+
+    fn label(debugger: &StepDebugger) -> &str {
+        &debugger.current().unwrap().label
+    }
+
+    #[test]
+    fn test_step_and_rewind() {
+        let mut debugger: StepDebugger = labelled(&["a", "b", "c"]);
+
+        debugger.step_back();
+        assert_eq!("a", label(&debugger));
+
+        debugger.step_forward();
+        debugger.step_forward();
+        debugger.step_forward();
+        assert_eq!("c", label(&debugger));
+
+        debugger.step_back();
+        assert_eq!("b", label(&debugger));
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let mut debugger: StepDebugger = labelled(&["push", "push", "pop", "push", "pop", "upper"]).with_breakpoints(&["pop"]);
+
+        debugger.run_to_breakpoint();
+        assert_eq!(2, debugger.position());
+
+        debugger.run_to_breakpoint();
+        assert_eq!(4, debugger.position());
+
+        //nothing ahead, stays on the last one
+        debugger.run_to_breakpoint();
+        assert_eq!(4, debugger.position());
+
+        debugger.rewind_to_breakpoint();
+        assert_eq!(2, debugger.position());
+
+        debugger.rewind_to_breakpoint();
+        assert_eq!(0, debugger.position());
+    }
+
+    #[test]
+    fn test_play_pauses_on_breakpoint() {
+        let mut debugger: StepDebugger = labelled(&["push", "push", "pop", "push"]).with_breakpoints(&["pop"]);
+
+        debugger.toggle_play();
+        for _ in 0..PLAY_INTERVAL * 10 {
+            debugger.tick();
+        }
+
+        assert!(!debugger.is_playing());
+        assert_eq!("pop", label(&debugger));
+    }
+
+    #[test]
+    fn test_toggle_breakpoint_uses_current_label() {
+        let mut debugger: StepDebugger = labelled(&["event", "insert"]);
+        debugger.step_forward();
+
+        debugger.toggle_breakpoint();
+        assert_eq!(vec!["insert".to_string()], debugger.breakpoints);
+
+        debugger.toggle_breakpoint();
+        assert!(debugger.breakpoints.is_empty());
+    }
+
+    #[test]
+    fn test_hull_steps_carry_the_stack() {
+        let points: Vec<Point2d> = vec![
+            Point2d::new(0f32, 0f32),
+            Point2d::new(4f32, 0f32),
+            Point2d::new(2f32, 3f32),
+            Point2d::new(2f32, 1f32),
+        ];
+
+        let mut debugger: StepDebugger = StepDebugger::convex_hull(points);
+        debugger.initialize().unwrap();
+
+        assert!(debugger.current().unwrap().scene.notes.iter().any(|n| n.starts_with("stack")));
+    }
+}
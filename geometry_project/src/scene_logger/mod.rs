@@ -1,2 +1,3 @@
 pub mod scene_logger;
-
+pub mod scene_log_reader;
+pub mod trace_file;
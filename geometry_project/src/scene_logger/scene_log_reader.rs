@@ -0,0 +1,247 @@
+use std::fs;
+
+use crate::{
+    display::scene::Scene,
+    scene_logger::trace_file::{TraceFormat, TraceReader},
+};
+
+const FILE_MARKER: &str = "file_name = ";
+const LINE_MARKER: &str = ", line_number = ";
+const STEP_MARKER: &str = ", step = ";
+const SCENE_MARKER: &str = ", scene_data = ";
+
+//One scene written by SceneLogger::log_scene together with where it was logged from
+#[derive(Debug, Clone)]
+pub struct LoggedScene {
+    pub target: String,
+    pub file: String,
+    pub line: u32,
+    pub step: Option<String>,
+    pub scene: Scene,
+}
+
+impl LoggedScene {
+    pub fn location(&self) -> String {
+        match &self.step {
+            Some(step) => format!("{}:{} {}", self.file, self.line, step),
+            None => format!("{}:{}", self.file, self.line),
+        }
+    }
+}
+
+//All scenes logged under one target, in the order they were written
+#[derive(Debug, Clone)]
+pub struct SceneGroup {
+    pub target: String,
+    pub scenes: Vec<LoggedScene>,
+}
+
+//Undo the escaping done by the Debug formatting of a string
+fn unescape_debug(escaped: &str) -> String {
+    let mut result: String = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some('0') => result.push('\0'),
+            Some('u') => {
+                let code: String = chars.by_ref().skip_while(|c| *c == '{').take_while(|c| *c != '}').collect();
+
+                if let Some(decoded) = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                    result.push(decoded);
+                }
+            }
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+//Value of a `name: "..."` field of a Debug printed struct
+fn quoted_field(line: &str, name: &str) -> Option<String> {
+    let start: usize = line.find(&format!("{}: \"", name))? + name.len() + 3;
+    let rest: &str = &line[start..];
+
+    let mut escaped: bool = false;
+    for (i, c) in rest.char_indices() {
+        match (c, escaped) {
+            ('\\', false) => escaped = true,
+            ('"', false) => {
+                return Some(unescape_debug(&rest[..i]));
+            }
+            _ => escaped = false,
+        }
+    }
+
+    None
+}
+
+//Message written by log_scene: " file_name = {}, line_number = {}, scene_data = {}",
+//log_step adds ", step = {}" in front of the scene
+fn parse_message(target: String, message: &str) -> Option<LoggedScene> {
+    let rest: &str = &message[message.find(FILE_MARKER)? + FILE_MARKER.len()..];
+    let (file, rest) = rest.split_once(LINE_MARKER)?;
+    let (line, json) = rest.split_once(SCENE_MARKER)?;
+
+    let (line, step) = match line.split_once(STEP_MARKER) {
+        Some((line, step)) => (line, Some(step.to_string())),
+        None => (line, None),
+    };
+
+    let scene: Scene = serde_json::from_str(json.trim()).ok()?;
+
+    Some(LoggedScene {
+        target,
+        file: file.to_string(),
+        line: line.trim().parse().ok()?,
+        step,
+        scene,
+    })
+}
+
+//Lines are either LogMessage { .. } records or the bare message, anything
+//that does not hold a scene is skipped
+pub fn parse_log_line(line: &str) -> Option<LoggedScene> {
+    if !line.contains("scene_data = ") {
+        return None;
+    }
+
+    match quoted_field(line, "message") {
+        Some(message) => {
+            let target: String = quoted_field(line, "target").unwrap_or_else(|| "unknown".to_string());
+            parse_message(target, &message)
+        }
+        None => parse_message("unknown".to_string(), line),
+    }
+}
+
+pub fn parse_log(text: &str) -> Vec<LoggedScene> {
+    text.lines().filter_map(parse_log_line).collect()
+}
+
+pub fn read_scene_log(path: &str) -> Result<Vec<LoggedScene>, &'static str> {
+    let text: String = fs::read_to_string(path).map_err(|_| "Could not read scene log")?;
+
+    Ok(parse_log(&text))
+}
+
+//The traced input comes first as an "input" step, every step is logged
+//under the traced algorithm's name
+pub fn read_trace(path: &str) -> Result<Vec<LoggedScene>, &'static str> {
+    let reader = TraceReader::open(path).map_err(|_| "Could not read trace header")?;
+    let target: String = reader.header().algorithm.clone();
+
+    let mut scenes: Vec<LoggedScene> = vec![LoggedScene {
+        target: target.clone(),
+        file: path.to_string(),
+        line: 0,
+        step: Some("input".to_string()),
+        scene: reader.header().input.clone(),
+    }];
+
+    for step in reader {
+        let step = step.map_err(|_| "Could not read trace step")?;
+
+        scenes.push(LoggedScene {
+            target: target.clone(),
+            file: step.file,
+            line: step.line,
+            step: Some(step.label),
+            scene: step.scene,
+        });
+    }
+
+    Ok(scenes)
+}
+
+//Trace files are recognised by their extension, anything else is read as a log
+pub fn read_scenes(path: &str) -> Result<Vec<LoggedScene>, &'static str> {
+    match TraceFormat::from_path(path) {
+        Some(_) => read_trace(path),
+        None => read_scene_log(path),
+    }
+}
+
+//Groups keep the order their target was first seen in
+pub fn group_by_target(scenes: Vec<LoggedScene>) -> Vec<SceneGroup> {
+    let mut groups: Vec<SceneGroup> = Vec::new();
+
+    for scene in scenes {
+        match groups.iter_mut().find(|g| g.target == scene.target) {
+            Some(group) => group.scenes.push(scene),
+            None => groups.push(SceneGroup {
+                target: scene.target.clone(),
+                scenes: vec![scene],
+            }),
+        }
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = r#"LogMessage { level: Info, target: "geometry_project", file: "src\\main.rs", line: 45, module: "geometry_project", flush: false, message: "Lets start", data: "" }
+LogMessage { level: Debug, target: "Algorithms", file: "src\\scene_logger\\scene_logger.rs", line: 40, module: "geometry_project::scene_logger::scene_logger", flush: false, message: " file_name = src\\algorithms\\convex_hull.rs, line_number = 64, scene_data = {\"layers\":[{\"name\":\"default\",\"z_order\":0,\"visible\":true,\"primitives\":[{\"kind\":\"Point\",\"point\":{\"x\":1.0,\"y\":2.0},\"style\":{\"color\":16777215,\"size\":1}}]}],\"legend\":[]}", data: "" }
+LogMessage { level: Debug, target: "LineIntersection", file: "src\\scene_logger\\scene_logger.rs", line: 40, module: "geometry_project::scene_logger::scene_logger", flush: false, message: " file_name = src\\scenarios\\line_intersection_scenario.rs, line_number = 97, scene_data = {\"points\":[],\"lines\":[],\"point_colors\":[],\"line_colors\":[]}", data: "" }
+LogMessage { level: Debug, target: "Algorithms", file: "src\\scene_logger\\scene_logger.rs", line: 40, module: "geometry_project::scene_logger::scene_logger", flush: false, message: " file_name = src\\algorithms\\convex_hull.rs, line_number = 70, scene_data = {\"points\":[{\"x\":3.0,\"y\":4.0}],\"lines\":[],\"point_colors\":[],\"line_colors\":[]}", data: "" }"#;
+
+    #[test]
+    fn test_unescape_debug() {
+        assert_eq!("a \"b\" \\c\n", unescape_debug(r#"a \"b\" \\c\n"#));
+        assert_eq!("\u{e9}", unescape_debug(r#"\u{e9}"#));
+    }
+
+    #[test]
+    fn test_parse_log_message_records() {
+        let scenes: Vec<LoggedScene> = parse_log(LOG);
+
+        assert_eq!(3, scenes.len());
+        assert_eq!("Algorithms", scenes[0].target);
+        assert_eq!("src\\algorithms\\convex_hull.rs:64", scenes[0].location());
+        assert_eq!(vec![crate::entities::point2d::Point2d::new(1f32, 2f32)], scenes[0].scene.points());
+    }
+
+    #[test]
+    fn test_parse_bare_message() {
+        let line: &str = r#" file_name = src/a.rs, line_number = 3, scene_data = {"layers":[],"legend":[]}"#;
+        let scene: LoggedScene = parse_log_line(line).unwrap();
+
+        assert_eq!("unknown", scene.target);
+        assert_eq!(3, scene.line);
+        assert_eq!(None, scene.step);
+    }
+
+    #[test]
+    fn test_parse_step() {
+        let line: &str = r#" file_name = src/a.rs, line_number = 3, step = pop, scene_data = {"layers":[],"legend":[]}"#;
+        let scene: LoggedScene = parse_log_line(line).unwrap();
+
+        assert_eq!(3, scene.line);
+        assert_eq!(Some("pop".to_string()), scene.step);
+        assert_eq!("src/a.rs:3 pop", scene.location());
+    }
+
+    #[test]
+    fn test_group_by_target() {
+        let groups: Vec<SceneGroup> = group_by_target(parse_log(LOG));
+
+        assert_eq!(2, groups.len());
+        assert_eq!("Algorithms", groups[0].target);
+        assert_eq!(2, groups[0].scenes.len());
+        assert_eq!(70, groups[0].scenes[1].line);
+        assert_eq!("LineIntersection", groups[1].target);
+    }
+}
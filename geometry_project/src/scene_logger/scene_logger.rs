@@ -45,6 +45,28 @@ impl SceneLogger {
         true
     }
 
+    //Same as log_scene with the name of the algorithm step before the scene
+    pub fn log_step(&self, step: &str, scene: Scene, location: &Location) -> bool
+    {
+        if !self.enabled
+        {
+            return false;
+        }
+
+        let serialized = match serde_json::to_string(&scene)
+        {
+            Ok(val) => val,
+            Err(_) => {return false;}
+        };
+
+        log::debug!(
+            target: &self.target,
+            " file_name = {}, line_number = {}, step = {}, scene_data = {}",
+            location.file(), location.line(), step, serialized);
+
+        true
+    }
+
     #[track_caller]
     pub fn log_scene_proxy<T: ISceneProxy + Sized>(&self, to_scene: &T) -> bool {
         if !self.enabled
@@ -0,0 +1,349 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    panic::Location,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    algorithms::tracer::{TraceStep, Tracer},
+    display::scene::Scene,
+};
+
+pub const TRACE_VERSION: u32 = 1;
+
+//Binary traces start with these bytes, anything else is read as NDJSON
+const BINARY_MAGIC: &[u8; 4] = b"GTRC";
+
+//Newline delimited JSON is one record per line, the binary format is
+//MessagePack records each prefixed by their length as a little endian u32.
+//Both start with a TraceHeader followed by one TraceStep per step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    Json,
+    Binary,
+}
+
+impl TraceFormat {
+    //.ndjson / .jsonl for JSON, .gtrace for binary
+    pub fn from_path(path: &str) -> Option<TraceFormat> {
+        let extension: &str = path.rsplit_once('.')?.1;
+
+        match extension {
+            "ndjson" | "jsonl" => Some(TraceFormat::Json),
+            "gtrace" => Some(TraceFormat::Binary),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceHeader {
+    pub version: u32,
+    pub algorithm: String,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    pub input: Scene,
+}
+
+impl TraceHeader {
+    pub fn new(algorithm: &str, seed: Option<u64>, input: Scene) -> Self {
+        TraceHeader {
+            version: TRACE_VERSION,
+            algorithm: algorithm.to_string(),
+            seed,
+            input,
+        }
+    }
+}
+
+fn invalid_data<E: std::fmt::Display>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+//Streams steps to a file as the algorithm runs, nothing is kept in memory
+pub struct TraceWriter<W: Write> {
+    out: W,
+    format: TraceFormat,
+    steps: usize,
+    error: Option<io::Error>,
+}
+
+impl TraceWriter<BufWriter<File>> {
+    pub fn create(path: &str, format: TraceFormat, header: &TraceHeader) -> io::Result<Self> {
+        TraceWriter::new(BufWriter::new(File::create(path)?), format, header)
+    }
+
+    //The format follows the extension, JSON for any other extension
+    pub fn create_for_path(path: &str, header: &TraceHeader) -> io::Result<Self> {
+        TraceWriter::create(path, TraceFormat::from_path(path).unwrap_or(TraceFormat::Json), header)
+    }
+}
+
+impl<W: Write> TraceWriter<W> {
+    pub fn new(out: W, format: TraceFormat, header: &TraceHeader) -> io::Result<Self> {
+        let mut writer: TraceWriter<W> = TraceWriter {
+            out,
+            format,
+            steps: 0,
+            error: None,
+        };
+
+        if format == TraceFormat::Binary {
+            writer.out.write_all(BINARY_MAGIC)?;
+        }
+
+        writer.write_record(header)?;
+
+        Ok(writer)
+    }
+
+    fn write_record<T: Serialize>(&mut self, record: &T) -> io::Result<()> {
+        match self.format {
+            TraceFormat::Json => {
+                serde_json::to_writer(&mut self.out, record).map_err(invalid_data)?;
+                self.out.write_all(b"\n")
+            }
+            TraceFormat::Binary => {
+                let bytes: Vec<u8> = rmp_serde::to_vec_named(record).map_err(invalid_data)?;
+                let len: u32 = u32::try_from(bytes.len()).map_err(invalid_data)?;
+
+                self.out.write_all(&len.to_le_bytes())?;
+                self.out.write_all(&bytes)
+            }
+        }
+    }
+
+    pub fn write_step(&mut self, step: &TraceStep) -> io::Result<()> {
+        self.write_record(step)?;
+        self.steps += 1;
+
+        Ok(())
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    //Reports the first error hit while tracing, the tracer itself can not
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        self.out.flush()?;
+
+        Ok(self.out)
+    }
+}
+
+impl<W: Write> Tracer for TraceWriter<W> {
+    fn is_enabled(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn record(&mut self, step: &str, location: &'static Location<'static>, scene: Scene) {
+        let step: TraceStep = TraceStep {
+            label: step.to_string(),
+            file: location.file().to_string(),
+            line: location.line(),
+            scene,
+        };
+
+        if let Err(error) = self.write_step(&step) {
+            self.error = Some(error);
+        }
+    }
+}
+
+//Reads the header up front and then one step per call to next
+pub struct TraceReader<R: BufRead> {
+    input: R,
+    format: TraceFormat,
+    header: TraceHeader,
+    line: String,
+}
+
+impl TraceReader<BufReader<File>> {
+    pub fn open(path: &str) -> io::Result<Self> {
+        TraceReader::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: BufRead> TraceReader<R> {
+    pub fn new(mut input: R) -> io::Result<Self> {
+        let format: TraceFormat = match input.fill_buf()?.starts_with(BINARY_MAGIC) {
+            true => {
+                input.consume(BINARY_MAGIC.len());
+                TraceFormat::Binary
+            }
+            false => TraceFormat::Json,
+        };
+
+        let mut line: String = String::new();
+        let header: TraceHeader = match Self::read_record(&mut input, format, &mut line)? {
+            Some(h) => h,
+            None => {
+                return Err(invalid_data("Trace has no header"));
+            }
+        };
+
+        if header.version > TRACE_VERSION {
+            return Err(invalid_data(format!("Unsupported trace version {}", header.version)));
+        }
+
+        Ok(TraceReader {
+            input,
+            format,
+            header,
+            line,
+        })
+    }
+
+    pub fn header(&self) -> &TraceHeader {
+        &self.header
+    }
+
+    pub fn format(&self) -> TraceFormat {
+        self.format
+    }
+
+    //None at a clean end of the input
+    fn read_record<T: DeserializeOwned>(input: &mut R, format: TraceFormat, line: &mut String) -> io::Result<Option<T>> {
+        match format {
+            TraceFormat::Json => loop {
+                line.clear();
+
+                if input.read_line(line)? == 0 {
+                    return Ok(None);
+                }
+
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                return serde_json::from_str(line.trim()).map(Some).map_err(invalid_data);
+            },
+            TraceFormat::Binary => {
+                if input.fill_buf()?.is_empty() {
+                    return Ok(None);
+                }
+
+                let mut len: [u8; 4] = [0; 4];
+                input.read_exact(&mut len)?;
+                let len: u64 = u32::from_le_bytes(len) as u64;
+
+                //the buffer grows with what is actually read, a corrupt length
+                //can not ask for gigabytes up front
+                let mut bytes: Vec<u8> = Vec::new();
+                if (&mut *input).take(len).read_to_end(&mut bytes)? as u64 != len {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Trace record is truncated"));
+                }
+
+                rmp_serde::from_slice(&bytes).map(Some).map_err(invalid_data)
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for TraceReader<R> {
+    type Item = io::Result<TraceStep>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Self::read_record(&mut self.input, self.format, &mut self.line).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::entities::point2d::Point2d;
+
+    fn header() -> TraceHeader {
+        let mut input: Scene = Scene::new();
+        input.push_points([Point2d::new(1f32, 2f32), Point2d::new(3f32, 4f32)]);
+
+        TraceHeader::new("convex_hull", Some(42), input)
+    }
+
+    fn write_trace(format: TraceFormat, steps: usize) -> Vec<u8> {
+        let mut writer: TraceWriter<Vec<u8>> = TraceWriter::new(Vec::new(), format, &header()).unwrap();
+
+        for i in 0..steps {
+            writer.trace("push", || {
+                let mut scene: Scene = Scene::new();
+                scene.push_point(Point2d::new(i as f32, 0f32));
+                scene
+            });
+        }
+
+        writer.finish().unwrap()
+    }
+
+    fn assert_round_trip(format: TraceFormat) {
+        let bytes: Vec<u8> = write_trace(format, 3);
+        let reader: TraceReader<Cursor<Vec<u8>>> = TraceReader::new(Cursor::new(bytes)).unwrap();
+
+        assert_eq!(format, reader.format());
+        assert_eq!(&header(), reader.header());
+
+        let steps: Vec<TraceStep> = reader.map(|s| s.unwrap()).collect();
+
+        assert_eq!(3, steps.len());
+        assert_eq!("push", steps[2].label);
+        assert!(steps[2].file.ends_with("trace_file.rs"));
+        assert_eq!(vec![Point2d::new(2f32, 0f32)], steps[2].scene.points());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        assert_round_trip(TraceFormat::Json);
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        assert_round_trip(TraceFormat::Binary);
+    }
+
+    #[test]
+    fn test_json_is_one_record_per_line() {
+        let text: String = String::from_utf8(write_trace(TraceFormat::Json, 2)).unwrap();
+
+        assert_eq!(3, text.lines().count());
+        assert!(text.lines().next().unwrap().contains("\"algorithm\":\"convex_hull\""));
+    }
+
+    #[test]
+    fn test_truncated_binary_is_an_error() {
+        let mut bytes: Vec<u8> = write_trace(TraceFormat::Binary, 2);
+        bytes.truncate(bytes.len() - 3);
+
+        let results: Vec<io::Result<TraceStep>> = TraceReader::new(Cursor::new(bytes)).unwrap().collect();
+
+        assert_eq!(2, results.len());
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn test_corrupt_length_is_an_error() {
+        let mut bytes: Vec<u8> = write_trace(TraceFormat::Binary, 0);
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(&[1, 2, 3]);
+
+        let results: Vec<io::Result<TraceStep>> = TraceReader::new(Cursor::new(bytes)).unwrap().collect();
+
+        assert_eq!(1, results.len());
+        assert_eq!(io::ErrorKind::UnexpectedEof, results[0].as_ref().unwrap_err().kind());
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(Some(TraceFormat::Json), TraceFormat::from_path("hull.ndjson"));
+        assert_eq!(Some(TraceFormat::Binary), TraceFormat::from_path("sweep.gtrace"));
+        assert_eq!(None, TraceFormat::from_path("log_output.txt"));
+    }
+}
@@ -0,0 +1,310 @@
+use crate::entities::{line2d::Line2D, point2d::Point2d, rectangle2d::Rectangle2D};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+impl Axis {
+    //Levels alternate starting with x at the root
+    fn at_depth(depth: usize) -> Axis {
+        match depth % 2 {
+            0 => Axis::X,
+            _ => Axis::Y,
+        }
+    }
+
+    fn value(&self, point: Point2d) -> f32 {
+        match self {
+            Axis::X => point.x,
+            Axis::Y => point.y,
+        }
+    }
+}
+
+fn distance_squared(a: Point2d, b: Point2d) -> f32 {
+    (a.x - b.x) * (a.x - b.x) + (a.y - b.y) * (a.y - b.y)
+}
+
+//A static 2-d tree kept in one array. The median of every range is its node,
+//the points before it are not greater on the node's axis and the points after
+//it not smaller, so the tree is balanced and needs no pointers
+#[derive(Debug, Clone)]
+pub struct KdTree {
+    points: Vec<Point2d>,
+}
+
+fn build(points: &mut [Point2d], depth: usize) {
+    if points.len() <= 1 {
+        return;
+    }
+
+    let axis: Axis = Axis::at_depth(depth);
+    let mid: usize = points.len() / 2;
+    points.select_nth_unstable_by(mid, |a, b| axis.value(*a).total_cmp(&axis.value(*b)));
+
+    let (before, after) = points.split_at_mut(mid);
+    build(before, depth + 1);
+    build(&mut after[1..], depth + 1);
+}
+
+//Keeps the k closest candidates sorted by distance, equal distances in the
+//order they were found
+fn keep_nearest(best: &mut Vec<(f32, Point2d)>, k: usize, candidate: (f32, Point2d)) {
+    let at: usize = best.partition_point(|(d, _)| *d <= candidate.0);
+
+    if at < k {
+        best.insert(at, candidate);
+        best.truncate(k);
+    }
+}
+
+fn nearest_in(points: &[Point2d], depth: usize, query: Point2d, k: usize, best: &mut Vec<(f32, Point2d)>) {
+    if points.is_empty() {
+        return;
+    }
+
+    let mid: usize = points.len() / 2;
+    let node: Point2d = points[mid];
+    let axis: Axis = Axis::at_depth(depth);
+    keep_nearest(best, k, (distance_squared(node, query), node));
+
+    let offset: f32 = axis.value(query) - axis.value(node);
+    let (near, far) = match offset < 0f32 {
+        true => (&points[..mid], &points[mid + 1..]),
+        false => (&points[mid + 1..], &points[..mid]),
+    };
+
+    nearest_in(near, depth + 1, query, k, best);
+
+    //the far side can only hold something closer than the splitting line
+    if best.len() < k || offset * offset <= best[best.len() - 1].0 {
+        nearest_in(far, depth + 1, query, k, best);
+    }
+}
+
+//Visits the nodes of every range that may hold a point of the window
+fn visit_window<F>(points: &[Point2d], depth: usize, min: Point2d, max: Point2d, visit: &mut F)
+where
+    F: FnMut(Point2d),
+{
+    if points.is_empty() {
+        return;
+    }
+
+    let mid: usize = points.len() / 2;
+    let node: Point2d = points[mid];
+    let axis: Axis = Axis::at_depth(depth);
+    visit(node);
+
+    if axis.value(min) <= axis.value(node) {
+        visit_window(&points[..mid], depth + 1, min, max, visit);
+    }
+
+    if axis.value(max) >= axis.value(node) {
+        visit_window(&points[mid + 1..], depth + 1, min, max, visit);
+    }
+}
+
+fn splits_in(points: &[Point2d], depth: usize, cell: Rectangle2D, out: &mut Vec<(Axis, Line2D)>) {
+    if points.is_empty() {
+        return;
+    }
+
+    let mid: usize = points.len() / 2;
+    let node: Point2d = points[mid];
+    let axis: Axis = Axis::at_depth(depth);
+
+    let (line, before, after) = match axis {
+        Axis::X => (
+            Line2D::new_flat(node.x, cell.min.y, node.x, cell.max.y),
+            Rectangle2D::new(cell.min, Point2d::new(node.x, cell.max.y)),
+            Rectangle2D::new(Point2d::new(node.x, cell.min.y), cell.max),
+        ),
+        Axis::Y => (
+            Line2D::new_flat(cell.min.x, node.y, cell.max.x, node.y),
+            Rectangle2D::new(cell.min, Point2d::new(cell.max.x, node.y)),
+            Rectangle2D::new(Point2d::new(cell.min.x, node.y), cell.max),
+        ),
+    };
+
+    out.push((axis, line));
+    splits_in(&points[..mid], depth + 1, before, out);
+    splits_in(&points[mid + 1..], depth + 1, after, out);
+}
+
+impl KdTree {
+    //Points that are not finite can not be ordered and are left out
+    pub fn new(mut points: Vec<Point2d>) -> Self {
+        points.retain(|p| p.is_finite());
+        build(&mut points, 0);
+
+        KdTree { points }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    //In tree order, not the order they were given in
+    pub fn points(&self) -> &[Point2d] {
+        &self.points
+    }
+
+    pub fn bounds(&self) -> Option<Rectangle2D> {
+        Rectangle2D::from_points(self.points.iter().copied())
+    }
+
+    pub fn nearest(&self, query: Point2d) -> Option<Point2d> {
+        self.k_nearest(query, 1).first().copied()
+    }
+
+    //Closest first, fewer than k when the tree is smaller
+    pub fn k_nearest(&self, query: Point2d, k: usize) -> Vec<Point2d> {
+        let mut best: Vec<(f32, Point2d)> = Vec::with_capacity(k + 1);
+
+        if k > 0 {
+            nearest_in(&self.points, 0, query, k, &mut best);
+        }
+
+        best.into_iter().map(|(_, p)| p).collect()
+    }
+
+    //Points at most radius away, in no particular order
+    pub fn within_radius(&self, center: Point2d, radius: f32) -> Vec<Point2d> {
+        let mut found: Vec<Point2d> = Vec::new();
+        let min: Point2d = Point2d::new(center.x - radius, center.y - radius);
+        let max: Point2d = Point2d::new(center.x + radius, center.y + radius);
+
+        visit_window(&self.points, 0, min, max, &mut |p| {
+            if distance_squared(p, center) <= radius * radius {
+                found.push(p);
+            }
+        });
+
+        found
+    }
+
+    //Points inside the rectangle or on its border, in no particular order
+    pub fn range(&self, rect: Rectangle2D) -> Vec<Point2d> {
+        let mut found: Vec<Point2d> = Vec::new();
+
+        visit_window(&self.points, 0, rect.min, rect.max, &mut |p| {
+            if rect.contains_closed(&p, 0f32) {
+                found.push(p);
+            }
+        });
+
+        found
+    }
+
+    //The two closest points, n nearest neighbour queries instead of n^2 checks.
+    //Duplicates are a pair at distance 0
+    pub fn closest_pair(&self) -> Option<(Point2d, Point2d)> {
+        self.points
+            .iter()
+            .filter_map(|p| self.k_nearest(*p, 2).get(1).map(|q| (*p, *q)))
+            .min_by(|a, b| distance_squared(a.0, a.1).total_cmp(&distance_squared(b.0, b.1)))
+    }
+
+    //The splitting line of every node cut to the cell it splits, the root
+    //spans the whole of bounds
+    pub fn splits(&self, bounds: Rectangle2D) -> Vec<(Axis, Line2D)> {
+        let mut out: Vec<(Axis, Line2D)> = Vec::with_capacity(self.points.len());
+        splits_in(&self.points, 0, bounds, &mut out);
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Scattered but repeatable, with a few duplicates
+    fn scattered(count: usize) -> Vec<Point2d> {
+        (0..count)
+            .map(|i| Point2d::new(((i * 37) % 101) as f32, ((i * 53) % 97) as f32 * 0.5f32))
+            .collect()
+    }
+
+    fn sorted(mut points: Vec<Point2d>) -> Vec<Point2d> {
+        points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        points
+    }
+
+    #[test]
+    fn test_k_nearest_matches_brute_force() {
+        let points: Vec<Point2d> = scattered(200);
+        let tree: KdTree = KdTree::new(points.clone());
+        let query: Point2d = Point2d::new(40.5f32, 20.25f32);
+
+        let mut by_distance: Vec<Point2d> = points.clone();
+        by_distance.sort_by(|a, b| distance_squared(*a, query).total_cmp(&distance_squared(*b, query)));
+
+        let nearest: Vec<Point2d> = tree.k_nearest(query, 5);
+        let distances = |points: &[Point2d]| -> Vec<f32> { points.iter().map(|p| distance_squared(*p, query)).collect() };
+
+        assert_eq!(distances(&by_distance[..5]), distances(&nearest));
+        assert_eq!(Some(nearest[0]), tree.nearest(query));
+        assert_eq!(200, tree.k_nearest(query, 500).len());
+        assert!(tree.k_nearest(query, 0).is_empty());
+    }
+
+    #[test]
+    fn test_radius_and_range_match_brute_force() {
+        let points: Vec<Point2d> = scattered(300);
+        let tree: KdTree = KdTree::new(points.clone());
+
+        let center: Point2d = Point2d::new(50f32, 25f32);
+        let expected: Vec<Point2d> = points.iter().copied().filter(|p| distance_squared(*p, center) <= 100f32).collect();
+        assert_eq!(sorted(expected), sorted(tree.within_radius(center, 10f32)));
+
+        let rect: Rectangle2D = Rectangle2D::new(Point2d::new(10f32, 5f32), Point2d::new(30f32, 20f32));
+        let expected: Vec<Point2d> = points.iter().copied().filter(|p| rect.contains_closed(p, 0f32)).collect();
+        assert!(!expected.is_empty());
+        assert_eq!(sorted(expected), sorted(tree.range(rect)));
+    }
+
+    #[test]
+    fn test_closest_pair() {
+        let points: Vec<Point2d> = vec![
+            Point2d::new(0f32, 0f32),
+            Point2d::new(10f32, 10f32),
+            Point2d::new(5f32, 1f32),
+            Point2d::new(10.5f32, 10f32),
+            Point2d::new(-4f32, 3f32),
+        ];
+
+        let (a, b) = KdTree::new(points).closest_pair().unwrap();
+        assert_eq!(0.25f32, distance_squared(a, b));
+
+        assert!(KdTree::new(vec![Point2d::new(1f32, 1f32)]).closest_pair().is_none());
+    }
+
+    #[test]
+    fn test_structure() {
+        let tree: KdTree = KdTree::new(vec![
+            Point2d::new(2f32, 3f32),
+            Point2d::new(f32::NAN, 0f32),
+            Point2d::new(5f32, 4f32),
+            Point2d::new(9f32, 6f32),
+        ]);
+
+        assert_eq!(3, tree.len());
+        assert_eq!(None, KdTree::new(Vec::new()).nearest(Point2d::origin()));
+
+        //the root splits on x at the median, its one child on y
+        let bounds: Rectangle2D = tree.bounds().unwrap();
+        let splits: Vec<(Axis, Line2D)> = tree.splits(bounds);
+        assert_eq!(3, splits.len());
+        assert_eq!((Axis::X, Line2D::new_flat(5f32, 3f32, 5f32, 6f32)), splits[0]);
+        assert_eq!(Axis::Y, splits[1].0);
+        assert_eq!(5f32, splits[1].1.end.x);
+    }
+}
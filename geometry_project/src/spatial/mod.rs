@@ -0,0 +1 @@
+pub mod kd_tree;
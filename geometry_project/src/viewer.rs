@@ -0,0 +1,242 @@
+use ctrlc::set_handler;
+use data_structures::vec2d::Vec2D;
+use geometry_project::{
+    algorithms::random_geometry::Random2D,
+    display::{
+        camera::Camera,
+        input::InputFrame,
+        minifb_input::MinifbInput,
+        rgb::RGB,
+        scenario::{describe_bindings, KeyBinding, Scenario, ScenarioConfig},
+        scene::Scene,
+        scene_diff::SceneDiff,
+        snapshot::{self, SNAPSHOT_KEY_BINDINGS},
+        view_controller::{ViewController, VIEW_KEY_BINDINGS},
+    },
+    entities::rectangle2d::Rectangle2D,
+    formats::scenario_serializer,
+    scenarios::{
+        registry, replay_scenario::ReplayScenario, scenario_menu::ScenarioMenu, scene_scenario::SceneScenario,
+    },
+};
+use log::{log, trace, Level};
+use logging::flush;
+use logging::logger::logger::LoggingManager;
+use minifb::{Key, Window, WindowOptions};
+use std::time::Duration;
+
+use crate::{
+    cli::{self, Command, Options},
+    exit_with,
+};
+
+pub const WINDOW_TITLE: &str = "Geometry Renderer - Esc to exit";
+
+pub const LOG_FILE: &str = cli::DEFAULT_LOG_FILE;
+pub const DIFF_EPSILON: f32 = 0.0001;
+
+//Runs, replays and diffs open a window, everything else is done by main
+pub fn open(options: &Options) {
+    let mut scenario: Box<dyn Scenario> = match &options.command {
+        //Has to read the log before the logger truncates it
+        Command::Replay(path) => Box::new(ReplayScenario::from_file(path).unwrap_or_else(|e| exit_with(e, path))),
+        Command::Diff(before, after) => {
+            Box::new(diff_scenario(before, after).unwrap_or_else(|e| exit_with(e, before)))
+        }
+        _ => {
+            let index: usize = registry::index_of(&options.scenario).unwrap_or_else(|| {
+                eprintln!("Unknown scenario {}, available:\n{}", options.scenario, registry::describe());
+                std::process::exit(2);
+            });
+
+            //Always seeded so a run can be repeated with --seed
+            let mut config: ScenarioConfig = options.config.clone();
+            let seed: u64 = config.seed.unwrap_or_else(rand::random);
+            config.seed = Some(seed);
+            Random2D::seed(seed);
+            println!("{} with seed {}", options.scenario, seed);
+
+            //Tab and F keys switch to the other registered scenarios
+            let input: Option<String> = config.input.clone();
+            let mut menu: ScenarioMenu = ScenarioMenu::new(&registry::SCENARIOS, index, config);
+            if let Err(e) = menu.start() {
+                eprintln!("{}: {}", options.scenario, e);
+                std::process::exit(1);
+            }
+
+            if let Some(path) = input {
+                report_input_warnings(&path);
+            }
+
+            Box::new(menu)
+        }
+    };
+
+    LoggingManager::init(LOG_FILE).expect("Failed to initialize Logger");
+
+    set_handler(handle_sigint).expect("Error setting ctrlc hook");
+
+    let (width, height) = options.window;
+
+    let window = Window::new(
+        WINDOW_TITLE,
+        width,
+        height,
+        WindowOptions::default(),
+    )
+    .unwrap_or_else(|e| {
+        panic!("{}", e);
+    });
+
+    //The same margin as the old fixed 25..75 points in a 0..100 view
+    let bounds: Rectangle2D = options.config.bounds;
+    let home: Rectangle2D = bounds.expand(bounds.width().max(bounds.height()) / 2f32);
+
+    log!(Level::Info, "Lets start");
+
+    window_loop(window, options.window, home, scenario.as_mut());
+}
+
+fn diff_scenario(before_path: &str, after_path: &str) -> Result<SceneScenario, &'static str> {
+    let before: Scene = Scene::load(before_path)?;
+    let after: Scene = Scene::load(after_path)?;
+    let diff: SceneDiff = before.diff(&after, DIFF_EPSILON);
+
+    println!("{}", diff.summary());
+
+    Ok(SceneScenario::new(
+        diff.to_scene(),
+        vec![format!("{} -> {}", before_path, after_path), diff.summary()],
+    ))
+}
+
+//What an imported drawing held that the scenario could not use. Scenarios
+//only keep the entities, so the file is read again for these
+fn report_input_warnings(path: &str) {
+    if let Ok(file) = scenario_serializer::load_scenario(path) {
+        for warning in &file.warnings {
+            eprintln!("{} {}", path, warning);
+        }
+    }
+}
+
+fn handle_sigint() {
+    eprintln!("Caught Ctrl^C flushing log");
+    LoggingManager::static_flush();
+    std::process::exit(1);
+}
+
+fn blank_screen(width: usize, height: usize) -> Vec2D<RGB> {
+    Vec2D::new_from_flatpack(
+        vec![RGB::black(); width * height],
+        width,
+        height,
+    )
+    .expect("width height unexpected")
+}
+
+fn window_loop(mut window: Window, size: (usize, usize), home: Rectangle2D, scenario: &mut dyn Scenario) {
+    let (width, height) = size;
+    let mut buffer: Vec2D<RGB> = blank_screen(width, height);
+    let mut camera: Camera = Camera::new(home.width(), home.height());
+    camera.view_port = home;
+    let mut view: ViewController = ViewController::new(camera.view_port, width, height);
+    let mut title: String = WINDOW_TITLE.to_string();
+    let mut frame: u64 = 0;
+    let mut input: MinifbInput = MinifbInput::new();
+
+    match scenario.initialize() {
+        Ok(_) => {}
+        Err(e_msg) => {
+            panic!("{}", e_msg);
+        }
+    };
+
+    println!("Ready to render");
+
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        std::thread::sleep(Duration::from_millis(5));
+
+        trace!("next frame");
+        let mut input_frame: InputFrame = input.poll(&window);
+        input_frame.locate(&camera, width, height);
+
+        scenario.handle_input(&input_frame);
+        let view_changed: bool = view.handle_input(&input_frame, &mut camera);
+
+        let scenario_changed: bool = scenario.redraw();
+        if scenario_changed {
+            trace!("Redraw scenario");
+            camera.clear();
+            scenario.process(&mut camera);
+        }
+
+        if scenario_changed || view_changed {
+            buffer = blank_screen(width, height);
+            camera.set_hud(hud_lines(frame, &camera));
+            camera.set_help(help_lines(scenario));
+            camera.draw(&mut buffer);
+        }
+
+        //after drawing so a screenshot has what is about to be shown. Reports
+        //go to stderr with the other viewer messages
+        for report in snapshot::handle_snapshot_keys(&input_frame, &buffer, &camera, scenario) {
+            eprintln!("{}", report);
+        }
+
+        frame += 1;
+
+        let next_title: String = view.title(WINDOW_TITLE);
+        if next_title != title {
+            window.set_title(&next_title);
+            title = next_title;
+        }
+
+        buffer_to_window(&mut window, buffer.clone(), width, height);
+    }
+
+    flush!("end");
+}
+
+fn hud_lines(frame: u64, camera: &Camera) -> Vec<String> {
+    let view = camera.view_port;
+
+    let mut lines: Vec<String> = vec![
+        format!("frame {}", frame),
+        format!(
+            "view ({:.1}, {:.1}) - ({:.1}, {:.1})",
+            view.min.x, view.min.y, view.max.x, view.max.y
+        ),
+    ];
+
+    //number keys toggle the layers in this order
+    for (i, name) in camera.layer_names().iter().enumerate().take(9) {
+        let state: &str = match camera.is_layer_hidden(name) {
+            true => "hidden",
+            false => "shown",
+        };
+
+        lines.push(format!("{} {} ({})", i + 1, name, state));
+    }
+
+    lines
+}
+
+fn help_lines(scenario: &dyn Scenario) -> Vec<String> {
+    let mut bindings: Vec<KeyBinding> = VIEW_KEY_BINDINGS.to_vec();
+    bindings.extend(SNAPSHOT_KEY_BINDINGS);
+    bindings.extend(scenario.key_bindings());
+
+    let mut lines: Vec<String> = vec!["Keys".to_string(), String::new()];
+    lines.extend(describe_bindings(&bindings));
+
+    lines
+}
+
+fn buffer_to_window(window: &mut Window, buffer2d: Vec2D<RGB>, width: usize, height: usize) {
+    let buffer: Vec<u32> = buffer2d.into_iter().map(|r| r.0).collect();
+
+    if let Err(e) = window.update_with_buffer(buffer.as_slice(), width, height) {
+        eprintln!("Window update failed: {}", e);
+    }
+}